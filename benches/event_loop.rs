@@ -0,0 +1,126 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Benchmarks that run against a real event loop.
+//!
+//! These need a display to run. The event loop runs on the main thread, while the benchmarks run
+//! on a separate thread and measure how long it takes for the event loop to respond.
+
+use async_winit::event_loop::EventLoopBuilder;
+use async_winit::window::Window;
+use async_winit::{ThreadSafe, Timer};
+
+use criterion::{BenchmarkId, Criterion};
+use futures_lite::future;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use std::thread;
+use std::time::Duration;
+
+/// The number of windows to open.
+const WINDOWS: usize = 32;
+
+/// Measure the round trip time of an operation that runs on the event loop thread.
+fn ops(c: &mut Criterion, windows: &[Window<ThreadSafe>]) {
+    let window = &windows[0];
+    let mut group = c.benchmark_group("ops");
+
+    group.bench_function("inner_size", |b| {
+        b.iter(|| future::block_on(window.inner_size()));
+    });
+
+    group.bench_function("title", |b| {
+        b.iter(|| future::block_on(window.title()));
+    });
+
+    group.finish();
+}
+
+/// Measure how long it takes for the event loop to fire a timer.
+fn timers(c: &mut Criterion, _windows: &[Window<ThreadSafe>]) {
+    let mut group = c.benchmark_group("event_loop_timers");
+
+    group.bench_function("fire", |b| {
+        b.iter(|| future::block_on(Timer::<ThreadSafe>::after(Duration::ZERO)));
+    });
+
+    group.finish();
+}
+
+/// Measure how long it takes for events to be posted to windows, with many windows open.
+fn post_event(c: &mut Criterion, windows: &[Window<ThreadSafe>]) {
+    let mut group = c.benchmark_group("post_event");
+
+    for count in [1, WINDOWS] {
+        let windows = &windows[..count];
+
+        group.bench_with_input(BenchmarkId::new("redraw", count), &windows, |b, windows| {
+            b.iter(|| {
+                let mut waiters = windows
+                    .iter()
+                    .map(|window| window.redraw_requested().wait())
+                    .collect::<Vec<_>>();
+                windows.iter().for_each(Window::request_redraw);
+
+                future::block_on(future::poll_fn(|cx| {
+                    waiters.retain_mut(|waiter| Pin::new(waiter).poll(cx).is_pending());
+
+                    if waiters.is_empty() {
+                        Poll::Ready(())
+                    } else {
+                        Poll::Pending
+                    }
+                }));
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn main() {
+    let event_loop = EventLoopBuilder::new().build::<ThreadSafe>();
+    let target = event_loop.window_target().clone();
+
+    event_loop.block_on(async move {
+        // Open the windows ahead of time.
+        let mut windows = Vec::with_capacity(WINDOWS);
+        for _ in 0..WINDOWS {
+            windows.push(Window::<ThreadSafe>::new().await.unwrap());
+        }
+
+        // Run the benchmarks on another thread.
+        let (done_tx, done_rx) = async_channel::bounded(1);
+        thread::spawn(move || {
+            let mut criterion = Criterion::default().configure_from_args();
+
+            for bench in [ops, timers, post_event] {
+                bench(&mut criterion, &windows);
+            }
+
+            criterion.final_summary();
+            future::block_on(done_tx.send(windows)).ok();
+        });
+
+        // Keep the windows alive until the benchmarks are done.
+        let _windows = done_rx.recv().await;
+        target.exit().await
+    });
+}
@@ -0,0 +1,99 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Benchmarks for the parts of the reactor that don't need a running event loop.
+
+use async_winit::bench;
+use async_winit::ThreadUnsafe;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use futures_lite::future;
+
+use std::sync::Arc;
+use std::task::{Wake, Waker};
+use std::time::{Duration, Instant};
+
+/// A waker that does nothing.
+struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+fn dispatch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dispatch");
+
+    for listeners in [0, 1, 16] {
+        let handler = bench::handler::<u32, ThreadUnsafe>();
+        for _ in 0..listeners {
+            handler.wait_direct(|event| {
+                black_box(event);
+                false
+            });
+        }
+
+        group.bench_with_input(
+            BenchmarkId::new("direct", listeners),
+            &handler,
+            |b, handler| {
+                b.iter(|| future::block_on(bench::dispatch(handler, &mut black_box(1))));
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn timers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("timers");
+    let waker = Waker::from(Arc::new(Noop));
+
+    group.bench_function("insert_remove", |b| {
+        let deadline = Instant::now() + Duration::from_secs(60 * 60);
+        b.iter(|| {
+            let id = bench::insert_timer::<ThreadUnsafe>(deadline, &waker);
+            bench::remove_timer::<ThreadUnsafe>(black_box(id));
+        });
+    });
+
+    group.bench_function("insert_fire", |b| {
+        let mut wakers = Vec::new();
+        b.iter(|| {
+            bench::insert_timer::<ThreadUnsafe>(Instant::now(), &waker);
+            bench::process_timers::<ThreadUnsafe>(&mut wakers);
+            wakers.drain(..).for_each(Waker::wake);
+        });
+    });
+
+    group.bench_function("insert_fire_many", |b| {
+        let mut wakers = Vec::new();
+        b.iter(|| {
+            let now = Instant::now();
+            for _ in 0..128 {
+                bench::insert_timer::<ThreadUnsafe>(now, &waker);
+            }
+            bench::process_timers::<ThreadUnsafe>(&mut wakers);
+            wakers.drain(..).for_each(Waker::wake);
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, dispatch, timers);
+criterion_main!(benches);
@@ -0,0 +1,57 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! An example using the built-in executor to run several tasks at once.
+
+use std::time::Duration;
+
+use async_winit::event_loop::{EventLoop, EventLoopBuilder};
+use async_winit::{ThreadUnsafe, Timer};
+
+fn main() {
+    main2(EventLoopBuilder::new().build())
+}
+
+fn main2(evl: EventLoop<ThreadUnsafe>) {
+    let target = evl.window_target().clone();
+    evl.block_on(async move {
+        // Print a message every half second in the background.
+        target
+            .spawn(async {
+                let mut count = 0;
+                loop {
+                    Timer::<ThreadUnsafe>::after(Duration::from_millis(500)).await;
+                    count += 1;
+                    println!("Tick {}", count);
+                }
+            })
+            .detach();
+
+        // Compute a value in another task.
+        let task = target.spawn(async {
+            Timer::<ThreadUnsafe>::after(Duration::from_secs(1)).await;
+            42
+        });
+
+        println!("The answer is {}", task.await);
+
+        // Wait a little longer, then exit.
+        Timer::<ThreadUnsafe>::after(Duration::from_secs(2)).await;
+        target.exit().await
+    });
+}
@@ -25,7 +25,6 @@ use async_winit::window::Window;
 use async_winit::{DefaultThreadSafety, Timer};
 
 use futures_lite::prelude::*;
-use softbuffer::GraphicsContext;
 
 fn main() {
     main2(EventLoopBuilder::new().build())
@@ -59,7 +58,7 @@ fn main2(evl: EventLoop) {
         // Drawing.
         let draw = {
             let window = window.clone();
-            let mut sb = None;
+            let mut surface = window.softbuffer();
             let mut buf = vec![];
 
             async move {
@@ -67,18 +66,7 @@ fn main2(evl: EventLoop) {
 
                 loop {
                     let _guard = waiter.hold().await;
-                    let inner_size = window.inner_size().await;
-
-                    // Get the softbuffer.
-                    let graphics = match &mut sb {
-                        Some(graphics) => graphics,
-                        sb @ None => {
-                            let graphics =
-                                unsafe { GraphicsContext::new(&window, &window) }.unwrap();
-
-                            sb.insert(graphics)
-                        }
-                    };
+                    let inner_size = surface.size().await;
 
                     // Draw.
                     let pixel = 0xAA11AA11;
@@ -86,7 +74,7 @@ fn main2(evl: EventLoop) {
                         inner_size.width as usize * inner_size.height as usize,
                         pixel,
                     );
-                    graphics.set_buffer(&buf, inner_size.width as u16, inner_size.height as u16);
+                    surface.present(&buf).await.unwrap();
                 }
             }
         };
@@ -0,0 +1,137 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+//! A high-level abstraction over the application lifecycle.
+//!
+//! On some platforms (most notably Android), windows can only be created after the application has
+//! been resumed, and must be dropped once the application has been suspended. The [`App`] trait
+//! and [`run_app`] take care of this choreography.
+//!
+//! ```no_run
+//! use async_winit::app::{run_app, App, AppFuture};
+//! use async_winit::event_loop::{EventLoop, EventLoopWindowTarget};
+//! use async_winit::window::Window;
+//! use async_winit::ThreadUnsafe;
+//!
+//! #[derive(Default)]
+//! struct MyApp {
+//!     window: Option<Window<ThreadUnsafe>>,
+//! }
+//!
+//! impl App<ThreadUnsafe> for MyApp {
+//!     fn resumed<'a>(&'a mut self, _target: &'a EventLoopWindowTarget<ThreadUnsafe>) -> AppFuture<'a> {
+//!         Box::pin(async move {
+//!             self.window = Some(Window::<ThreadUnsafe>::new().await.unwrap());
+//!         })
+//!     }
+//!
+//!     fn suspended(&mut self) -> AppFuture<'_> {
+//!         self.window = None;
+//!         Box::pin(async {})
+//!     }
+//!
+//!     fn window_events<'a>(&'a mut self, _target: &'a EventLoopWindowTarget<ThreadUnsafe>) -> AppFuture<'a> {
+//!         Box::pin(async move {
+//!             let window = self.window.as_ref().unwrap();
+//!             window.close_requested().wait().await;
+//!         })
+//!     }
+//! }
+//!
+//! run_app(EventLoop::<ThreadUnsafe>::new(), MyApp::default());
+//! ```
+
+use crate::event_loop::{EventLoop, EventLoopWindowTarget};
+use crate::sync::ThreadSafety;
+use crate::DefaultThreadSafety;
+
+use std::future::Future;
+use std::pin::Pin;
+
+use futures_lite::prelude::*;
+
+/// The future type returned by the methods of [`App`].
+pub type AppFuture<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+/// An application that can be driven by [`run_app`].
+pub trait App<TS: ThreadSafety = DefaultThreadSafety> {
+    /// The application has been resumed.
+    ///
+    /// This is where windows and graphics resources should be created. This is called once at
+    /// startup, and then again every time the application is resumed after being suspended.
+    fn resumed<'a>(&'a mut self, target: &'a EventLoopWindowTarget<TS>) -> AppFuture<'a>;
+
+    /// The application has been suspended.
+    ///
+    /// This is where windows and graphics resources should be dropped. The event loop does not
+    /// finish handling the `Suspended` event until this future completes.
+    fn suspended(&mut self) -> AppFuture<'_> {
+        Box::pin(async {})
+    }
+
+    /// Handle the events for the application's windows.
+    ///
+    /// This future runs while the application is resumed, and is cancelled when the application is
+    /// suspended. Once it completes, the application exits.
+    fn window_events<'a>(&'a mut self, target: &'a EventLoopWindowTarget<TS>) -> AppFuture<'a>;
+}
+
+/// Run an [`App`] on the provided event loop.
+pub fn run_app<TS, A>(event_loop: EventLoop<TS>, app: A) -> !
+where
+    TS: ThreadSafety + 'static,
+    A: App<TS> + 'static,
+{
+    let target = event_loop.window_target().clone();
+
+    event_loop.block_on(async move {
+        let mut app = app;
+
+        // Listen for both events for the entire lifetime of the application, so none are missed.
+        let mut resumed = target.resumed().wait();
+        let mut suspended = target.suspended().wait();
+
+        loop {
+            // Wait for the application to be resumed.
+            resumed.next().await;
+            app.resumed(&target).await;
+
+            // Run the application until it is suspended.
+            let guard = {
+                let run = async {
+                    app.window_events(&target).await;
+                    None
+                };
+                let hold = async { Some(suspended.hold().await) };
+
+                run.or(hold).await
+            };
+
+            match guard {
+                Some(guard) => {
+                    // Let the application clean up before the suspension is finished.
+                    app.suspended().await;
+                    drop(guard);
+                }
+
+                None => break,
+            }
+        }
+
+        target.exit().await
+    })
+}
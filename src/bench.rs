@@ -0,0 +1,60 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Hooks into the reactor for the benchmarks.
+//!
+//! These let the benchmarks exercise parts of the runtime that are not reachable through the public
+//! API without a running event loop. This module is not part of the public API and may change at
+//! any time.
+
+use crate::handler::{Event, Handler};
+use crate::reactor::Reactor;
+use crate::sync::ThreadSafety;
+
+use std::task::Waker;
+use std::time::Duration;
+
+use instant::Instant;
+
+/// Create a new, empty handler.
+pub fn handler<T: Event, TS: ThreadSafety>() -> Handler<T, TS> {
+    Handler::new()
+}
+
+/// Dispatch an event to a handler, as the reactor would.
+pub async fn dispatch<T: Event, TS: ThreadSafety>(
+    handler: &Handler<T, TS>,
+    event: &mut T::Unique<'_>,
+) {
+    handler.run_with(event).await;
+}
+
+/// Insert a timer into the global reactor.
+pub fn insert_timer<TS: ThreadSafety>(deadline: Instant, waker: &Waker) -> usize {
+    Reactor::<TS>::get().insert_timer(deadline, Duration::ZERO, waker)
+}
+
+/// Remove a timer from the global reactor.
+pub fn remove_timer<TS: ThreadSafety>(id: usize) {
+    Reactor::<TS>::get().remove_timer(id);
+}
+
+/// Fire all of the expired timers in the global reactor.
+pub fn process_timers<TS: ThreadSafety>(wakers: &mut Vec<Waker>) -> Option<Instant> {
+    Reactor::<TS>::get().process_timers(wakers)
+}
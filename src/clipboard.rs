@@ -0,0 +1,204 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Access to the system clipboard, through `arboard`.
+//!
+//! Some platforms only allow the clipboard to be used from the main thread, and on X11 the
+//! clipboard contents are only served for as long as the program holds onto them. To handle both,
+//! clipboard operations always run on the event loop thread, which owns the clipboard for the
+//! lifetime of the program.
+//!
+//! ```no_run
+//! use async_winit::event_loop::EventLoop;
+//! use async_winit::ThreadUnsafe;
+//!
+//! let event_loop = EventLoop::<ThreadUnsafe>::new();
+//! let target = event_loop.window_target().clone();
+//!
+//! event_loop.block_on(async move {
+//!     let clipboard = target.clipboard();
+//!     clipboard.set_text("Hello, world!").await.unwrap();
+//!     assert_eq!(clipboard.get_text().await.unwrap(), "Hello, world!");
+//! #   futures_lite::future::pending().await
+//! });
+//! ```
+
+use crate::oneoff::{oneoff, Complete};
+use crate::reactor::{EventLoopOp, Reactor};
+use crate::sync::ThreadSafety;
+use crate::DefaultThreadSafety;
+
+use std::cell::RefCell;
+use std::fmt;
+
+#[doc(inline)]
+pub use arboard::{Error, ImageData};
+
+thread_local! {
+    /// The clipboard, created the first time it is used on the event loop thread.
+    static CLIPBOARD: RefCell<Option<arboard::Clipboard>> = const { RefCell::new(None) };
+}
+
+/// A handle to the system clipboard.
+///
+/// This is created by [`EventLoopWindowTarget::clipboard`]. Operations from other threads fail
+/// with [`Error::Unknown`] once the event loop has exited.
+///
+/// [`EventLoopWindowTarget::clipboard`]: crate::event_loop::EventLoopWindowTarget::clipboard
+pub struct Clipboard<TS: ThreadSafety = DefaultThreadSafety> {
+    /// The reactor that runs clipboard operations.
+    reactor: TS::Rc<Reactor<TS>>,
+}
+
+impl<TS: ThreadSafety> Clone for Clipboard<TS> {
+    fn clone(&self) -> Self {
+        Self {
+            reactor: self.reactor.clone(),
+        }
+    }
+}
+
+impl<TS: ThreadSafety> fmt::Debug for Clipboard<TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Clipboard { .. }")
+    }
+}
+
+impl<TS: ThreadSafety> Clipboard<TS> {
+    pub(crate) fn new(reactor: TS::Rc<Reactor<TS>>) -> Self {
+        Self { reactor }
+    }
+
+    /// Get the text currently on the clipboard.
+    pub async fn get_text(&self) -> Result<String, Error> {
+        if self.reactor.on_event_loop_thread() {
+            return with_clipboard(|clipboard| clipboard.get_text());
+        }
+
+        let (tx, rx) = oneoff();
+        self.run(ClipboardOp::GetText(tx)).await?;
+        rx.recv().await
+    }
+
+    /// Put text onto the clipboard.
+    pub async fn set_text(&self, text: impl Into<String>) -> Result<(), Error> {
+        let text = text.into();
+        if self.reactor.on_event_loop_thread() {
+            return with_clipboard(|clipboard| clipboard.set_text(text));
+        }
+
+        let (tx, rx) = oneoff();
+        self.run(ClipboardOp::SetText(text, tx)).await?;
+        rx.recv().await
+    }
+
+    /// Get the image currently on the clipboard.
+    ///
+    /// The image is returned as RGBA pixels.
+    pub async fn get_image(&self) -> Result<ImageData<'static>, Error> {
+        if self.reactor.on_event_loop_thread() {
+            return with_clipboard(|clipboard| clipboard.get_image());
+        }
+
+        let (tx, rx) = oneoff();
+        self.run(ClipboardOp::GetImage(tx)).await?;
+        rx.recv().await
+    }
+
+    /// Put an image onto the clipboard.
+    ///
+    /// The image should consist of RGBA pixels.
+    pub async fn set_image(&self, image: ImageData<'_>) -> Result<(), Error> {
+        let image = image.to_owned_img();
+        if self.reactor.on_event_loop_thread() {
+            return with_clipboard(|clipboard| clipboard.set_image(image));
+        }
+
+        let (tx, rx) = oneoff();
+        self.run(ClipboardOp::SetImage(image, tx)).await?;
+        rx.recv().await
+    }
+
+    /// Clear the contents of the clipboard.
+    pub async fn clear(&self) -> Result<(), Error> {
+        if self.reactor.on_event_loop_thread() {
+            return with_clipboard(|clipboard| clipboard.clear());
+        }
+
+        let (tx, rx) = oneoff();
+        self.run(ClipboardOp::Clear(tx)).await?;
+        rx.recv().await
+    }
+
+    /// Send an operation to the event loop thread.
+    async fn run(&self, op: ClipboardOp<TS>) -> Result<(), Error> {
+        self.reactor
+            .push_event_loop_op(EventLoopOp::Clipboard(op))
+            .await
+            .map_err(|_| Error::Unknown {
+                description: "the event loop has exited".into(),
+            })
+    }
+}
+
+/// An operation on the clipboard, run on the event loop thread.
+pub(crate) enum ClipboardOp<TS: ThreadSafety> {
+    GetText(Complete<Result<String, Error>, TS>),
+    SetText(String, Complete<Result<(), Error>, TS>),
+    GetImage(Complete<Result<ImageData<'static>, Error>, TS>),
+    SetImage(ImageData<'static>, Complete<Result<(), Error>, TS>),
+    Clear(Complete<Result<(), Error>, TS>),
+}
+
+impl<TS: ThreadSafety> ClipboardOp<TS> {
+    /// Run the operation. This must be called on the event loop thread.
+    pub(crate) fn run(self) {
+        match self {
+            ClipboardOp::GetText(waker) => {
+                waker.send(with_clipboard(|clipboard| clipboard.get_text()));
+            }
+            ClipboardOp::SetText(text, waker) => {
+                waker.send(with_clipboard(|clipboard| clipboard.set_text(text)));
+            }
+            ClipboardOp::GetImage(waker) => {
+                waker.send(with_clipboard(|clipboard| clipboard.get_image()));
+            }
+            ClipboardOp::SetImage(image, waker) => {
+                waker.send(with_clipboard(|clipboard| clipboard.set_image(image)));
+            }
+            ClipboardOp::Clear(waker) => {
+                waker.send(with_clipboard(|clipboard| clipboard.clear()));
+            }
+        }
+    }
+}
+
+/// Run a closure with the clipboard, creating it if it doesn't exist yet.
+fn with_clipboard<R>(
+    f: impl FnOnce(&mut arboard::Clipboard) -> Result<R, Error>,
+) -> Result<R, Error> {
+    CLIPBOARD.with(|slot| {
+        let mut slot = slot.borrow_mut();
+        let clipboard = match &mut *slot {
+            Some(clipboard) => clipboard,
+            slot @ None => slot.insert(arboard::Clipboard::new()?),
+        };
+
+        f(clipboard)
+    })
+}
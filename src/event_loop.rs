@@ -48,18 +48,25 @@ Public License along with `async-winit`. If not, see <https://www.gnu.org/licens
 //!
 //! [`Handler`]: crate::Handler
 
+use crate::filter::FlowOptions;
 use crate::handler::Handler;
 use crate::reactor::{EventLoopOp, Reactor};
 use crate::sync::ThreadSafety;
+use crate::watchdog::{SlowDispatch, Watchdog, WatchdogCallback};
+use crate::window::Window;
 use crate::DefaultThreadSafety;
 
 use std::convert::Infallible;
 use std::fmt;
 use std::future::Future;
+use std::marker::PhantomData;
+use std::num::NonZeroUsize;
 use std::ops;
+use std::time::Duration;
 
 use raw_window_handle::{HasRawDisplayHandle, RawDisplayHandle};
 use winit::event_loop::EventLoopProxy;
+use winit::window::WindowId;
 
 #[doc(inline)]
 pub use winit::event_loop::{ControlFlow, DeviceEventFilter, EventLoopClosed};
@@ -77,6 +84,57 @@ pub struct Wakeup {
     pub(crate) _private: (),
 }
 
+/// The windowing backend that an [`EventLoop`] is running on.
+///
+/// This can be used to branch on the platform at runtime, which is useful on Linux where the same
+/// binary can run on either X11 or Wayland.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// The X Window System, through either Xlib or XCB.
+    X11,
+
+    /// A Wayland compositor.
+    Wayland,
+
+    /// The Win32 API on Windows.
+    Win32,
+
+    /// AppKit on macOS.
+    AppKit,
+
+    /// UIKit on iOS.
+    UiKit,
+
+    /// The Android NDK.
+    Android,
+
+    /// A web browser.
+    Web,
+
+    /// The Orbital windowing system on Redox.
+    Orbital,
+
+    /// Some other backend.
+    Other,
+}
+
+impl From<RawDisplayHandle> for Backend {
+    fn from(handle: RawDisplayHandle) -> Self {
+        match handle {
+            RawDisplayHandle::Xlib(_) | RawDisplayHandle::Xcb(_) => Backend::X11,
+            RawDisplayHandle::Wayland(_) => Backend::Wayland,
+            RawDisplayHandle::Windows(_) => Backend::Win32,
+            RawDisplayHandle::AppKit(_) => Backend::AppKit,
+            RawDisplayHandle::UiKit(_) => Backend::UiKit,
+            RawDisplayHandle::Android(_) => Backend::Android,
+            RawDisplayHandle::Web(_) => Backend::Web,
+            RawDisplayHandle::Orbital(_) => Backend::Orbital,
+            _ => Backend::Other,
+        }
+    }
+}
+
 /// Provides a way to retrieve events from the system and from the windows that were registered to
 /// the events loop.
 ///
@@ -97,6 +155,29 @@ pub struct EventLoop<TS: ThreadSafety = DefaultThreadSafety> {
 
     /// The window target.
     window_target: EventLoopWindowTarget<TS>,
+
+    /// Resets the reactor once this event loop is dropped.
+    _reset: ResetReactor<TS>,
+
+    /// Keeps the system timer resolution raised while this event loop is alive.
+    #[cfg(windows)]
+    _high_resolution_timer: Option<crate::platform::windows::HighResolutionTimer>,
+}
+
+/// Discards the global reactor when dropped.
+///
+/// The reactor's lifetime is tied to the [`EventLoop`] that drives it. Once the event loop is gone,
+/// the reactor is reset so that its windows, timers and proxies are released instead of living
+/// until the end of the process. This does not make it possible to create another [`EventLoop`];
+/// `winit` still panics if that is attempted.
+struct ResetReactor<TS: ThreadSafety> {
+    _marker: PhantomData<TS>,
+}
+
+impl<TS: ThreadSafety> Drop for ResetReactor<TS> {
+    fn drop(&mut self) {
+        Reactor::<TS>::reset();
+    }
 }
 
 impl<TS: ThreadSafety> fmt::Debug for EventLoop<TS> {
@@ -105,6 +186,19 @@ impl<TS: ThreadSafety> fmt::Debug for EventLoop<TS> {
     }
 }
 
+/// An executor whose tasks can be run by [`EventLoop::block_on_with_executor`].
+///
+/// This is implemented for `async_executor::LocalExecutor` and `async_executor::Executor`, and
+/// cannot be implemented outside of this crate.
+#[cfg(feature = "executor")]
+pub trait EventLoopExecutor: crate::sync::__private::Executor {}
+
+#[cfg(feature = "executor")]
+impl EventLoopExecutor for async_executor::LocalExecutor<'static> {}
+
+#[cfg(feature = "executor")]
+impl EventLoopExecutor for async_executor::Executor<'static> {}
+
 /// A reference to the `EventLoop` that allows the user access to the underlying display connections.
 ///
 /// Unlike in `winit`, this type is cheaply clonable. It is not actually used that often, since most of
@@ -113,7 +207,7 @@ impl<TS: ThreadSafety> fmt::Debug for EventLoop<TS> {
 /// available monitors.
 pub struct EventLoopWindowTarget<TS: ThreadSafety = DefaultThreadSafety> {
     /// The associated reactor, cached for convenience.
-    reactor: TS::Rc<Reactor<TS>>,
+    pub(crate) reactor: TS::Rc<Reactor<TS>>,
 
     /// The event loop proxy.
     proxy: EventLoopProxy<Wakeup>,
@@ -151,6 +245,26 @@ impl<TS: ThreadSafety> Clone for EventLoopWindowTarget<TS> {
 pub struct EventLoopBuilder {
     /// The underlying builder.
     pub(crate) inner: winit::event_loop::EventLoopBuilder<Wakeup>,
+
+    /// Options for choosing the control flow.
+    flow_options: FlowOptions,
+
+    /// Whether to collect latency metrics.
+    metrics: bool,
+
+    /// The threshold for the watchdog, if it is enabled.
+    watchdog_threshold: Option<Duration>,
+
+    /// The callback for the watchdog.
+    watchdog_callback: Option<WatchdogCallback>,
+
+    /// Whether to raise the system timer resolution.
+    #[cfg(windows)]
+    pub(crate) high_resolution_timer: bool,
+
+    /// The Android app to run the event loop with.
+    #[cfg(android_platform)]
+    pub(crate) android_app: Option<crate::platform::android::activity::AndroidApp>,
 }
 
 impl fmt::Debug for EventLoopBuilder {
@@ -164,9 +278,113 @@ impl EventLoopBuilder {
     pub fn new() -> Self {
         Self {
             inner: winit::event_loop::EventLoopBuilder::with_user_event(),
+            flow_options: FlowOptions::default(),
+            metrics: false,
+            watchdog_threshold: None,
+            watchdog_callback: None,
+            #[cfg(windows)]
+            high_resolution_timer: false,
+            #[cfg(android_platform)]
+            android_app: None,
         }
     }
 
+    /// Set the control flow to use when there are no pending timers and no tasks to run.
+    ///
+    /// The default is [`ControlFlow::Wait`]. Power-insensitive applications that want to render
+    /// continuously may want to use [`ControlFlow::Poll`] instead.
+    #[inline]
+    pub fn with_control_flow(&mut self, control_flow: ControlFlow) -> &mut Self {
+        self.flow_options.control_flow = control_flow;
+        self
+    }
+
+    /// Set the maximum amount of time that the event loop will sleep for.
+    ///
+    /// If this is set, the event loop wakes up periodically, even if there are no events or timers.
+    /// By default, the event loop can sleep indefinitely.
+    #[inline]
+    pub fn with_max_wait(&mut self, max_wait: Option<Duration>) -> &mut Self {
+        self.flow_options.max_wait = max_wait;
+        self
+    }
+
+    /// Set whether to poll or to wait for the next timer when a task yields while a timer is
+    /// pending.
+    ///
+    /// If this is `true` (the default), the event loop polls so the yielding task runs again as soon
+    /// as possible. If this is `false`, the event loop waits until the next timer fires.
+    #[inline]
+    pub fn with_prefer_poll(&mut self, prefer_poll: bool) -> &mut Self {
+        self.flow_options.prefer_poll = prefer_poll;
+        self
+    }
+
+    /// Set the maximum number of timers to fire in a single pass of the event loop.
+    ///
+    /// Expired timers past this budget are fired on the next pass instead, so that a burst of
+    /// timers can't keep the event loop from handling events. By default, there is no limit.
+    /// The number of deferred timers can be read through [`EventLoopWindowTarget::deferred_timers`].
+    #[inline]
+    pub fn with_timer_budget(&mut self, budget: Option<NonZeroUsize>) -> &mut Self {
+        self.flow_options.timer_budget = budget;
+        self
+    }
+
+    /// Set the maximum number of window and event loop operations to run in a single pass of the
+    /// event loop.
+    ///
+    /// Operations past this budget are run on the next pass instead. By default, this is the
+    /// capacity of the operation queue. The number of deferred operations can be read through
+    /// [`EventLoopWindowTarget::deferred_ops`].
+    #[inline]
+    pub fn with_op_budget(&mut self, budget: Option<NonZeroUsize>) -> &mut Self {
+        self.flow_options.op_budget = budget;
+        self
+    }
+
+    /// Set whether to collect latency metrics for events.
+    ///
+    /// If this is `true`, the event loop records how long each event takes to be handled, as well as
+    /// how late the event loop wakes up for timers. These can be read through
+    /// [`EventLoopWindowTarget::metrics`]. This is `false` by default, since stamping each event has
+    /// a small cost.
+    #[inline]
+    pub fn with_metrics(&mut self, metrics: bool) -> &mut Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Set the threshold for the watchdog.
+    ///
+    /// If this is set, any event dispatch or window operation that holds the event loop for longer
+    /// than the threshold is reported, along with which event or operation it was. By default,
+    /// reports are logged as warnings through the `log` and `tracing` features, if they are
+    /// enabled; use [`with_watchdog_callback`] to handle them differently. The watchdog is disabled
+    /// by default.
+    ///
+    /// [`with_watchdog_callback`]: Self::with_watchdog_callback
+    #[inline]
+    pub fn with_watchdog(&mut self, threshold: Option<Duration>) -> &mut Self {
+        self.watchdog_threshold = threshold;
+        self
+    }
+
+    /// Set the callback that the watchdog reports slow dispatches to.
+    ///
+    /// This has no effect unless a threshold is set through [`with_watchdog`]. The callback runs on
+    /// the event loop thread, so it should not block.
+    ///
+    /// [`with_watchdog`]: Self::with_watchdog
+    #[inline]
+    pub fn with_watchdog_callback(
+        &mut self,
+        callback: impl FnMut(&SlowDispatch) + Send + 'static,
+    ) -> &mut Self {
+        self.watchdog_callback = Some(Box::new(callback));
+        self
+    }
+
     /// Builds a new event loop.
     ///
     /// In general, this function must be called on the same thread that `main()` is being run inside of.
@@ -179,9 +397,23 @@ impl EventLoopBuilder {
     /// [`platform`]: crate::platform
     pub fn build<TS: ThreadSafety>(&mut self) -> EventLoop<TS> {
         let inner = self.inner.build();
+        let reactor = Reactor::<TS>::get();
+        reactor.set_flow_options(self.flow_options);
+        reactor.set_metrics_enabled(self.metrics);
+        reactor.set_watchdog(
+            self.watchdog_threshold
+                .map(|threshold| Watchdog::new(threshold, self.watchdog_callback.take())),
+        );
+        #[cfg(macos_platform)]
+        crate::platform::macos::listen_for_apple_events();
+        #[cfg(android_platform)]
+        if let Some(app) = self.android_app.take() {
+            reactor.android_app.set(app).ok();
+        }
+
         EventLoop {
             window_target: EventLoopWindowTarget {
-                reactor: Reactor::<TS>::get(),
+                reactor,
                 proxy: inner.create_proxy(),
                 raw_display_handle: inner.raw_display_handle(),
                 #[cfg(any(x11_platform, wayland_platform))]
@@ -200,6 +432,15 @@ impl EventLoopBuilder {
                 },
             },
             inner,
+            _reset: ResetReactor {
+                _marker: PhantomData,
+            },
+            #[cfg(windows)]
+            _high_resolution_timer: if self.high_resolution_timer {
+                crate::platform::windows::HighResolutionTimer::new()
+            } else {
+                None
+            },
         }
     }
 }
@@ -260,6 +501,59 @@ impl<TS: ThreadSafety> EventLoopWindowTarget<TS> {
         futures_lite::future::pending().await
     }
 
+    /// Wait until an exit is requested, and get the exit code.
+    ///
+    /// This resolves once any task calls [`set_exit`] or one of its variants. To shut down other
+    /// services before the event loop exits, use the [`exit_requested`] handler along with
+    /// [`Waiter::hold`] instead.
+    ///
+    /// [`set_exit`]: Self::set_exit
+    /// [`exit_requested`]: Self::exit_requested
+    /// [`Waiter::hold`]: crate::Waiter::hold
+    pub async fn wait_exit(&self) -> i32 {
+        // Set up the listener before checking, so the request can't be missed.
+        let exit_requested = self.exit_requested().wait();
+
+        match self.reactor.exit_requested() {
+            Some(code) => code,
+            None => exit_requested.await,
+        }
+    }
+
+    /// Get the number of expired timers whose firing has been deferred to a later pass.
+    ///
+    /// This only increases if a budget is set through [`EventLoopBuilder::with_timer_budget`].
+    #[inline]
+    pub fn deferred_timers(&self) -> usize {
+        self.reactor.deferred_timers()
+    }
+
+    /// Get the number of operations that have been deferred to a later pass.
+    ///
+    /// This increases if there are more operations queued than the budget set through
+    /// [`EventLoopBuilder::with_op_budget`].
+    #[inline]
+    pub fn deferred_ops(&self) -> usize {
+        self.reactor.deferred_ops()
+    }
+
+    /// Get a snapshot of the latency metrics collected so far.
+    ///
+    /// Returns `None` unless metrics were enabled through [`EventLoopBuilder::with_metrics`].
+    #[inline]
+    pub fn metrics(&self) -> Option<crate::metrics::Metrics> {
+        self.reactor.metrics()
+    }
+
+    /// Get the handler that is run when an exit is requested.
+    ///
+    /// The handler receives the requested exit code. The event loop does not exit until the
+    /// handler has finished running.
+    #[inline]
+    pub fn exit_requested(&self) -> &Handler<i32, TS> {
+        &self.reactor.evl_registration.exit_requested
+    }
+
     /// Get the handler for the `Resumed` event.
     #[inline]
     pub fn resumed(&self) -> &Handler<(), TS> {
@@ -272,12 +566,86 @@ impl<TS: ThreadSafety> EventLoopWindowTarget<TS> {
         &self.reactor.evl_registration.suspended
     }
 
+    /// Get the handler that is run when a new window is created.
+    ///
+    /// The handler receives the ID of the new window, which can be passed to [`window`] to get a
+    /// handle to it.
+    ///
+    /// [`window`]: Self::window
+    #[inline]
+    pub fn window_created(&self) -> &Handler<WindowId, TS> {
+        &self.reactor.evl_registration.window_created
+    }
+
+    /// Get the handler that is run when a gamepad is connected.
+    #[cfg(feature = "gamepad")]
+    #[inline]
+    pub fn gamepad_connected(&self) -> &Handler<crate::gamepad::GamepadId, TS> {
+        &self.reactor.evl_registration.gamepad_connected
+    }
+
+    /// Get the handler that is run when a gamepad is disconnected.
+    #[cfg(feature = "gamepad")]
+    #[inline]
+    pub fn gamepad_disconnected(&self) -> &Handler<crate::gamepad::GamepadId, TS> {
+        &self.reactor.evl_registration.gamepad_disconnected
+    }
+
+    /// Get the handler that is run when a button on a gamepad is pressed or released.
+    #[cfg(feature = "gamepad")]
+    #[inline]
+    pub fn gamepad_button(&self) -> &Handler<crate::gamepad::GamepadButton, TS> {
+        &self.reactor.evl_registration.gamepad_button
+    }
+
+    /// Get the handler that is run when an axis on a gamepad changes.
+    #[cfg(feature = "gamepad")]
+    #[inline]
+    pub fn gamepad_axis(&self) -> &Handler<crate::gamepad::GamepadAxis, TS> {
+        &self.reactor.evl_registration.gamepad_axis
+    }
+
+    /// Get the handler that is run when an item in a `muda` menu is activated.
+    #[cfg(all(feature = "muda", any(windows, macos_platform)))]
+    #[inline]
+    pub fn menu_event(&self) -> &Handler<crate::menu::MenuEvent, TS> {
+        &self.reactor.evl_registration.menu_event
+    }
+
+    /// Get a handle to the system clipboard.
+    #[cfg(feature = "arboard")]
+    #[inline]
+    pub fn clipboard(&self) -> crate::clipboard::Clipboard<TS> {
+        crate::clipboard::Clipboard::new(self.reactor.clone())
+    }
+
+    /// Get a handle to the window with the provided ID, if it still exists.
+    #[inline]
+    pub fn window(&self, id: WindowId) -> Option<Window<TS>> {
+        let (inner, registration) = self.reactor.window(id)?;
+        let window = Window::from_parts(inner, registration, self.reactor.clone());
+        Some(window)
+    }
+
+    /// Get handles to all of the windows that currently exist.
+    ///
+    /// This includes windows that were created by other parts of the program.
+    #[inline]
+    pub fn windows(&self) -> impl Iterator<Item = Window<TS>> + '_ {
+        self.reactor
+            .windows()
+            .into_iter()
+            .map(move |(inner, registration)| {
+                Window::from_parts(inner, registration, self.reactor.clone())
+            })
+    }
+
     /// Get the primary monitor.
     #[inline]
     pub async fn primary_monitor(&self) -> Option<winit::monitor::MonitorHandle> {
         let (tx, rx) = crate::oneoff::oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::PrimaryMonitor(tx))
+            .push_event_loop_op_or_wait(EventLoopOp::PrimaryMonitor(tx))
             .await;
         rx.recv().await
     }
@@ -287,17 +655,23 @@ impl<TS: ThreadSafety> EventLoopWindowTarget<TS> {
     pub async fn available_monitors(&self) -> impl Iterator<Item = winit::monitor::MonitorHandle> {
         let (tx, rx) = crate::oneoff::oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::AvailableMonitors(tx))
+            .push_event_loop_op_or_wait(EventLoopOp::AvailableMonitors(tx))
             .await;
         rx.recv().await.into_iter()
     }
 
+    /// Get the windowing backend that the event loop is running on.
+    #[inline]
+    pub fn backend(&self) -> Backend {
+        self.raw_display_handle.into()
+    }
+
     /// Set the device event filter.
     #[inline]
     pub async fn set_device_event_filter(&self, filter: DeviceEventFilter) {
         let (tx, rx) = crate::oneoff::oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetDeviceFilter { filter, waker: tx })
+            .push_event_loop_op_or_wait(EventLoopOp::SetDeviceFilter { filter, waker: tx })
             .await;
 
         // Wait for the filter to be set.
@@ -305,6 +679,159 @@ impl<TS: ThreadSafety> EventLoopWindowTarget<TS> {
     }
 }
 
+impl EventLoopWindowTarget<crate::ThreadUnsafe> {
+    /// Register a function that can cancel exit requests.
+    ///
+    /// When an exit is requested, `interceptor` is called with the requested exit code. If it
+    /// resolves to `false`, the exit is cancelled. The event loop only exits once every interceptor
+    /// has resolved to `true`.
+    ///
+    /// Note that the futures returned by [`exit`] and [`exit_with_code`] never complete, even if
+    /// the exit is cancelled.
+    ///
+    /// [`exit`]: Self::exit
+    /// [`exit_with_code`]: Self::exit_with_code
+    pub fn intercept_exit<F, Fut>(&self, mut interceptor: F)
+    where
+        F: FnMut(i32) -> Fut + 'static,
+        Fut: Future<Output = bool> + 'static,
+    {
+        self.reactor
+            .intercept_exit(Box::new(move |code| Box::pin(interceptor(code))));
+    }
+}
+
+#[cfg(feature = "thread_safe")]
+impl EventLoopWindowTarget<crate::ThreadSafe> {
+    /// Register a function that can cancel exit requests.
+    ///
+    /// When an exit is requested, `interceptor` is called with the requested exit code. If it
+    /// resolves to `false`, the exit is cancelled. The event loop only exits once every interceptor
+    /// has resolved to `true`.
+    ///
+    /// Note that the futures returned by [`exit`] and [`exit_with_code`] never complete, even if
+    /// the exit is cancelled.
+    ///
+    /// [`exit`]: Self::exit
+    /// [`exit_with_code`]: Self::exit_with_code
+    pub fn intercept_exit<F, Fut>(&self, mut interceptor: F)
+    where
+        F: FnMut(i32) -> Fut + Send + 'static,
+        Fut: Future<Output = bool> + Send + 'static,
+    {
+        self.reactor
+            .intercept_exit(Box::new(move |code| Box::pin(interceptor(code))));
+    }
+}
+
+#[cfg(feature = "executor")]
+impl EventLoopWindowTarget<crate::ThreadUnsafe> {
+    /// Spawn a task onto the event loop.
+    ///
+    /// The task runs concurrently with the future passed to [`EventLoop::block_on`]. It is
+    /// cancelled if the returned [`Task`] is dropped; use [`Task::detach`] to let it run in the
+    /// background.
+    ///
+    /// [`Task`]: crate::Task
+    /// [`Task::detach`]: crate::Task::detach
+    #[inline]
+    pub fn spawn<F>(&self, future: F) -> crate::Task<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        self.reactor.executor.spawn(future)
+    }
+
+    /// Spawn a task that is cancelled once the application is suspended.
+    ///
+    /// The task resolves to `None` if it was cancelled. The `Suspended` event is not finished being
+    /// handled until the future has been dropped, so it is safe to hold on to graphics resources in
+    /// the future.
+    #[inline]
+    pub fn spawn_until_suspend<F>(&self, future: F) -> crate::Task<Option<F::Output>>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let reactor = self.reactor.clone();
+        self.reactor
+            .executor
+            .spawn(async move { reactor.until_suspend(future).await })
+    }
+
+    /// Spawn a task that is cancelled once the application is suspended, and restarted using
+    /// `factory` once the application is resumed.
+    ///
+    /// The first future is created immediately. The task completes once one of the futures
+    /// completes without being cancelled.
+    #[inline]
+    pub fn spawn_resumable<F, Fut>(&self, factory: F) -> crate::Task<()>
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future + 'static,
+    {
+        let reactor = self.reactor.clone();
+        self.reactor
+            .executor
+            .spawn(async move { reactor.resumable(factory).await })
+    }
+}
+
+#[cfg(all(feature = "executor", feature = "thread_safe"))]
+impl EventLoopWindowTarget<crate::ThreadSafe> {
+    /// Spawn a task onto the event loop.
+    ///
+    /// The task runs concurrently with the future passed to [`EventLoop::block_on`]. It is
+    /// cancelled if the returned [`Task`] is dropped; use [`Task::detach`] to let it run in the
+    /// background.
+    ///
+    /// [`Task`]: crate::Task
+    /// [`Task::detach`]: crate::Task::detach
+    #[inline]
+    pub fn spawn<F>(&self, future: F) -> crate::Task<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.reactor.executor.spawn(future)
+    }
+
+    /// Spawn a task that is cancelled once the application is suspended.
+    ///
+    /// The task resolves to `None` if it was cancelled. The `Suspended` event is not finished being
+    /// handled until the future has been dropped, so it is safe to hold on to graphics resources in
+    /// the future.
+    #[inline]
+    pub fn spawn_until_suspend<F>(&self, future: F) -> crate::Task<Option<F::Output>>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let reactor = self.reactor.clone();
+        self.reactor
+            .executor
+            .spawn(async move { reactor.until_suspend(future).await })
+    }
+
+    /// Spawn a task that is cancelled once the application is suspended, and restarted using
+    /// `factory` once the application is resumed.
+    ///
+    /// The first future is created immediately. The task completes once one of the futures
+    /// completes without being cancelled.
+    #[inline]
+    pub fn spawn_resumable<F, Fut>(&self, factory: F) -> crate::Task<()>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future + Send + 'static,
+    {
+        let reactor = self.reactor.clone();
+        self.reactor
+            .executor
+            .spawn(async move { reactor.resumable(factory).await })
+    }
+}
+
 unsafe impl<TS: ThreadSafety> HasRawDisplayHandle for EventLoopWindowTarget<TS> {
     fn raw_display_handle(&self) -> RawDisplayHandle {
         self.raw_display_handle
@@ -323,13 +850,61 @@ impl<TS: ThreadSafety + 'static> EventLoop<TS> {
     pub fn block_on(self, future: impl Future<Output = Infallible> + 'static) -> ! {
         let inner = self.inner;
 
-        let mut future = Box::pin(future);
+        let reactor = self.window_target.reactor.clone();
+        let mut future = Box::pin(async move { reactor.run_tasks(future).await });
         let mut filter = crate::filter::Filter::<TS>::new(&inner);
 
         inner.run(move |event, elwt, flow| {
             filter.handle_event(future.as_mut(), event, elwt, flow);
         })
     }
+
+    /// Split this event loop into the underlying event loop and a guard that keeps the reactor
+    /// alive.
+    #[cfg(wasm_platform)]
+    pub(crate) fn into_parts(self) -> (winit::event_loop::EventLoop<Wakeup>, impl Sized) {
+        (self.inner, (self.window_target, self._reset))
+    }
+
+    /// Block on a future forever, while running the tasks of the provided executor.
+    ///
+    /// `executor` is a reference to an [`EventLoopExecutor`], such as an `Rc` or an `Arc`. Its tasks
+    /// are run on the event loop thread whenever they are woken, so there is no need to wrap futures
+    /// in `executor.run()`.
+    #[cfg(feature = "executor")]
+    #[inline]
+    pub fn block_on_with_executor<E>(
+        self,
+        executor: E,
+        future: impl Future<Output = Infallible> + 'static,
+    ) -> !
+    where
+        E: ops::Deref + 'static,
+        E::Target: EventLoopExecutor,
+    {
+        use crate::sync::__private::Executor;
+
+        self.block_on(async move { executor.run(Box::pin(future)).await })
+    }
+
+    /// Block on a future forever, inside of a `tokio` runtime.
+    ///
+    /// The future, along with any tasks spawned using `tokio::task::spawn_local`, can use types
+    /// from `tokio` and libraries built on top of it. The runtime's I/O and time drivers are not
+    /// run by the event loop, so they are left to the runtime's worker threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `handle` belongs to a current-thread runtime, since nothing would drive it.
+    #[cfg(feature = "tokio")]
+    #[inline]
+    pub fn block_on_tokio(
+        self,
+        handle: tokio::runtime::Handle,
+        future: impl Future<Output = Infallible> + 'static,
+    ) -> ! {
+        self.block_on(crate::tokio_compat::drive(handle, future))
+    }
 }
 
 impl<TS: ThreadSafety> ops::Deref for EventLoop<TS> {
@@ -20,26 +20,32 @@ Public License along with `async-winit`. If not, see <https://www.gnu.org/licens
 //!
 //! This module is exposed such that it is possible to integrate `async-winit` easily with existing
 //! `winit` applications. The `Filter` type can be provided events, and will send those events to this
-//! library's event handlers.
+//! library's event handlers. For event loops that are not driven by `winit` at all, the
+//! [`ReactorHandle`] type can be used to deliver events directly.
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::cmp;
+use std::fmt;
 use std::future::Future;
+use std::num::NonZeroUsize;
 use std::pin::Pin;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll, Wake, Waker};
-use std::time::Instant;
+use std::time::Duration;
 
 use futures_lite::prelude::*;
-use parking::Parker;
+use instant::Instant;
 
 use crate::event_loop::Wakeup;
 use crate::reactor::Reactor;
+use crate::sync::__private::{Parker, Unparker};
 use crate::sync::ThreadSafety;
+use crate::DefaultThreadSafety;
 
-use winit::event::Event;
+use winit::event::{Event, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop, EventLoopProxy, EventLoopWindowTarget};
+use winit::window::WindowId;
 
 /// Either a function returned, or an associated future returned first.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -55,7 +61,7 @@ pub enum ReturnOrFinish<O, T> {
 ///
 /// This type takes events and passes them to the event handlers. It also handles the `async` contexts
 /// that are waiting for events.
-pub struct Filter<TS: ThreadSafety> {
+pub struct Filter<TS: ThreadSafety = DefaultThreadSafety> {
     /// The deadline to wait until.
     deadline: Option<Instant>,
 
@@ -63,7 +69,7 @@ pub struct Filter<TS: ThreadSafety> {
     wakers: Vec<Waker>,
 
     /// The parker to use for posting.
-    parker: Parker,
+    parker: TS::Parker,
 
     /// The notifier.
     notifier: Arc<ReactorWaker>,
@@ -81,6 +87,9 @@ pub struct Filter<TS: ThreadSafety> {
     /// The future has indicated that it wants to yield.
     yielding: bool,
 
+    /// Options for choosing the control flow.
+    options: FlowOptions,
+
     /// The reactor.
     reactor: TS::Rc<Reactor<TS>>,
 }
@@ -95,20 +104,24 @@ impl<TS: ThreadSafety> Filter<TS> {
         // Create a waker to wake us up.
         let notifier = Arc::new(ReactorWaker {
             proxy: Mutex::new(inner.create_proxy()),
+            id: NEXT_WAKER_ID.fetch_add(1, Ordering::Relaxed),
             notified: AtomicBool::new(true),
             awake: AtomicBool::new(false),
+            epoch: Instant::now(),
+            wake_deadline: AtomicU64::new(WAKE_UNKNOWN),
         });
         let notifier_waker = Waker::from(notifier.clone());
         reactor.set_proxy(notifier.clone());
 
         // Parker/unparker pair.
-        let (parker, unparker) = parking::pair();
+        let (parker, unparker) = TS::Parker::pair();
         let parker_waker = Waker::from(Arc::new(EventPostWaker {
             reactor_waker: notifier.clone(),
             unparker,
         }));
 
         Filter {
+            options: reactor.flow_options(),
             deadline: None,
             wakers: vec![],
             parker,
@@ -152,17 +165,22 @@ impl<TS: ThreadSafety> Filter<TS> {
                 // We were previously asleep and are now awake.
                 self.notifier.awake.store(true, Ordering::SeqCst);
 
+                // We don't know when we'll wake up next until we're about to sleep.
+                self.notifier
+                    .wake_deadline
+                    .store(WAKE_UNKNOWN, Ordering::SeqCst);
+
                 // Figure out how long we should wait for.
                 self.deadline = self.reactor.process_timers(&mut self.wakers);
 
+                // Wake up the timers while we're awake, so the proxy isn't used.
+                flush_wakers(&mut self.wakers);
+
                 // We are not about to fall asleep.
                 false
             }
 
             Event::RedrawEventsCleared => {
-                // We are about to fall asleep, so make sure that the future knows it.
-                self.notifier.awake.store(false, Ordering::SeqCst);
-
                 // We are about to fall asleep.
                 true
             }
@@ -211,20 +229,35 @@ impl<TS: ThreadSafety> Filter<TS> {
                 };
 
                 // Wake any wakers that need to be woken.
-                for waker in self.wakers.drain(..) {
-                    waker.wake();
-                }
+                flush_wakers(&mut self.wakers);
 
                 // Park the thread until it is notified, or until the timeout.
-                match deadline {
-                    None => self.parker.park(),
-                    Some(deadline) => {
-                        self.parker.park_deadline(deadline);
+                //
+                // The browser's main thread can't block, so on the web we keep polling instead.
+                #[cfg(not(wasm_platform))]
+                {
+                    #[cfg(feature = "tracing")]
+                    let _span = tracing::trace_span!("park", ?deadline).entered();
+
+                    match deadline {
+                        None => self.parker.park(),
+                        Some(deadline) => {
+                            self.parker.park_deadline(deadline);
+                        }
                     }
                 }
+
+                #[cfg(wasm_platform)]
+                let _ = deadline;
             }
         }
 
+        // Run anything left over before we poll the future for the last time.
+        if about_to_sleep {
+            self.reactor.drain_loop_queue(elwt);
+            flush_wakers(&mut self.wakers);
+        }
+
         // If the future is still notified, we should poll it.
         while !self.yielding && self.notifier.notified.swap(false, Ordering::SeqCst) {
             let mut cx = Context::from_waker(&self.notifier_waker);
@@ -241,27 +274,45 @@ impl<TS: ThreadSafety> Filter<TS> {
             }
         }
 
-        // Wake everything up if we're about to sleep.
-        if about_to_sleep {
-            self.reactor.drain_loop_queue(elwt);
-            for waker in self.wakers.drain(..) {
-                waker.wake();
-            }
-        }
+        // Wakeups only need to go through the proxy once we're asleep.
+        let woken_while_sleeping = about_to_sleep && {
+            self.notifier.awake.store(false, Ordering::SeqCst);
+
+            // Catch any wakeups that happened before we were marked as asleep.
+            self.notifier.notified.load(Ordering::SeqCst)
+        };
 
         // Set the control flow.
         if let Some(code) = self.reactor.exit_requested() {
             // The user wants to exit.
             flow.set_exit_with_code(code);
-        } else if self.yielding {
+        } else if woken_while_sleeping {
+            // The future was woken up while we were falling asleep.
+            flow.set_poll();
+        } else if self.yielding && (self.options.prefer_poll || self.deadline.is_none()) {
             // The future wants to be polled again as soon as possible.
             flow.set_poll();
         } else if let Some(deadline) = self.deadline {
             // The future wants to be polled again when the deadline is reached.
+            let deadline = match self.options.max_wait {
+                Some(max_wait) => cmp::min(deadline, Instant::now() + max_wait),
+                None => deadline,
+            };
+
             flow.set_wait_until(deadline);
+        } else if let (ControlFlow::Wait, Some(max_wait)) =
+            (self.options.control_flow, self.options.max_wait)
+        {
+            // Wake up periodically.
+            flow.set_wait_until(Instant::now() + max_wait);
         } else {
-            // The future wants to poll.
-            flow.set_wait();
+            // Use the default control flow.
+            *flow = self.options.control_flow;
+        }
+
+        // Let the reactor know when we're going to wake up, so it can skip redundant wakeups.
+        if about_to_sleep {
+            self.notifier.set_wake_deadline(*flow);
         }
 
         // Return the output if any.
@@ -269,10 +320,160 @@ impl<TS: ThreadSafety> Filter<TS> {
     }
 }
 
+/// Wake up all of the wakers collected during this pass.
+///
+/// While the event loop is awake, these wakeups are coalesced into the notifier's flag instead of
+/// each going through the event loop proxy. This takes the wakers rather than the whole filter,
+/// since the reactor may still be borrowed by the event being posted.
+fn flush_wakers(wakers: &mut Vec<Waker>) {
+    for waker in wakers.drain(..) {
+        waker.wake();
+    }
+}
+
+/// A handle to the reactor that powers `async-winit`.
+///
+/// This can be used to drive `async-winit` from a custom event loop, instead of using the
+/// [`Filter`]. Events are delivered to the event handlers through the `post_*` methods, and
+/// operations that need to run on the event loop thread (like creating windows) are run through
+/// [`drain_ops`].
+///
+/// Note that the reactor can't wake up a custom event loop by itself. The event loop should call
+/// [`drain_ops`] and [`process_timers`] regularly.
+///
+/// [`drain_ops`]: ReactorHandle::drain_ops
+/// [`process_timers`]: ReactorHandle::process_timers
+pub struct ReactorHandle<TS: ThreadSafety = DefaultThreadSafety> {
+    /// The reactor.
+    reactor: TS::Rc<Reactor<TS>>,
+}
+
+/// An event that affects the lifecycle of the application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LifecycleEvent {
+    /// The application has been resumed.
+    Resumed,
+
+    /// The application has been suspended.
+    Suspended,
+}
+
+impl<TS: ThreadSafety> fmt::Debug for ReactorHandle<TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ReactorHandle { .. }")
+    }
+}
+
+impl<TS: ThreadSafety> Clone for ReactorHandle<TS> {
+    fn clone(&self) -> Self {
+        Self {
+            reactor: self.reactor.clone(),
+        }
+    }
+}
+
+impl<TS: ThreadSafety> ReactorHandle<TS> {
+    /// Get a handle to the current reactor.
+    pub fn get() -> Self {
+        Self {
+            reactor: Reactor::<TS>::get(),
+        }
+    }
+
+    /// Deliver a window event to the window's event handlers.
+    ///
+    /// The future completes once the event handlers have finished handling the event.
+    pub async fn post_window_event(&self, window_id: WindowId, event: WindowEvent<'_>) {
+        self.reactor
+            .post_event::<()>(Event::WindowEvent { window_id, event })
+            .await
+    }
+
+    /// Deliver a `RedrawRequested` event to the window's event handlers.
+    pub async fn post_redraw_requested(&self, window_id: WindowId) {
+        self.reactor
+            .post_event::<()>(Event::RedrawRequested(window_id))
+            .await
+    }
+
+    /// Deliver a lifecycle event to the event loop's event handlers.
+    pub async fn post_lifecycle_event(&self, event: LifecycleEvent) {
+        let event = match event {
+            LifecycleEvent::Resumed => Event::Resumed,
+            LifecycleEvent::Suspended => Event::Suspended,
+        };
+
+        self.reactor.post_event::<()>(event).await
+    }
+
+    /// Run the pending operations that need to run on the event loop thread.
+    pub fn drain_ops<T: 'static>(&self, elwt: &EventLoopWindowTarget<T>) {
+        self.reactor.drain_loop_queue(elwt);
+    }
+
+    /// Wake up the timers that have expired.
+    ///
+    /// Returns the time at which the next timer expires, if any.
+    pub fn process_timers(&self) -> Option<Instant> {
+        let mut wakers = vec![];
+        let deadline = self.reactor.process_timers(&mut wakers);
+
+        for waker in wakers {
+            waker.wake();
+        }
+
+        deadline
+    }
+
+    /// Get the exit code, if an exit has been requested.
+    pub fn exit_requested(&self) -> Option<i32> {
+        self.reactor.exit_requested()
+    }
+}
+
+/// Options for choosing the control flow of the event loop.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FlowOptions {
+    /// The control flow to use when there is nothing else to do.
+    pub(crate) control_flow: ControlFlow,
+
+    /// The maximum amount of time to wait for before waking up.
+    pub(crate) max_wait: Option<Duration>,
+
+    /// Whether to poll instead of waiting for timers when the future is yielding.
+    pub(crate) prefer_poll: bool,
+
+    /// The maximum number of timers to fire per pass, or `None` for no limit.
+    pub(crate) timer_budget: Option<NonZeroUsize>,
+
+    /// The maximum number of event loop operations to run per pass, or `None` to use the
+    /// capacity of the operation queue.
+    pub(crate) op_budget: Option<NonZeroUsize>,
+}
+
+impl Default for FlowOptions {
+    fn default() -> Self {
+        Self {
+            control_flow: ControlFlow::Wait,
+            max_wait: None,
+            prefer_poll: true,
+            timer_budget: None,
+            op_budget: None,
+        }
+    }
+}
+
 pub(crate) struct ReactorWaker {
     /// The proxy used to wake up the event loop.
+    ///
+    /// Each thread keeps its own clone of this in `CACHED_PROXY`, so this is only locked the first
+    /// time a thread wakes up the event loop.
     proxy: Mutex<EventLoopProxy<Wakeup>>,
 
+    /// A unique ID for this waker, used to tell whether a cached proxy belongs to it.
+    id: usize,
+
     /// Whether or not we are already notified.
     notified: AtomicBool,
 
@@ -280,9 +481,56 @@ pub(crate) struct ReactorWaker {
     ///
     /// The reactor is awake when we don't
     awake: AtomicBool,
+
+    /// The instant that `wake_deadline` is measured from.
+    epoch: Instant,
+
+    /// When the event loop is scheduled to wake up, in nanoseconds since `epoch`.
+    ///
+    /// This is `WAKE_UNKNOWN` while the event loop is awake or polling, and `WAKE_NEVER` if it is
+    /// waiting indefinitely.
+    wake_deadline: AtomicU64,
 }
 
+/// The event loop's next wakeup is unknown, so every notification must go through.
+const WAKE_UNKNOWN: u64 = 0;
+
+/// The event loop is not scheduled to wake up on its own.
+const WAKE_NEVER: u64 = u64::MAX;
+
 impl ReactorWaker {
+    /// Notify the event loop that something needs to happen by `deadline`.
+    ///
+    /// If the event loop is already scheduled to wake up before then, this skips the proxy
+    /// entirely; the event loop handles it when it wakes up on its own.
+    pub(crate) fn notify_by(&self, deadline: Instant) {
+        let scheduled = self.wake_deadline.load(Ordering::SeqCst);
+        if scheduled != WAKE_UNKNOWN && self.nanos_since_epoch(deadline) >= scheduled {
+            return;
+        }
+
+        self.notify();
+    }
+
+    /// Record when the event loop is going to wake up, based on the control flow.
+    fn set_wake_deadline(&self, flow: ControlFlow) {
+        let deadline = match flow {
+            ControlFlow::Wait => WAKE_NEVER,
+            ControlFlow::WaitUntil(deadline) => self.nanos_since_epoch(deadline),
+            _ => WAKE_UNKNOWN,
+        };
+
+        self.wake_deadline.store(deadline, Ordering::SeqCst);
+    }
+
+    /// Convert an instant to nanoseconds since the epoch, avoiding the sentinel values.
+    fn nanos_since_epoch(&self, instant: Instant) -> u64 {
+        let nanos = instant.saturating_duration_since(self.epoch).as_nanos();
+        u64::try_from(nanos)
+            .unwrap_or(WAKE_NEVER - 1)
+            .clamp(WAKE_UNKNOWN + 1, WAKE_NEVER - 1)
+    }
+
     pub(crate) fn notify(&self) {
         // If we are already notified, don't notify again.
         if self.notified.swap(true, Ordering::SeqCst) {
@@ -294,15 +542,37 @@ impl ReactorWaker {
             return;
         }
 
-        // Wake up the reactor.
-        self.proxy
-            .lock()
-            .unwrap()
-            .send_event(Wakeup { _private: () })
-            .ok();
+        // Wake up the reactor, using this thread's proxy if we can.
+        let sent = CACHED_PROXY.try_with(|cached| {
+            let mut cached = cached.borrow_mut();
+            let proxy = match &mut *cached {
+                Some((id, proxy)) if *id == self.id => proxy,
+                cached => &mut cached.insert((self.id, self.proxy())).1,
+            };
+
+            proxy.send_event(Wakeup { _private: () }).ok();
+        });
+
+        // We're in a thread-local destructor; use the shared proxy.
+        if sent.is_err() {
+            self.proxy().send_event(Wakeup { _private: () }).ok();
+        }
+    }
+
+    /// Get a clone of the shared proxy.
+    fn proxy(&self) -> EventLoopProxy<Wakeup> {
+        self.proxy.lock().unwrap_or_else(|e| e.into_inner()).clone()
     }
 }
 
+/// The source of unique IDs for `ReactorWaker`.
+static NEXT_WAKER_ID: AtomicUsize = AtomicUsize::new(0);
+
+std::thread_local! {
+    /// This thread's clone of the most recently used event loop proxy.
+    static CACHED_PROXY: RefCell<Option<(usize, EventLoopProxy<Wakeup>)>> = const { RefCell::new(None) };
+}
+
 impl Wake for ReactorWaker {
     fn wake(self: Arc<Self>) {
         self.notify()
@@ -313,15 +583,15 @@ impl Wake for ReactorWaker {
     }
 }
 
-struct EventPostWaker {
+struct EventPostWaker<U> {
     /// The underlying reactor waker.
     reactor_waker: Arc<ReactorWaker>,
 
     /// The unparker for the notifier.
-    unparker: parking::Unparker,
+    unparker: U,
 }
 
-impl Wake for EventPostWaker {
+impl<U: Unparker> Wake for EventPostWaker<U> {
     fn wake(self: Arc<Self>) {
         self.reactor_waker.notify();
         self.unparker.unpark();
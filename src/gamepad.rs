@@ -0,0 +1,184 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Gamepad input, through `gilrs`.
+//!
+//! Gamepads are polled once per iteration of the event loop, as long as one of the gamepad
+//! handlers on [`EventLoopWindowTarget`] is being listened to. While that is the case, the event
+//! loop also wakes up regularly to poll them, so gamepad input arrives even if there are no other
+//! events.
+//!
+//! [`EventLoopWindowTarget`]: crate::event_loop::EventLoopWindowTarget
+
+use crate::event::ElementState;
+use crate::reactor::Reactor;
+use crate::sync::ThreadSafety;
+
+use std::cell::{Cell, RefCell};
+use std::sync::Arc;
+use std::task::{Wake, Waker};
+use std::time::Duration;
+
+use instant::Instant;
+
+#[doc(inline)]
+pub use gilrs::{Axis, Button, GamepadId};
+
+/// How often gamepads are polled while they are being listened to.
+const POLL_INTERVAL: Duration = Duration::from_millis(8);
+
+/// A button on a gamepad was pressed or released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GamepadButton {
+    /// The gamepad that the button belongs to.
+    pub gamepad: GamepadId,
+
+    /// The button.
+    pub button: Button,
+
+    /// Whether the button was pressed or released.
+    pub state: ElementState,
+}
+
+/// An axis on a gamepad changed its value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GamepadAxis {
+    /// The gamepad that the axis belongs to.
+    pub gamepad: GamepadId,
+
+    /// The axis.
+    pub axis: Axis,
+
+    /// The new value of the axis, between `-1.0` and `1.0`.
+    pub value: f32,
+}
+
+/// The state of the gamepad context on the event loop thread.
+enum Context {
+    /// The context hasn't been created yet.
+    Uninit,
+
+    /// The context could not be created, so gamepads are not available.
+    Unavailable,
+
+    /// The context is ready to be polled.
+    Ready(gilrs::Gilrs),
+}
+
+thread_local! {
+    /// The gamepad context, which is only used on the event loop thread.
+    static GILRS: RefCell<Context> = const { RefCell::new(Context::Uninit) };
+
+    /// The deadline of the timer that wakes up the event loop to poll gamepads.
+    static NEXT_POLL: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Deliver the gamepad events that have been received since the last poll.
+pub(crate) async fn poll_gamepads<TS: ThreadSafety>(reactor: &Reactor<TS>) {
+    let registration = &reactor.evl_registration;
+    if !registration.gamepad_connected.is_listening()
+        && !registration.gamepad_disconnected.is_listening()
+        && !registration.gamepad_button.is_listening()
+        && !registration.gamepad_axis.is_listening()
+    {
+        return;
+    }
+
+    // Don't hold the context while the handlers run.
+    let events = GILRS.with(|gilrs| {
+        let mut gilrs = gilrs.borrow_mut();
+
+        if let Context::Uninit = &*gilrs {
+            *gilrs = match gilrs::Gilrs::new() {
+                Ok(context) => Context::Ready(context),
+                Err(_) => Context::Unavailable,
+            };
+        }
+
+        let mut events = Vec::new();
+        if let Context::Ready(context) = &mut *gilrs {
+            while let Some(event) = context.next_event() {
+                events.push(event);
+            }
+        }
+
+        events
+    });
+
+    for event in events {
+        match event.event {
+            gilrs::EventType::Connected => {
+                let mut id = event.id;
+                registration.gamepad_connected.run_with(&mut id).await;
+            }
+            gilrs::EventType::Disconnected => {
+                let mut id = event.id;
+                registration.gamepad_disconnected.run_with(&mut id).await;
+            }
+            gilrs::EventType::ButtonPressed(button, _) => {
+                let mut button = GamepadButton {
+                    gamepad: event.id,
+                    button,
+                    state: ElementState::Pressed,
+                };
+                registration.gamepad_button.run_with(&mut button).await;
+            }
+            gilrs::EventType::ButtonReleased(button, _) => {
+                let mut button = GamepadButton {
+                    gamepad: event.id,
+                    button,
+                    state: ElementState::Released,
+                };
+                registration.gamepad_button.run_with(&mut button).await;
+            }
+            gilrs::EventType::AxisChanged(axis, value, _) => {
+                let mut axis = GamepadAxis {
+                    gamepad: event.id,
+                    axis,
+                    value,
+                };
+                registration.gamepad_axis.run_with(&mut axis).await;
+            }
+            _ => {}
+        }
+    }
+
+    schedule_poll(reactor);
+}
+
+/// Make sure that the event loop wakes up in time for the next poll.
+fn schedule_poll<TS: ThreadSafety>(reactor: &Reactor<TS>) {
+    /// The timer only needs to wake up the event loop, which polls the gamepads.
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let now = Instant::now();
+    NEXT_POLL.with(|next_poll| {
+        if matches!(next_poll.get(), Some(deadline) if deadline > now) {
+            return;
+        }
+
+        let deadline = now + POLL_INTERVAL;
+        let waker = Waker::from(Arc::new(NoopWaker));
+        reactor.insert_timer(deadline, POLL_INTERVAL / 2, &waker);
+        next_poll.set(Some(deadline));
+    });
+}
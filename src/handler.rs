@@ -20,15 +20,18 @@ Public License along with `async-winit`. If not, see <https://www.gnu.org/licens
 
 use std::cell::Cell;
 use std::future::{Future, IntoFuture};
-use std::mem;
 use std::ops::{Deref, DerefMut};
 use std::pin::Pin;
+use std::sync::atomic::Ordering;
 use std::task::{Context, Poll, Waker};
+use std::time::Duration;
 
 use futures_lite::{future, Stream};
+use instant::Instant;
 use slab::Slab;
 
 use crate::sync::{MutexGuard, ThreadSafety, __private::*};
+use crate::{DefaultThreadSafety, Timer};
 
 /// An event handler.
 ///
@@ -50,47 +53,149 @@ use crate::sync::{MutexGuard, ThreadSafety, __private::*};
 ///
 /// This type does not allocate unless you use any waiting functions; therefore, you only pay overhead
 /// for events that you use.
-pub struct Handler<T: Event, TS: ThreadSafety> {
+///
+/// When `TS` is `ThreadSafe`, the handler, its [`Waiter`]s and their [`HoldGuard`]s are `Send` and
+/// `Sync`, so events can be awaited from worker threads or multithreaded executors.
+pub struct Handler<T: Event, TS: ThreadSafety = DefaultThreadSafety> {
     /// State of the handler.
     ///
-    /// `State` is around sixteen words plus the size of `T::Clonable`, and we store around 25 of
+    /// `State` is around ten words plus the size of `T::Clonable`, and we store around 25 of
     /// them per instance of `window::Registration`. In the interest of not blowing up the size of
     /// `Registration`, we allocate this on the heap. Also, since sometimes the event will not ever
     /// be used, we use a `OnceLock` to avoid allocating the state until it is needed.
-    state: TS::OnceLock<Box<TS::Mutex<State<T>>>>,
+    state: TS::OnceLock<Box<LockedState<T, TS>>>,
+
+    /// The number of waiters and direct listeners.
+    ///
+    /// This lets us skip events that no one is listening to without touching the state. Waiters
+    /// are counted in the low 32 bits and direct listeners in the high 32 bits.
+    listening: TS::AtomicU64,
 }
 
-struct State<T: Event> {
+/// The state of a handler, behind its lock.
+type LockedState<T, TS> = <TS as __ThreadSafety>::Mutex<State<T, TS>>;
+
+struct State<T: Event, TS: ThreadSafety> {
     /// Listeners for the event.
     ///
     /// These form a linked list.
-    listeners: Slab<Listener>,
+    listeners: Slab<Listener<TS>>,
 
     /// List of direct listeners.
-    directs: Vec<DirectListener<T>>,
+    ///
+    /// Most handlers never have direct listeners, so this is only allocated once one is added. The
+    /// extra box keeps it to a single word until then.
+    #[allow(clippy::box_collection)]
+    directs: Option<Box<Vec<DirectListener<T>>>>,
 
     /// The head and tail of the linked list.
-    head_and_tail: Option<(usize, usize)>,
+    head_and_tail: Option<(Index, Index)>,
 
     /// The top-level task waiting for this task to finish.
     waker: Option<Waker>,
 
     /// The currently active event.
+    ///
+    /// The last listener in the chain takes this instead of cloning it, so it may be `None` while
+    /// the event is still being delivered.
     instance: Option<T::Clonable>,
+
+    /// Whether or not an event is being delivered to the listeners.
+    active: bool,
 }
 
-type DirectListener<T> =
-    Box<dyn FnMut(&mut <T as Event>::Unique<'_>) -> DirectFuture + Send + 'static>;
-type DirectFuture = Pin<Box<dyn Future<Output = bool> + Send + 'static>>;
+/// The amount that a `Waiter` adds to `Handler::listening`.
+const ONE_WAITER: u64 = 1;
+
+/// The amount that a direct listener adds to `Handler::listening`.
+const ONE_DIRECT: u64 = 1 << 32;
+
+/// The bits of `Handler::listening` that count `Waiter`s.
+const WAITER_MASK: u64 = ONE_DIRECT - 1;
+
+/// A lock on the state of a handler.
+type StateGuard<'a, T, TS> = MutexGuard<'a, State<T, TS>, TS>;
+
+/// The index of a listener in the handler's slab.
+///
+/// There will never be more than `u32::MAX` listeners on one handler, and using a smaller index
+/// keeps `Listener` and `State` compact.
+type Index = u32;
+
+/// A closure that is called directly by the event handler.
+enum DirectListener<T: Event> {
+    /// A synchronous closure, which can be called without allocating.
+    Sync(Box<SyncDirect<T>>),
+
+    /// An asynchronous closure.
+    Async(Box<dyn AsyncDirect<T>>),
+}
+
+/// A synchronous direct listener.
+type SyncDirect<T> = dyn FnMut(&mut <T as Event>::Unique<'_>) -> bool + Send + 'static;
+
+/// An asynchronous direct listener.
+trait AsyncDirect<T: Event>: Send {
+    /// Start running the listener for an event.
+    ///
+    /// The future is stored inside of the listener, so this doesn't allocate.
+    fn start(&mut self, event: &mut T::Unique<'_>) -> Pin<&mut (dyn Future<Output = bool> + Send)>;
+
+    /// Drop the future once it has completed.
+    fn finish(&mut self);
+}
+
+/// An asynchronous direct listener that reuses the storage for its future across events.
+struct ReusableDirect<F, Fut> {
+    /// The closure that creates the future.
+    f: F,
+
+    /// The storage for the future.
+    ///
+    /// This is allocated once, when the listener is registered.
+    future: Pin<Box<Option<Fut>>>,
+}
+
+impl<T, F, Fut> AsyncDirect<T> for ReusableDirect<F, Fut>
+where
+    T: Event,
+    F: FnMut(&mut T::Unique<'_>) -> Fut + Send,
+    Fut: Future<Output = bool> + Send,
+{
+    fn start(&mut self, event: &mut T::Unique<'_>) -> Pin<&mut (dyn Future<Output = bool> + Send)> {
+        self.future.set(Some((self.f)(event)));
+        self.future.as_mut().as_pin_mut().unwrap()
+    }
+
+    fn finish(&mut self) {
+        self.future.set(None);
+    }
+}
 
 impl<T: Event, TS: ThreadSafety> Handler<T, TS> {
     pub(crate) fn new() -> Self {
         Self {
             state: TS::OnceLock::new(),
+            listening: TS::AtomicU64::new(0),
         }
     }
 
+    /// Whether or not anything is listening to this event.
+    pub(crate) fn is_listening(&self) -> bool {
+        self.listening.load(Ordering::Acquire) != 0
+    }
+
+    /// Whether or not any `Waiter`s are listening to this event.
+    fn has_waiters(&self) -> bool {
+        self.listening.load(Ordering::Acquire) & WAITER_MASK != 0
+    }
+
     pub(crate) async fn run_with(&self, event: &mut T::Unique<'_>) {
+        // If no one is listening, don't bother with the state.
+        if !self.is_listening() {
+            return;
+        }
+
         // If the state hasn't been created yet, return.
         let state = match self.state.get() {
             Some(state) => state,
@@ -103,6 +208,11 @@ impl<T: Event, TS: ThreadSafety> Handler<T, TS> {
             return;
         }
 
+        // If only direct listeners are registered, don't bother downgrading the event.
+        if !self.has_waiters() {
+            return;
+        }
+
         // Set up the listeners to run.
         {
             let state = state_lock.get_or_insert_with(|| state.lock().unwrap());
@@ -115,6 +225,7 @@ impl<T: Event, TS: ThreadSafety> Handler<T, TS> {
 
             // Set up the state.
             state.instance = Some(T::downgrade(event));
+            state.active = true;
 
             // Notify the first entry in the list.
             if let Some(waker) = state.notify(head) {
@@ -128,11 +239,13 @@ impl<T: Event, TS: ThreadSafety> Handler<T, TS> {
 
             // If there are no listeners, return.
             if state.head_and_tail.is_none() {
+                state.instance = None;
+                state.active = false;
                 return Poll::Ready(());
             }
 
             // If the waking is over, return.
-            if state.instance.is_none() {
+            if !state.active {
                 return Poll::Ready(());
             }
 
@@ -152,36 +265,40 @@ impl<T: Event, TS: ThreadSafety> Handler<T, TS> {
 
     async fn run_direct_listeners(
         &self,
-        state: &mut Option<MutexGuard<'_, State<T>, TS>>,
+        state: &mut Option<StateGuard<'_, T, TS>>,
         event: &mut T::Unique<'_>,
     ) -> bool {
         /// Guard to restore direct listeners event a
         struct RestoreDirects<'a, T: Event, TS: ThreadSafety> {
             state: &'a Handler<T, TS>,
-            directs: Vec<DirectListener<T>>,
+            #[allow(clippy::box_collection)]
+            directs: Option<Box<Vec<DirectListener<T>>>>,
         }
 
         impl<T: Event, TS: ThreadSafety> Drop for RestoreDirects<'_, T, TS> {
             fn drop(&mut self) {
-                let mut directs = mem::take(&mut self.directs);
-                self.state
-                    .state()
-                    .lock()
-                    .unwrap()
-                    .directs
-                    .append(&mut directs);
+                let mut directs = match self.directs.take() {
+                    Some(directs) => directs,
+                    None => return,
+                };
+
+                let mut state = self.state.state().lock().unwrap();
+                match &mut state.directs {
+                    Some(added) => added.append(&mut directs),
+                    None => state.directs = Some(directs),
+                }
             }
         }
 
         // If there are not indirect listeners, skip this part entirely.
         let state_ref = state.as_mut().unwrap();
-        if state_ref.directs.is_empty() {
+        if state_ref.directs.is_none() {
             return false;
         }
 
         // Take out the direct listeners.
         let mut directs = RestoreDirects {
-            directs: mem::take(&mut state_ref.directs),
+            directs: state_ref.directs.take(),
             state: self,
         };
 
@@ -189,8 +306,18 @@ impl<T: Event, TS: ThreadSafety> Handler<T, TS> {
         *state = None;
 
         // Iterate over the direct listeners.
-        for direct in &mut directs.directs {
-            if direct(event).await {
+        let listeners = directs.directs.as_mut().unwrap();
+        for direct in listeners.iter_mut() {
+            let handled = match direct {
+                DirectListener::Sync(f) => f(event),
+                DirectListener::Async(f) => {
+                    let handled = f.start(event).await;
+                    f.finish();
+                    handled
+                }
+            };
+
+            if handled {
                 return true;
             }
         }
@@ -209,19 +336,32 @@ impl<T: Event, TS: ThreadSafety> Handler<T, TS> {
         F: FnMut(&mut T::Unique<'_>) -> Fut + Send + 'static,
     >(
         &self,
-        mut f: F,
+        f: F,
     ) {
-        let mut state = self.state().lock().unwrap();
-        state.directs.push(Box::new(move |u| Box::pin(f(u))))
+        self.push_direct(DirectListener::Async(Box::new(ReusableDirect {
+            f,
+            future: Box::pin(None::<Fut>),
+        })));
     }
 
     /// Register a closure be called when the event is received.
-    pub fn wait_direct(&self, mut f: impl FnMut(&mut T::Unique<'_>) -> bool + Send + 'static) {
-        self.wait_direct_async(move |u| std::future::ready(f(u)))
+    pub fn wait_direct(&self, f: impl FnMut(&mut T::Unique<'_>) -> bool + Send + 'static) {
+        self.push_direct(DirectListener::Sync(Box::new(f)));
+    }
+
+    /// Add a direct listener.
+    fn push_direct(&self, direct: DirectListener<T>) {
+        self.state()
+            .lock()
+            .unwrap()
+            .directs
+            .get_or_insert_with(Default::default)
+            .push(direct);
+        self.listening.fetch_add(ONE_DIRECT, Ordering::AcqRel);
     }
 
     /// Get the inner state.
-    fn state(&self) -> &TS::Mutex<State<T>> {
+    fn state(&self) -> &LockedState<T, TS> {
         self.state
             .get_or_init(|| Box::new(TS::Mutex::new(State::new())))
     }
@@ -239,12 +379,15 @@ impl<'a, T: Event, TS: ThreadSafety> IntoFuture for &'a Handler<T, TS> {
 }
 
 /// Waits for an event to be received.
-pub struct Waiter<'a, T: Event, TS: ThreadSafety> {
+pub struct Waiter<'a, T: Event, TS: ThreadSafety = DefaultThreadSafety> {
     /// The event handler.
     handler: &'a Handler<T, TS>,
 
     /// The index of our listener.
-    index: usize,
+    index: Index,
+
+    /// The signal used to notify our listener.
+    signal: TS::Rc<Signal<TS>>,
 }
 
 impl<T: Event, TS: ThreadSafety> Unpin for Waiter<'_, T, TS> {}
@@ -256,12 +399,62 @@ impl<'a, T: Event, TS: ThreadSafety> Waiter<'a, T, TS> {
         let state = handler.state();
 
         // Insert the listener.
-        let index = state.lock().unwrap().insert();
-        Self { handler, index }
+        let signal = TS::Rc::new(Signal::new());
+        let index = state.lock().unwrap().insert(signal.clone());
+        handler.listening.fetch_add(ONE_WAITER, Ordering::AcqRel);
+        Self {
+            handler,
+            index,
+            signal,
+        }
+    }
+
+    /// Poll for a notification.
+    ///
+    /// The lock is only taken once we've been notified, so waiting on an event doesn't contend
+    /// with the thread that is delivering it.
+    fn poll_notified(&self, cx: &mut Context<'_>) -> Poll<(StateGuard<'a, T, TS>, T::Clonable)> {
+        // See if we are notified, registering our waker if we aren't.
+        if !self.signal.is_notified() {
+            self.signal.waker.register(cx.waker());
+
+            // Check again in case we were notified while registering.
+            if !self.signal.is_notified() {
+                return Poll::Pending;
+            }
+        }
+
+        let mut state = self.handler.state().lock().unwrap();
+
+        // Take out the notification.
+        self.signal.notified.store(0, Ordering::Release);
+
+        // If we're the last listener in the chain, no one else needs the event, so we can take it
+        // instead of cloning it. With a single listener, this is the only copy that is made.
+        let is_tail = state.listeners[self.index as usize].next.get().is_none();
+        let event = if is_tail {
+            state.instance.take()
+        } else {
+            state.instance.clone()
+        };
+
+        match event {
+            Some(event) => Poll::Ready((state, event)),
+            None => {
+                // We were added to the chain after the previous tail took the event, so we
+                // can't receive it. Pass the notification along so the delivery can finish.
+                if state.active {
+                    self.notify_next(state);
+                }
+
+                self.signal.waker.register(cx.waker());
+                Poll::Pending
+            }
+        }
     }
 
-    fn notify_next(&mut self, mut state: MutexGuard<'_, State<T>, TS>) {
-        if let Some(next) = state.listeners[self.index].next.get() {
+    fn notify_next(&self, mut state: StateGuard<'_, T, TS>) {
+        if let Some(next) = state.listeners[self.index as usize].next.get() {
             // Notify the next listener.
             if let Some(waker) = state.notify(next) {
                 waker.wake();
@@ -269,6 +462,7 @@ impl<'a, T: Event, TS: ThreadSafety> Waiter<'a, T, TS> {
         } else {
             // We're done with the chain, notify the top-level task.
             state.instance = None;
+            state.active = false;
             if let Some(waker) = state.waker.take() {
                 waker.wake();
             }
@@ -278,31 +472,41 @@ impl<'a, T: Event, TS: ThreadSafety> Waiter<'a, T, TS> {
     /// Wait for a guard that prevents the event from moving on.
     pub async fn hold(&mut self) -> HoldGuard<'_, 'a, T, TS> {
         // Wait for the event.
-        let event = future::poll_fn(|cx| {
-            let mut state = self.handler.state().lock().unwrap();
-
-            // See if we are notified.
-            if state.take_notification(self.index) {
-                let event = match state.instance.clone() {
-                    Some(event) => event,
-                    None => return Poll::Pending,
-                };
-
-                // Return the event.
-                return Poll::Ready(event);
-            }
-
-            // Register the waker and sleep.
-            state.register_waker(self.index, cx.waker());
-            Poll::Pending
-        })
-        .await;
+        let event = future::poll_fn(|cx| self.poll_notified(cx).map(|(_, event)| event)).await;
 
         HoldGuard {
             waiter: self,
             event: Some(event),
         }
     }
+
+    /// Only emit an event once no new events have been received for the given duration.
+    ///
+    /// This is useful for events that come in bursts, where only the final event matters. For
+    /// instance, it can be used to save the window size once the user has stopped resizing it.
+    pub fn debounce(self, duration: Duration) -> Debounce<'a, T, TS> {
+        Debounce {
+            waiter: self,
+            timer: Timer::never(),
+            duration,
+            pending: None,
+            finished: false,
+        }
+    }
+
+    /// Emit at most one event per the given duration.
+    ///
+    /// The first event is emitted immediately. Events received during the following `duration`
+    /// are dropped, except for the last one, which is emitted once `duration` has elapsed.
+    pub fn throttle(self, duration: Duration) -> Throttle<'a, T, TS> {
+        Throttle {
+            waiter: self,
+            timer: Timer::never(),
+            duration,
+            pending: None,
+            finished: false,
+        }
+    }
 }
 
 impl<T: Event, TS: ThreadSafety> Future for Waiter<'_, T, TS> {
@@ -320,27 +524,17 @@ impl<T: Event, TS: ThreadSafety> Future for Waiter<'_, T, TS> {
 impl<T: Event, TS: ThreadSafety> Stream for Waiter<'_, T, TS> {
     type Item = T::Clonable;
 
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let mut state = self.handler.state.get().unwrap().lock().unwrap();
-
-        // See if we are notified.
-        if state.take_notification(self.index) {
-            let event = match state.instance.clone() {
-                Some(event) => event,
-                None => return Poll::Pending,
-            };
-
-            // Notify the next listener in the chain.
-            self.notify_next(state);
-
-            // Return the event.
-            return Poll::Ready(Some(event));
-        }
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let (state, event) = match self.poll_notified(cx) {
+            Poll::Ready(ready) => ready,
+            Poll::Pending => return Poll::Pending,
+        };
 
-        // Register the waker.
-        state.register_waker(self.index, cx.waker());
+        // Notify the next listener in the chain.
+        self.notify_next(state);
 
-        Poll::Pending
+        // Return the event.
+        Poll::Ready(Some(event))
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -353,17 +547,142 @@ impl<'a, T: Event, TS: ThreadSafety> Drop for Waiter<'a, T, TS> {
         let mut state = self.handler.state().lock().unwrap();
 
         // Remove the listener.
-        let listener = state.remove(self.index);
+        state.remove(self.index);
+        self.handler
+            .listening
+            .fetch_sub(ONE_WAITER, Ordering::AcqRel);
 
         // Notify the next listener if we are notified.
-        if listener.notified.get() {
+        if self.signal.is_notified() {
             self.notify_next(state);
         }
     }
 }
 
+/// A stream that only emits an event once no new events have been received for a while.
+///
+/// This is returned by [`Waiter::debounce`].
+pub struct Debounce<'a, T: Event, TS: ThreadSafety = DefaultThreadSafety> {
+    /// The underlying waiter.
+    waiter: Waiter<'a, T, TS>,
+
+    /// The timer that fires once the events have settled.
+    timer: Timer<TS>,
+
+    /// The amount of time to wait for the events to settle.
+    duration: Duration,
+
+    /// The last event we received.
+    pending: Option<T::Clonable>,
+
+    /// The underlying waiter has finished.
+    finished: bool,
+}
+
+impl<T: Event, TS: ThreadSafety> Unpin for Debounce<'_, T, TS> {}
+
+impl<T: Event, TS: ThreadSafety> Stream for Debounce<'_, T, TS> {
+    type Item = T::Clonable;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        // Take all of the events that are available, restarting the timer for each one.
+        while !this.finished {
+            match Pin::new(&mut this.waiter).poll_next(cx) {
+                Poll::Ready(Some(event)) => {
+                    this.pending = Some(event);
+                    this.timer.set_after(this.duration);
+                }
+                Poll::Ready(None) => this.finished = true,
+                Poll::Pending => break,
+            }
+        }
+
+        // Flush the last event if there won't be any more.
+        if this.finished {
+            this.timer.set_never();
+            return Poll::Ready(this.pending.take());
+        }
+
+        // Emit the last event once the events have settled.
+        if this.pending.is_some() && Pin::new(&mut this.timer).poll(cx).is_ready() {
+            return Poll::Ready(this.pending.take());
+        }
+
+        Poll::Pending
+    }
+}
+
+/// A stream that emits at most one event per a given duration.
+///
+/// This is returned by [`Waiter::throttle`].
+pub struct Throttle<'a, T: Event, TS: ThreadSafety = DefaultThreadSafety> {
+    /// The underlying waiter.
+    waiter: Waiter<'a, T, TS>,
+
+    /// The timer that fires once the next event can be emitted.
+    timer: Timer<TS>,
+
+    /// The minimum amount of time between two events.
+    duration: Duration,
+
+    /// The last event we received while throttled.
+    pending: Option<T::Clonable>,
+
+    /// The underlying waiter has finished.
+    finished: bool,
+}
+
+impl<T: Event, TS: ThreadSafety> Unpin for Throttle<'_, T, TS> {}
+
+impl<T: Event, TS: ThreadSafety> Stream for Throttle<'_, T, TS> {
+    type Item = T::Clonable;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        while !this.finished {
+            match Pin::new(&mut this.waiter).poll_next(cx) {
+                Poll::Ready(Some(event)) => {
+                    let throttled = this
+                        .timer
+                        .deadline()
+                        .map_or(false, |deadline| deadline > Instant::now());
+
+                    if throttled {
+                        // Keep the event around until the throttle period is over.
+                        this.pending = Some(event);
+                    } else {
+                        // This event is newer than anything we were holding onto.
+                        this.pending = None;
+                        this.timer.set_after(this.duration);
+                        return Poll::Ready(Some(event));
+                    }
+                }
+                Poll::Ready(None) => this.finished = true,
+                Poll::Pending => break,
+            }
+        }
+
+        // Flush the last event if there won't be any more.
+        if this.finished {
+            this.timer.set_never();
+            return Poll::Ready(this.pending.take());
+        }
+
+        // Emit the last event once the throttle period is over.
+        if this.pending.is_some() && Pin::new(&mut this.timer).poll(cx).is_ready() {
+            this.timer.set_after(this.duration);
+            return Poll::Ready(this.pending.take());
+        }
+
+        Poll::Pending
+    }
+}
+
 /// A guard that notifies the next listener when dropped.
-pub struct HoldGuard<'waiter, 'handler, T: Event, TS: ThreadSafety> {
+pub struct HoldGuard<'waiter, 'handler, T: Event, TS: ThreadSafety = DefaultThreadSafety> {
     /// The waiter.
     waiter: &'waiter mut Waiter<'handler, T, TS>,
 
@@ -400,35 +719,36 @@ impl<T: Event, TS: ThreadSafety> Drop for HoldGuard<'_, '_, T, TS> {
     }
 }
 
-impl<T: Event> State<T> {
+impl<T: Event, TS: ThreadSafety> State<T, TS> {
     /// Get a fresh state instance.
     fn new() -> Self {
         Self {
             listeners: Slab::new(),
-            directs: Vec::new(),
+            directs: None,
             head_and_tail: None,
             waker: None,
             instance: None,
+            active: false,
         }
     }
 
     /// Insert a new listener into the list.
-    fn insert(&mut self) -> usize {
+    fn insert(&mut self, signal: TS::Rc<Signal<TS>>) -> Index {
         // Create the listener.
         let listener = Listener {
             next: Cell::new(None),
             prev: Cell::new(self.head_and_tail.map(|(_, tail)| tail)),
-            waker: Cell::new(None),
-            notified: Cell::new(false),
+            signal,
         };
 
         // Insert the listener into the list.
         let index = self.listeners.insert(listener);
+        let index = Index::try_from(index).expect("too many listeners on one handler");
 
         // Update the head and tail.
         match &mut self.head_and_tail {
             Some((_head, tail)) => {
-                self.listeners[*tail].next.set(Some(index));
+                self.listeners[*tail as usize].next.set(Some(index));
                 *tail = index;
             }
 
@@ -441,9 +761,9 @@ impl<T: Event> State<T> {
     }
 
     /// Remove a listener from the list.
-    fn remove(&mut self, index: usize) -> Listener {
+    fn remove(&mut self, index: Index) -> Listener<TS> {
         // Get the listener.
-        let listener = self.listeners.remove(index);
+        let listener = self.listeners.remove(index as usize);
 
         // Update the head and tail.
         match &mut self.head_and_tail {
@@ -462,62 +782,65 @@ impl<T: Event> State<T> {
 
         // Update the next and previous listeners.
         if let Some(next) = listener.next.get() {
-            self.listeners[next].prev.set(listener.prev.get());
+            self.listeners[next as usize].prev.set(listener.prev.get());
         }
 
         if let Some(prev) = listener.prev.get() {
-            self.listeners[prev].next.set(listener.next.get());
+            self.listeners[prev as usize].next.set(listener.next.get());
         }
 
         listener
     }
 
-    /// Take out the notification.
-    fn take_notification(&mut self, index: usize) -> bool {
-        self.listeners[index].notified.replace(false)
-    }
-
-    /// Register a waker.
-    fn register_waker(&mut self, index: usize, waker: &Waker) {
-        let listener = &mut self.listeners[index];
-
-        // If the listener's waker is the same as ours, no need to clone.
-        let current_waker = listener.waker.take();
-        match current_waker {
-            Some(current_waker) if current_waker.will_wake(waker) => {
-                listener.waker.replace(Some(current_waker));
-            }
-            _ => {
-                listener.waker.replace(Some(waker.clone()));
-            }
-        }
-    }
-
     /// Notify the listener.
-    fn notify(&mut self, index: usize) -> Option<Waker> {
+    fn notify(&mut self, index: Index) -> Option<Waker> {
+        let signal = &self.listeners[index as usize].signal;
+
         // If the listener is already notified, return.
-        if self.listeners[index].notified.replace(true) {
+        if signal.notified.swap(1, Ordering::AcqRel) != 0 {
             return None;
         }
 
         // Return the waker.
-        self.listeners[index].waker.replace(None)
+        signal.waker.take()
     }
 }
 
 /// A registered listener in the event handler.
-struct Listener {
+struct Listener<TS: ThreadSafety> {
     /// The next listener in the list.
-    next: Cell<Option<usize>>,
+    next: Cell<Option<Index>>,
 
     /// The previous listener in the list.
-    prev: Cell<Option<usize>>,
+    prev: Cell<Option<Index>>,
 
-    /// The waker for the listener.
-    waker: Cell<Option<Waker>>,
+    /// The signal shared with the listener's `Waiter`.
+    signal: TS::Rc<Signal<TS>>,
+}
 
+/// Notification state shared between a listener and its `Waiter`.
+///
+/// This lives outside of the handler's lock, so that a `Waiter` can check for and wait on
+/// notifications without taking the lock.
+struct Signal<TS: ThreadSafety> {
     /// Whether or not this listener is notified.
-    notified: Cell<bool>,
+    notified: TS::AtomicUsize,
+
+    /// The waker for the listener.
+    waker: TS::AtomicWaker,
+}
+
+impl<TS: ThreadSafety> Signal<TS> {
+    fn new() -> Self {
+        Self {
+            notified: TS::AtomicUsize::new(0),
+            waker: TS::AtomicWaker::new(),
+        }
+    }
+
+    fn is_notified(&self) -> bool {
+        self.notified.load(Ordering::Acquire) != 0
+    }
 }
 
 /// The type of event that can be sent over a [`Handler`].
@@ -544,3 +867,111 @@ impl<F: FnMut()> Drop for CallOnDrop<F> {
         (self.0)();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reactor::with_test_reactor;
+    use crate::ThreadUnsafe;
+
+    use futures_lite::{future, StreamExt};
+    use std::thread;
+
+    const PERIOD: Duration = Duration::from_millis(20);
+
+    /// Post an event, and poll the stream once while the event is being delivered.
+    fn post<S: Stream + Unpin>(
+        handler: &Handler<u32, ThreadUnsafe>,
+        stream: &mut S,
+        mut event: u32,
+    ) -> Option<S::Item> {
+        let (_, item) = future::block_on(future::zip(
+            handler.run_with(&mut event),
+            future::poll_once(stream.next()),
+        ));
+
+        item.flatten()
+    }
+
+    #[test]
+    fn throttle_drops_older_pending_event() {
+        with_test_reactor(|_| {
+            let handler = Handler::<u32, ThreadUnsafe>::new();
+            let mut throttle = handler.wait().throttle(PERIOD);
+
+            // The first event goes through, and the second one is held back.
+            assert_eq!(post(&handler, &mut throttle, 1), Some(1));
+            assert_eq!(post(&handler, &mut throttle, 2), None);
+
+            // Once the period is over, a newer event goes through immediately.
+            thread::sleep(PERIOD * 2);
+            assert_eq!(post(&handler, &mut throttle, 3), Some(3));
+
+            // The older event must not be emitted after the newer one.
+            thread::sleep(PERIOD * 2);
+            assert_eq!(future::block_on(future::poll_once(throttle.next())), None);
+        });
+    }
+
+    #[test]
+    fn throttle_emits_held_event_after_period() {
+        with_test_reactor(|_| {
+            let handler = Handler::<u32, ThreadUnsafe>::new();
+            let mut throttle = handler.wait().throttle(PERIOD);
+
+            assert_eq!(post(&handler, &mut throttle, 1), Some(1));
+            assert_eq!(post(&handler, &mut throttle, 2), None);
+            assert_eq!(post(&handler, &mut throttle, 3), None);
+
+            // Only the latest held event is emitted once the period is over.
+            thread::sleep(PERIOD * 2);
+            assert_eq!(
+                future::block_on(future::poll_once(throttle.next())),
+                Some(Some(3))
+            );
+            assert_eq!(future::block_on(future::poll_once(throttle.next())), None);
+        });
+    }
+
+    #[test]
+    fn debounce_emits_last_event_once_settled() {
+        with_test_reactor(|_| {
+            let handler = Handler::<u32, ThreadUnsafe>::new();
+            let mut debounce = handler.wait().debounce(PERIOD);
+
+            assert_eq!(post(&handler, &mut debounce, 1), None);
+            assert_eq!(post(&handler, &mut debounce, 2), None);
+
+            thread::sleep(PERIOD * 2);
+            assert_eq!(
+                future::block_on(future::poll_once(debounce.next())),
+                Some(Some(2))
+            );
+            assert_eq!(future::block_on(future::poll_once(debounce.next())), None);
+        });
+    }
+
+    #[test]
+    fn debounce_restarts_on_every_event() {
+        const SETTLE: Duration = Duration::from_millis(100);
+
+        with_test_reactor(|_| {
+            let handler = Handler::<u32, ThreadUnsafe>::new();
+            let mut debounce = handler.wait().debounce(SETTLE);
+
+            assert_eq!(post(&handler, &mut debounce, 1), None);
+            thread::sleep(SETTLE * 3 / 5);
+            assert_eq!(post(&handler, &mut debounce, 2), None);
+
+            // The first event would have settled by now, but the second one restarted the timer.
+            thread::sleep(SETTLE * 3 / 5);
+            assert_eq!(future::block_on(future::poll_once(debounce.next())), None);
+
+            thread::sleep(SETTLE);
+            assert_eq!(
+                future::block_on(future::poll_once(debounce.next())),
+                Some(Some(2))
+            );
+        });
+    }
+}
@@ -0,0 +1,113 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Run an I/O reactor next to the event loop.
+//!
+//! The event loop thread is busy running `winit`, so it can't also wait on sockets and other I/O
+//! sources. [`spawn_reactor_thread`] starts a background thread that runs the `async-io` reactor,
+//! the same one used by `smol`, along with an executor. Networking tasks can be spawned onto that
+//! thread and their results awaited from the event loop.
+//!
+//! ```no_run
+//! use async_winit::event_loop::EventLoop;
+//! use async_winit::io::spawn_reactor_thread;
+//! use async_winit::ThreadUnsafe;
+//!
+//! use std::net::TcpStream;
+//!
+//! let io = spawn_reactor_thread().unwrap();
+//!
+//! EventLoop::<ThreadUnsafe>::new().block_on(async move {
+//!     let stream = io
+//!         .spawn(async_io::Async::<TcpStream>::connect(([127, 0, 0, 1], 8080)))
+//!         .await;
+//!
+//!     println!("connected: {}", stream.is_ok());
+//! #   futures_lite::future::pending().await
+//! });
+//! ```
+
+use crate::Task;
+
+use async_channel::{bounded, Sender};
+use async_executor::Executor;
+
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::sync::Arc;
+use std::thread;
+
+/// Start a background thread that runs an I/O reactor.
+///
+/// The thread runs until every handle to it has been dropped. Tasks that haven't completed by then
+/// are cancelled.
+pub fn spawn_reactor_thread() -> io::Result<ReactorThread> {
+    let executor = Arc::new(Executor::new());
+    let (shutdown, stop) = bounded::<()>(1);
+
+    thread::Builder::new()
+        .name("async-winit-io".into())
+        .spawn({
+            let executor = executor.clone();
+            move || {
+                // Nothing is ever sent, so this runs until the sender is dropped.
+                async_io::block_on(executor.run(async move {
+                    stop.recv().await.ok();
+                }))
+            }
+        })?;
+
+    Ok(ReactorThread {
+        executor,
+        _shutdown: shutdown,
+    })
+}
+
+/// A handle to a thread running an I/O reactor.
+///
+/// This is created by [`spawn_reactor_thread`].
+#[derive(Clone)]
+pub struct ReactorThread {
+    /// The executor running on the thread.
+    executor: Arc<Executor<'static>>,
+
+    /// Closed once every handle is dropped, which stops the thread.
+    _shutdown: Sender<()>,
+}
+
+impl fmt::Debug for ReactorThread {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReactorThread").finish_non_exhaustive()
+    }
+}
+
+impl ReactorThread {
+    /// Spawn a future onto the reactor thread.
+    ///
+    /// The returned [`Task`] can be awaited from any thread, including the event loop thread. The
+    /// future is cancelled if the task is dropped; use [`Task::detach`] to let it run in the
+    /// background.
+    pub fn spawn<F>(&self, future: F) -> Task<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.executor.spawn(future)
+    }
+}
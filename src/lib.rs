@@ -25,10 +25,34 @@ mod reactor;
 mod sync;
 mod timer;
 
+#[cfg(feature = "tokio")]
+mod tokio_compat;
+
+#[cfg(all(test, loom, feature = "thread_safe"))]
+mod loom_tests;
+
+#[cfg(feature = "__bench")]
+#[doc(hidden)]
+pub mod bench;
+
+// Modules unique to `async-winit`.
+pub mod app;
+#[cfg(feature = "arboard")]
+pub mod clipboard;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+#[cfg(feature = "io")]
+pub mod io;
+#[cfg(all(feature = "muda", any(windows, macos_platform)))]
+pub mod menu;
+pub mod time;
+
 // Modules we need to change for `async-winit`.
 pub mod event_loop;
 pub mod filter;
+pub mod metrics;
 pub mod platform;
+pub mod watchdog;
 pub mod window;
 
 pub mod event {
@@ -39,15 +63,21 @@ pub mod event {
         AxisMotion, CursorMoved, KeyboardInput, MouseInput, MouseWheel, ScaleFactor,
         ScaleFactorChanged, ScaleFactorChanging, TouchpadMagnify, TouchpadPressure, TouchpadRotate,
     };
+
+    #[cfg(feature = "keyboard-types")]
+    pub use super::window::keyboard::keyboard_modifiers;
 }
 
 // Modules that can just be re-exported in `async-winit`.
 #[doc(inline)]
 pub use winit::{dpi, error, monitor};
 
-pub use handler::{Event, Handler, Waiter};
-pub use sync::{DefaultThreadSafety, ThreadSafety, ThreadUnsafe};
-pub use timer::Timer;
+pub use handler::{Debounce, Event, Handler, HoldGuard, Throttle, Waiter};
+pub use sync::{DefaultThreadSafety, MaybeSend, MaybeSync, ThreadSafety, ThreadUnsafe};
+pub use timer::{TickPolicy, Timer};
 
 #[cfg(feature = "thread_safe")]
 pub use sync::ThreadSafe;
+
+#[cfg(feature = "executor")]
+pub use async_executor::Task;
@@ -0,0 +1,111 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Model-checked tests for the thread-safe synchronization primitives.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" cargo test --features thread_safe --release loom`.
+
+use crate::handler::Handler;
+use crate::sync::__private::{Parker, Unparker, __ThreadSafety};
+use crate::ThreadSafe;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Wake, Waker};
+
+use loom::future::block_on;
+use loom::thread;
+
+/// Get a handler that lives for the rest of the model.
+fn handler<T: Clone + Send + 'static>() -> &'static Handler<T, ThreadSafe> {
+    Box::leak(Box::new(Handler::new()))
+}
+
+#[test]
+fn notify_chain() {
+    loom::model(|| {
+        let handler = handler::<u32>();
+
+        // Register the listeners before the event is posted.
+        let listeners = (0..2)
+            .map(|_| handler.wait())
+            .map(|waiter| thread::spawn(move || block_on(waiter)))
+            .collect::<Vec<_>>();
+
+        block_on(handler.run_with(&mut 7));
+
+        for listener in listeners {
+            assert_eq!(listener.join().unwrap(), 7);
+        }
+    });
+}
+
+#[test]
+fn notify_chain_dropped_listener() {
+    loom::model(|| {
+        let handler = handler::<u32>();
+
+        let first = handler.wait();
+        let second = handler.wait();
+
+        // One listener receives the event, the other one goes away while it is in flight.
+        let received = thread::spawn(move || block_on(first));
+        let dropped = thread::spawn(move || drop(second));
+
+        block_on(handler.run_with(&mut 7));
+
+        assert_eq!(received.join().unwrap(), 7);
+        dropped.join().unwrap();
+    });
+}
+
+#[test]
+fn wake_park_handshake() {
+    /// Unparks the event loop, like the filter's `EventPostWaker`.
+    struct Unpark<U>(U);
+
+    impl<U: Unparker> Wake for Unpark<U> {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    loom::model(|| {
+        let handler = handler::<()>();
+        let mut waiter = handler.wait();
+
+        let poster = thread::spawn(move || block_on(handler.run_with(&mut ())));
+
+        // Block the same way the filter does while an event is being processed.
+        let (parker, unparker) = <ThreadSafe as __ThreadSafety>::Parker::pair();
+        let waker = Waker::from(Arc::new(Unpark(unparker)));
+        let mut cx = Context::from_waker(&waker);
+
+        while Pin::new(&mut waiter).poll(&mut cx).is_pending() {
+            parker.park();
+        }
+
+        drop(waiter);
+        poster.join().unwrap();
+    });
+}
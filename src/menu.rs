@@ -0,0 +1,109 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Native menus, through `muda`.
+//!
+//! Menu events are delivered once per iteration of the event loop, through the
+//! [`EventLoopWindowTarget::menu_event`] handler. Menu bars are attached to windows with
+//! [`Window::attach_menu`].
+//!
+//! `muda` menus can only be used on the main thread, so menus should be created and attached on
+//! the event loop thread.
+//!
+//! ```no_run
+//! use async_winit::event_loop::EventLoop;
+//! use async_winit::menu::{Menu, MenuEvent};
+//! use async_winit::window::Window;
+//! use async_winit::ThreadUnsafe;
+//!
+//! use futures_lite::prelude::*;
+//!
+//! let event_loop = EventLoop::<ThreadUnsafe>::new();
+//! let target = event_loop.window_target().clone();
+//!
+//! event_loop.block_on(async move {
+//!     target.resumed().await;
+//!
+//!     let quit = muda::MenuItem::new("Quit", true, None);
+//!     let file = muda::Submenu::with_items("File", true, &[&quit]).unwrap();
+//!     let menu = Menu::with_items(&[&file]).unwrap();
+//!
+//!     let window = Window::<ThreadUnsafe>::new().await.unwrap();
+//!     window.attach_menu(&menu).unwrap();
+//!
+//!     let mut events = target.menu_event().wait();
+//!     while let Some(MenuEvent { id }) = events.next().await {
+//!         if id == *quit.id() {
+//!             target.exit().await;
+//!         }
+//!     }
+//! #   futures_lite::future::pending().await
+//! });
+//! ```
+//!
+//! [`EventLoopWindowTarget::menu_event`]: crate::event_loop::EventLoopWindowTarget::menu_event
+
+use crate::reactor::Reactor;
+use crate::sync::ThreadSafety;
+use crate::window::Window;
+
+#[doc(inline)]
+pub use muda::{Menu, MenuEvent, MenuId};
+
+impl<TS: ThreadSafety> Window<TS> {
+    /// Attach a menu bar to this window.
+    ///
+    /// On Windows, the menu is added to this window. On macOS, the menu bar belongs to the
+    /// application rather than to any window, so this makes `menu` the application's main menu.
+    pub fn attach_menu(&self, menu: &Menu) -> muda::Result<()> {
+        cfg_if::cfg_if! {
+            if #[cfg(windows)] {
+                use crate::platform::windows::WindowExtWindows;
+                menu.init_for_hwnd(self.hwnd())
+            } else {
+                menu.init_for_nsapp();
+                Ok(())
+            }
+        }
+    }
+
+    /// Remove a menu bar that was attached with [`Window::attach_menu`].
+    pub fn detach_menu(&self, menu: &Menu) -> muda::Result<()> {
+        cfg_if::cfg_if! {
+            if #[cfg(windows)] {
+                use crate::platform::windows::WindowExtWindows;
+                menu.remove_for_hwnd(self.hwnd())
+            } else {
+                menu.remove_for_nsapp();
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Deliver the menu events that have been received since the last iteration.
+pub(crate) async fn poll_menu_events<TS: ThreadSafety>(reactor: &Reactor<TS>) {
+    // Drain the channel even if nobody is listening, so that events don't pile up.
+    while let Ok(mut event) = MenuEvent::receiver().try_recv() {
+        reactor
+            .evl_registration
+            .menu_event
+            .run_with(&mut event)
+            .await;
+    }
+}
@@ -0,0 +1,191 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Latency measurements for the event loop.
+//!
+//! When enabled through [`EventLoopBuilder::with_metrics`], the reactor stamps each event with the
+//! [`Instant`] it entered the reactor and records how long it took for every listener to finish
+//! handling it. For timer wakeups, it also records how late the operating system woke up the event
+//! loop. This makes it possible to tell apart delays caused by the OS from delays caused by slow
+//! handlers.
+//!
+//! ```no_run
+//! use async_winit::event_loop::{EventLoopBuilder, EventLoop};
+//! use async_winit::ThreadUnsafe;
+//!
+//! let event_loop: EventLoop<ThreadUnsafe> = EventLoopBuilder::new().with_metrics(true).build();
+//! let target = event_loop.window_target().clone();
+//!
+//! event_loop.block_on(async move {
+//!     async_winit::time::sleep::<ThreadUnsafe>(std::time::Duration::from_secs(5)).await;
+//!
+//!     let metrics = target.metrics().unwrap();
+//!     println!("p99 dispatch latency: {:?}", metrics.dispatch().quantile(0.99));
+//!     println!("p99 wakeup latency: {:?}", metrics.wakeup().quantile(0.99));
+//! #   futures_lite::future::pending().await
+//! });
+//! ```
+//!
+//! [`EventLoopBuilder::with_metrics`]: crate::event_loop::EventLoopBuilder::with_metrics
+
+use std::time::Duration;
+
+use instant::Instant;
+
+/// The number of buckets in a [`Histogram`].
+///
+/// Bucket `n` holds samples shorter than `2^n` microseconds, so the last bucket covers everything
+/// longer than about 17 minutes.
+const BUCKETS: usize = 31;
+
+/// A snapshot of the latency measurements taken by the reactor.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    /// Time from an event entering the reactor to all of its listeners finishing.
+    dispatch: Histogram,
+
+    /// Time from a timer's deadline to the event loop actually waking up for it.
+    wakeup: Histogram,
+}
+
+impl Metrics {
+    /// Time from an event entering the reactor to all of its listeners finishing.
+    ///
+    /// High values here mean that handlers are doing too much work before yielding.
+    #[inline]
+    pub fn dispatch(&self) -> &Histogram {
+        &self.dispatch
+    }
+
+    /// Time from a timer's deadline to the event loop waking up to handle it.
+    ///
+    /// High values here mean that the operating system is waking the event loop up late.
+    #[inline]
+    pub fn wakeup(&self) -> &Histogram {
+        &self.wakeup
+    }
+
+    /// Record the time it took to dispatch an event that entered the reactor at `start`.
+    pub(crate) fn record_dispatch(&mut self, start: Instant) {
+        self.dispatch.record(start.elapsed());
+    }
+
+    /// Record a timer wakeup that was requested for `requested` and happened at `actual`.
+    pub(crate) fn record_wakeup(&mut self, requested: Instant, actual: Instant) {
+        let late = actual.saturating_duration_since(requested);
+        self.wakeup.record(late);
+    }
+}
+
+/// A histogram of durations, using exponentially sized buckets.
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    /// The number of samples in each bucket.
+    buckets: [u64; BUCKETS],
+
+    /// The total number of samples.
+    count: u64,
+
+    /// The sum of all samples.
+    total: Duration,
+
+    /// The longest sample.
+    max: Duration,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: [0; BUCKETS],
+            count: 0,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+}
+
+impl Histogram {
+    /// The number of samples recorded.
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The longest sample recorded.
+    #[inline]
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// The average of all samples, or zero if there are none.
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+
+        Duration::from_nanos((self.total.as_nanos() / u128::from(self.count)) as u64)
+    }
+
+    /// Estimate the given quantile, between `0.0` and `1.0`.
+    ///
+    /// The result is the upper bound of the bucket containing the quantile, capped at the longest
+    /// recorded sample. Returns zero if there are no samples.
+    pub fn quantile(&self, quantile: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+
+        let target = (self.count as f64 * quantile.clamp(0.0, 1.0)).ceil();
+        let target = target.max(1.0) as u64;
+        let mut seen = 0;
+
+        for (bound, count) in self.buckets() {
+            seen += count;
+            if seen >= target {
+                return bound.min(self.max);
+            }
+        }
+
+        self.max
+    }
+
+    /// Iterate over the buckets of this histogram.
+    ///
+    /// Each item is the exclusive upper bound of the bucket and the number of samples in it.
+    pub fn buckets(&self) -> impl Iterator<Item = (Duration, u64)> + '_ {
+        self.buckets
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| (Duration::from_micros(1 << i), count))
+    }
+
+    /// Record a sample.
+    pub(crate) fn record(&mut self, sample: Duration) {
+        let micros = sample.as_micros();
+        let bucket = if micros == 0 {
+            0
+        } else {
+            ((u128::BITS - micros.leading_zeros()) as usize).min(BUCKETS - 1)
+        };
+
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.total += sample;
+        self.max = self.max.max(sample);
+    }
+}
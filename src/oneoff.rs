@@ -17,41 +17,162 @@ Public License along with `async-winit`. If not, see <https://www.gnu.org/licens
 */
 
 //! One-off channel, which handles completions of ongoing events.
-
-// TODO: This implementation uses a full channel, which allocates and is overall very inefficient.
-//       We should use a leaner implementation later.
+//!
+//! This only uses a single allocation, shared between the sender and the receiver.
 
 use crate::sync::{ThreadSafety, __private::*};
 
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::Ordering;
+use std::task::{Context, Poll};
+
+/// The value has not been sent yet.
+const PENDING: usize = 0;
+
+/// The value has been sent.
+const SENT: usize = 1;
+
+/// The sender was dropped without sending a value.
+const CLOSED: usize = 2;
+
+/// The state shared between both ends of the channel.
+struct Slot<T, TS: ThreadSafety> {
+    /// The value, once it has been sent.
+    value: TS::Mutex<Option<T>>,
+
+    /// The state of the channel.
+    state: TS::AtomicUsize,
+
+    /// The waker for the receiver.
+    waker: TS::AtomicWaker,
+}
+
 /// A oneoff channel that can be used to receive a single event.
 pub(crate) struct Oneoff<T, TS: ThreadSafety> {
-    /// The channel used to receive the event.
-    rx: TS::Receiver<T>,
+    /// The shared slot.
+    slot: TS::Rc<Slot<T, TS>>,
 }
 
 impl<T, TS: ThreadSafety> Oneoff<T, TS> {
     /// Wait for the event to be sent.
     pub(crate) async fn recv(self) -> T {
-        self.rx.recv().await.unwrap()
+        self.await
+    }
+}
+
+impl<T, TS: ThreadSafety> Unpin for Oneoff<T, TS> {}
+
+impl<T, TS: ThreadSafety> Future for Oneoff<T, TS> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let slot = &self.slot;
+
+        if slot.state.load(Ordering::Acquire) == PENDING {
+            slot.waker.register(cx.waker());
+
+            // Check again in case the value was sent while registering.
+            if slot.state.load(Ordering::Acquire) == PENDING {
+                return Poll::Pending;
+            }
+        }
+
+        match slot.value.lock().unwrap().take() {
+            Some(value) => Poll::Ready(value),
+            None => panic!("event loop operation was dropped before it completed"),
+        }
     }
 }
 
 /// The sender end of the oneoff channel.
 pub(crate) struct Complete<T, TS: ThreadSafety> {
-    /// The channel used to send the event.
-    tx: TS::Sender<T>,
+    /// The shared slot.
+    slot: TS::Rc<Slot<T, TS>>,
 }
 
 impl<T, TS: ThreadSafety> Complete<T, TS> {
     /// Send the event.
     pub(crate) fn send(self, event: T) {
-        self.tx.try_send(event).ok();
+        *self.slot.value.lock().unwrap() = Some(event);
+        self.finish(SENT);
+    }
+
+    /// Set the final state of the channel and wake up the receiver.
+    fn finish(&self, state: usize) {
+        self.slot.state.store(state, Ordering::Release);
+        if let Some(waker) = self.slot.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T, TS: ThreadSafety> Drop for Complete<T, TS> {
+    fn drop(&mut self) {
+        // Let the receiver know that nothing is coming.
+        if self.slot.state.load(Ordering::Acquire) == PENDING {
+            self.finish(CLOSED);
+        }
     }
 }
 
 /// Create a pair of oneoff channels.
 pub(crate) fn oneoff<T, TS: ThreadSafety>() -> (Complete<T, TS>, Oneoff<T, TS>) {
-    let (tx, rx) = TS::channel_bounded(1);
+    let slot = TS::Rc::new(Slot {
+        value: TS::Mutex::new(None),
+        state: TS::AtomicUsize::new(PENDING),
+        waker: TS::AtomicWaker::new(),
+    });
+
+    (Complete { slot: slot.clone() }, Oneoff { slot })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{ThreadUnsafe, WakeFlag};
+
+    use futures_lite::future;
+
+    #[test]
+    fn value_sent_before_recv() {
+        let (tx, rx) = oneoff::<i32, ThreadUnsafe>();
+        tx.send(1);
+
+        assert_eq!(future::block_on(rx.recv()), 1);
+    }
+
+    #[test]
+    fn send_wakes_the_receiver() {
+        let (tx, mut rx) = oneoff::<i32, ThreadUnsafe>();
+        let (flag, waker) = WakeFlag::new();
+        let mut cx = Context::from_waker(&waker);
 
-    (Complete { tx }, Oneoff { rx })
+        assert!(Pin::new(&mut rx).poll(&mut cx).is_pending());
+
+        tx.send(1);
+        assert!(flag.woken());
+        assert_eq!(Pin::new(&mut rx).poll(&mut cx), Poll::Ready(1));
+    }
+
+    #[test]
+    fn dropped_sender_wakes_the_receiver() {
+        let (tx, mut rx) = oneoff::<i32, ThreadUnsafe>();
+        let (flag, waker) = WakeFlag::new();
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(Pin::new(&mut rx).poll(&mut cx).is_pending());
+
+        drop(tx);
+        assert!(flag.woken());
+    }
+
+    #[test]
+    #[should_panic = "event loop operation was dropped before it completed"]
+    fn dropped_sender_panics_the_receiver() {
+        let (tx, rx) = oneoff::<i32, ThreadUnsafe>();
+        drop(tx);
+
+        future::block_on(rx.recv());
+    }
 }
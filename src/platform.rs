@@ -39,7 +39,17 @@ pub mod wayland;
 #[cfg(windows)]
 pub mod windows;
 
-#[cfg(any(windows, x11_platform, wayland_platform))]
+#[cfg(wasm_platform)]
+pub mod web;
+
+#[cfg(any(
+    windows,
+    macos_platform,
+    android_platform,
+    x11_platform,
+    wayland_platform,
+    orbital_platform
+))]
 pub mod run_return;
 
 cfg_if::cfg_if! {
@@ -64,6 +74,8 @@ cfg_if::cfg_if! {
         pub(crate) use free_unix::PlatformSpecific;
     } else if #[cfg(windows)] {
         pub(crate) use windows::PlatformSpecific;
+    } else if #[cfg(wasm_platform)] {
+        pub(crate) use web::PlatformSpecific;
     }
 }
 
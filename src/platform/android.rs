@@ -26,9 +26,19 @@ use super::__private as sealed;
 #[doc(inline)]
 pub use winit::platform::android::activity;
 
-use crate::event_loop::EventLoopBuilder;
-use activity::AndroidApp;
+use crate::event_loop::{EventLoop, EventLoopBuilder, EventLoopWindowTarget};
+use crate::filter::{Filter, ReturnOrFinish};
+use crate::handler::Handler;
+use crate::sync::ThreadSafety;
+use activity::{AndroidApp, ConfigurationRef, Rect};
 
+use futures_lite::pin;
+
+use std::future::Future;
+use std::pin::Pin;
+
+use winit::event::Event;
+use winit::event_loop::ControlFlow;
 use winit::platform::android::EventLoopBuilderExtAndroid as _;
 use winit::window::WindowBuilder;
 
@@ -44,11 +54,172 @@ pub trait EventLoopBuilderExtAndroid: sealed::EventLoopBuilderPrivate {
 
 impl EventLoopBuilderExtAndroid for EventLoopBuilder {
     fn with_android_app(&mut self, app: AndroidApp) -> &mut Self {
-        self.inner.with_android_app(app);
+        self.inner.with_android_app(app.clone());
+        self.android_app = Some(app);
         self
     }
 }
 
+/// The result of pumping the event loop with [`EventLoopExtPumpEvents::pump_events`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PumpStatus<T> {
+    /// The pending events were handled, and the event loop should be pumped again later.
+    Continue,
+
+    /// The event loop has exited with the given exit code.
+    Exit(i32),
+
+    /// The future completed.
+    FutureReturned(T),
+}
+
+/// Additional methods on [`EventLoop`] for pumping events in bursts on Android.
+///
+/// [`EventLoop`]: crate::event_loop::EventLoop
+pub trait EventLoopExtPumpEvents: sealed::EventLoopPrivate {
+    /// Handle all of the pending events, then return control to the caller.
+    ///
+    /// This is useful when embedding `async-winit` into a host that drives the main loop itself,
+    /// such as `GameActivity` based projects that handle events from their own callbacks. Instead
+    /// of owning the thread until the app exits, each call runs one burst of events and drives
+    /// `future` until the event loop would go to sleep. The same future should be passed to every
+    /// call until it completes.
+    fn pump_events<F>(&mut self, future: Pin<&mut F>) -> PumpStatus<F::Output>
+    where
+        F: Future;
+}
+
+impl<TS: ThreadSafety> EventLoopExtPumpEvents for EventLoop<TS> {
+    fn pump_events<F>(&mut self, future: Pin<&mut F>) -> PumpStatus<F::Output>
+    where
+        F: Future,
+    {
+        use winit::platform::run_return::EventLoopExtRunReturn as _;
+
+        let reactor = self.reactor.clone();
+        let inner = &mut self.inner;
+
+        let fut = async move { reactor.run_tasks(future).await };
+        pin!(fut);
+
+        let mut filter = Filter::<TS>::new(inner);
+
+        let mut output = None;
+        let mut burst_done = false;
+        let exit = inner.run_return({
+            let output = &mut output;
+            let burst_done = &mut burst_done;
+            move |event, elwt, flow| {
+                let about_to_sleep = matches!(event, Event::RedrawEventsCleared);
+
+                match filter.handle_event(fut.as_mut(), event, elwt, flow) {
+                    ReturnOrFinish::FutureReturned(out) => {
+                        *output = Some(out);
+                        flow.set_exit()
+                    }
+
+                    ReturnOrFinish::Output(()) => {
+                        // Return to the caller once the pending events have been handled.
+                        if about_to_sleep && !matches!(flow, ControlFlow::ExitWithCode(_)) {
+                            *burst_done = true;
+                            flow.set_exit();
+                        }
+                    }
+                }
+            }
+        });
+
+        match output {
+            Some(output) => PumpStatus::FutureReturned(output),
+            None if burst_done => PumpStatus::Continue,
+            None => PumpStatus::Exit(exit),
+        }
+    }
+}
+
+/// Additional methods on [`EventLoopWindowTarget`] specific to Android.
+///
+/// [`EventLoopWindowTarget`]: crate::event_loop::EventLoopWindowTarget
+pub trait EventLoopWindowTargetExtAndroid<TS: ThreadSafety>:
+    sealed::EventLoopWindowTargetPrivate
+{
+    /// Get the `AndroidApp` that the event loop was created with.
+    ///
+    /// This can be used to query the content rectangle, make JNI calls or load assets from async
+    /// code. Returns `None` if no app was set through [`EventLoopBuilderExtAndroid::with_android_app`].
+    fn android_app(&self) -> Option<AndroidApp>;
+
+    /// Get the handler for configuration changes.
+    ///
+    /// This is called with the app's new configuration whenever it changes, for instance when the
+    /// device is rotated or the locale changes.
+    fn config_changed(&self) -> &Handler<ConfigurationRef, TS>;
+
+    /// Get the handler for content rectangle changes.
+    ///
+    /// The content rectangle is the part of the window that isn't covered by system UI, like the
+    /// status bar or the soft keyboard. This is called with the new rectangle whenever it changes,
+    /// for instance when the soft keyboard is shown or hidden, so the UI can be resized or panned
+    /// to keep the focused content visible. The first call reports the initial rectangle.
+    fn content_rect_changed(&self) -> &Handler<Rect, TS>;
+}
+
+impl<TS: ThreadSafety> EventLoopWindowTargetExtAndroid<TS> for EventLoopWindowTarget<TS> {
+    fn android_app(&self) -> Option<AndroidApp> {
+        self.reactor.android_app.get().cloned()
+    }
+
+    fn config_changed(&self) -> &Handler<ConfigurationRef, TS> {
+        &self.reactor.evl_registration.config_changed
+    }
+
+    fn content_rect_changed(&self) -> &Handler<Rect, TS> {
+        &self.reactor.evl_registration.content_rect_changed
+    }
+}
+
+/// Define the `android_main` entry point for an Android app.
+///
+/// On Android, the app is started through an `android_main` function that receives the
+/// `AndroidApp`, instead of through `main()`. This macro defines that function, builds an
+/// [`EventLoop`] with [`EventLoopBuilderExtAndroid::with_android_app`], and passes it to the given
+/// function. The thread safety of the event loop can be chosen with a second argument; it defaults
+/// to [`DefaultThreadSafety`].
+///
+/// ```no_run
+/// use async_winit::event_loop::EventLoop;
+/// use async_winit::ThreadUnsafe;
+///
+/// async_winit::android_main!(main2, ThreadUnsafe);
+///
+/// fn main2(evl: EventLoop<ThreadUnsafe>) {
+///     evl.block_on(async move {
+///         // ...
+///         # std::future::pending().await
+///     })
+/// }
+/// ```
+///
+/// [`EventLoop`]: crate::event_loop::EventLoop
+/// [`DefaultThreadSafety`]: crate::DefaultThreadSafety
+#[macro_export]
+macro_rules! android_main {
+    ($main:ident) => {
+        $crate::android_main!($main, $crate::DefaultThreadSafety);
+    };
+    ($main:ident, $ts:ty) => {
+        #[no_mangle]
+        fn android_main(app: $crate::platform::android::activity::AndroidApp) {
+            use $crate::platform::android::EventLoopBuilderExtAndroid as _;
+
+            let event_loop = $crate::event_loop::EventLoopBuilder::new()
+                .with_android_app(app)
+                .build::<$ts>();
+            $main(event_loop);
+        }
+    };
+}
+
 #[derive(Default)]
 pub(crate) struct PlatformSpecific;
 
@@ -21,9 +21,11 @@ Public License along with `async-winit`. If not, see <https://www.gnu.org/licens
 
 //! Features for both X11 and Wayland.
 
-use super::x11::XWindowType;
+use super::x11::{VisualInfo, XWindowType};
 use crate::dpi::Size;
 
+use std::os::raw;
+
 #[derive(Default)]
 pub(crate) struct PlatformSpecific {
     x11: super::x11::PlatformSpecific,
@@ -31,6 +33,10 @@ pub(crate) struct PlatformSpecific {
 }
 
 impl PlatformSpecific {
+    pub(crate) fn set_x11_visual(&mut self, x11_visual: VisualInfo) {
+        self.x11.set_x11_visual(x11_visual);
+    }
+
     pub(crate) fn set_x11_window_type(&mut self, x11_window_type: Vec<XWindowType>) {
         self.x11.set_x11_window_type(x11_window_type);
     }
@@ -52,6 +58,10 @@ impl PlatformSpecific {
         self.x11.set_x11_base_size(x11_base_size);
     }
 
+    pub(crate) fn set_x11_embed_parent(&mut self, x11_embed_parent: raw::c_ulong) {
+        self.x11.set_x11_embed_parent(x11_embed_parent);
+    }
+
     pub(crate) fn apply_to(self, wb: winit::window::WindowBuilder) -> winit::window::WindowBuilder {
         let Self { x11, wayland } = self;
 
@@ -20,8 +20,21 @@ Public License along with `async-winit`. If not, see <https://www.gnu.org/licens
 // contributers. It was originally released under the MIT license.
 
 //! Platform-specific iOS features.
-
+//!
+//! The `winit` backend that this crate is built on does not install any gesture recognizers on
+//! iOS, so gesture events like [`TouchpadMagnify`] and [`TouchpadRotate`] are never produced on
+//! iOS windows. Use the raw [`Touch`] events to recognize gestures instead.
+//!
+//! [`TouchpadMagnify`]: crate::event::WindowEvent::TouchpadMagnify
+//! [`TouchpadRotate`]: crate::event::WindowEvent::TouchpadRotate
+//! [`Touch`]: crate::event::WindowEvent::Touch
+
+use std::future::Future;
 use std::os::raw::c_void;
+use std::pin::Pin;
+
+use objc2::runtime::Object;
+use objc2::{class, msg_send};
 
 #[doc(inline)]
 pub use winit::platform::ios::{Idiom, MonitorHandleExtIOS, ScreenEdge, ValidOrientations};
@@ -30,6 +43,7 @@ use winit::platform::ios::{WindowBuilderExtIOS as _, WindowExtIOS as _};
 
 use super::__private as sealed;
 use crate::event_loop::EventLoop;
+use crate::handler::Handler;
 use crate::window::{Window, WindowBuilder};
 use crate::ThreadSafety;
 
@@ -48,10 +62,122 @@ impl<TS: ThreadSafety> EventLoopExtIOS for EventLoop<TS> {
     }
 }
 
+/// The orientation of the app's user interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum InterfaceOrientation {
+    /// The orientation could not be determined.
+    Unknown,
+
+    /// The device is upright, with the home button at the bottom.
+    Portrait,
+
+    /// The device is upside down, with the home button at the top.
+    PortraitUpsideDown,
+
+    /// The device is sideways, with the home button on the left.
+    LandscapeLeft,
+
+    /// The device is sideways, with the home button on the right.
+    LandscapeRight,
+}
+
+impl InterfaceOrientation {
+    /// Get the current interface orientation of the application.
+    ///
+    /// This must be called on the main thread.
+    pub(crate) fn current() -> Self {
+        // SAFETY: UIKit is linked by winit, and this is only called from the main thread.
+        let orientation: isize = unsafe {
+            let app: *mut Object = msg_send![class!(UIApplication), sharedApplication];
+            msg_send![app, statusBarOrientation]
+        };
+
+        // Values of `UIInterfaceOrientation`.
+        match orientation {
+            1 => Self::Portrait,
+            2 => Self::PortraitUpsideDown,
+            3 => Self::LandscapeRight,
+            4 => Self::LandscapeLeft,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// The distances from the edges of a window to its safe area, in physical pixels.
+///
+/// The safe area is the part of the window that isn't covered by the notch, the status bar or the
+/// home indicator.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SafeAreaInsets {
+    /// The inset from the top edge.
+    pub top: u32,
+
+    /// The inset from the left edge.
+    pub left: u32,
+
+    /// The inset from the bottom edge.
+    pub bottom: u32,
+
+    /// The inset from the right edge.
+    pub right: u32,
+}
+
+impl SafeAreaInsets {
+    /// Get the safe area insets of a window.
+    ///
+    /// This must be called on the main thread.
+    pub(crate) fn of(window: &winit::window::Window) -> Self {
+        // On iOS, the inner rectangle of the window is its safe area.
+        let (outer, inner) = match (window.outer_position(), window.inner_position()) {
+            (Ok(outer), Ok(inner)) => (outer, inner),
+            _ => return Self::default(),
+        };
+        let outer_size = window.outer_size();
+        let inner_size = window.inner_size();
+
+        let inset = |near: i32, far: i32| u32::try_from(far - near).unwrap_or(0);
+        Self {
+            top: inset(outer.y, inner.y),
+            left: inset(outer.x, inner.x),
+            bottom: inset(
+                inner.y + inner_size.height as i32,
+                outer.y + outer_size.height as i32,
+            ),
+            right: inset(
+                inner.x + inner_size.width as i32,
+                outer.x + outer_size.width as i32,
+            ),
+        }
+    }
+}
+
 /// Additional methods on [`Window`] that are specific to iOS.
 ///
 /// [`Window`]: crate::window::Window
-pub trait WindowExtIOS: sealed::WindowPrivate {
+pub trait WindowExtIOS<TS: ThreadSafety>: sealed::WindowPrivate {
+    /// Get the handler for interface orientation changes.
+    ///
+    /// This is called with the new orientation whenever the user interface rotates, before the
+    /// window's `resized` handler runs. The first resize of the window reports its initial
+    /// orientation.
+    fn orientation_changed(&self) -> &Handler<InterfaceOrientation, TS>;
+
+    /// Get the current safe area insets of the window.
+    ///
+    /// Content placed inside of the insets is not covered by the notch, the status bar or the home
+    /// indicator.
+    fn safe_area_insets(&self) -> Pin<Box<dyn Future<Output = SafeAreaInsets> + '_>>;
+
+    /// Get the handler for safe area changes.
+    ///
+    /// This is called with the new insets whenever they change, for instance when the device is
+    /// rotated. It runs after the window's `resized` handler. The first resize of the window
+    /// reports its initial insets.
+    fn safe_area_changed(&self) -> &Handler<SafeAreaInsets, TS>;
+
     /// Returns a pointer to the [`UIWindow`] that is used by this window.
     ///
     /// The pointer will become invalid when the [`Window`] is destroyed.
@@ -126,7 +252,22 @@ pub trait WindowExtIOS: sealed::WindowPrivate {
     fn set_prefers_status_bar_hidden(&self, hidden: bool);
 }
 
-impl<TS: ThreadSafety> WindowExtIOS for Window<TS> {
+impl<TS: ThreadSafety> WindowExtIOS<TS> for Window<TS> {
+    #[inline]
+    fn orientation_changed(&self) -> &Handler<InterfaceOrientation, TS> {
+        &self.registration().orientation_changed
+    }
+
+    #[inline]
+    fn safe_area_insets(&self) -> Pin<Box<dyn Future<Output = SafeAreaInsets> + '_>> {
+        Box::pin(self.uikit_safe_area_insets())
+    }
+
+    #[inline]
+    fn safe_area_changed(&self) -> &Handler<SafeAreaInsets, TS> {
+        &self.registration().safe_area_changed
+    }
+
     #[inline]
     fn ui_window(&self) -> *mut c_void {
         self.window().ui_window()
@@ -26,10 +26,24 @@ pub use winit::platform::macos::{ActivationPolicy, OptionAsAlt};
 
 use winit::platform::macos::{EventLoopBuilderExtMacOS as _, WindowExtMacOS as _};
 
+use std::future::Future;
+use std::mem;
 use std::os::raw::c_void;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, Once};
+
+use objc2::foundation::{NSObject, NSString};
+use objc2::runtime::{Bool, Object};
+use objc2::{class, declare_class, msg_send, sel, ClassType};
 
 use super::__private as sealed;
-use crate::event_loop::EventLoopBuilder;
+use crate::error::OsError;
+use crate::event_loop::{EventLoopBuilder, EventLoopWindowTarget};
+use crate::handler::Handler;
+use crate::oneoff::oneoff;
+use crate::reactor::{EventLoopOp, GlobalRegistration};
 use crate::window::{Window, WindowBuilder};
 use crate::ThreadSafety;
 
@@ -57,13 +71,17 @@ pub trait WindowExtMacOS: sealed::WindowPrivate {
     /// This is how fullscreen used to work on macOS in versions before Lion.
     /// And allows the user to have a fullscreen window without using another
     /// space or taking control over the entire monitor.
-    fn set_simple_fullscreen(&self, fullscreen: bool) -> bool;
+    ///
+    /// The change is made on the event loop thread.
+    fn set_simple_fullscreen(&self, fullscreen: bool) -> Pin<Box<dyn Future<Output = bool> + '_>>;
 
     /// Returns whether or not the window has shadow.
     fn has_shadow(&self) -> bool;
 
     /// Sets whether or not the window has shadow.
-    fn set_has_shadow(&self, has_shadow: bool);
+    ///
+    /// The change is made on the event loop thread.
+    fn set_has_shadow(&self, has_shadow: bool) -> Pin<Box<dyn Future<Output = ()> + '_>>;
 
     /// Get the window's edit state.
     ///
@@ -82,7 +100,9 @@ pub trait WindowExtMacOS: sealed::WindowPrivate {
     fn is_document_edited(&self) -> bool;
 
     /// Put the window in a state which indicates a file save is required.
-    fn set_document_edited(&self, edited: bool);
+    ///
+    /// The change is made on the event loop thread.
+    fn set_document_edited(&self, edited: bool) -> Pin<Box<dyn Future<Output = ()> + '_>>;
 
     /// Set option as alt behavior as described in [`OptionAsAlt`].
     ///
@@ -90,7 +110,12 @@ pub trait WindowExtMacOS: sealed::WindowPrivate {
     /// being processed as received characters. Instead, the input
     /// device's raw character will be placed in event queues with the
     /// Alt modifier set.
-    fn set_option_as_alt(&self, option_as_alt: OptionAsAlt);
+    ///
+    /// The change is made on the event loop thread.
+    fn set_option_as_alt(
+        &self,
+        option_as_alt: OptionAsAlt,
+    ) -> Pin<Box<dyn Future<Output = ()> + '_>>;
 
     /// Getter for the [`WindowExtMacOS::set_option_as_alt`].
     fn option_as_alt(&self) -> OptionAsAlt;
@@ -109,28 +134,37 @@ impl<TS: ThreadSafety> WindowExtMacOS for Window<TS> {
         self.window().simple_fullscreen()
     }
 
-    fn set_simple_fullscreen(&self, fullscreen: bool) -> bool {
-        self.window().set_simple_fullscreen(fullscreen)
+    fn set_simple_fullscreen(&self, fullscreen: bool) -> Pin<Box<dyn Future<Output = bool> + '_>> {
+        Box::pin(self.cocoa_op(WindowOp::SetSimpleFullscreen(fullscreen)))
     }
 
     fn has_shadow(&self) -> bool {
         self.window().has_shadow()
     }
 
-    fn set_has_shadow(&self, has_shadow: bool) {
-        self.window().set_has_shadow(has_shadow)
+    fn set_has_shadow(&self, has_shadow: bool) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(async move {
+            self.cocoa_op(WindowOp::SetHasShadow(has_shadow)).await;
+        })
     }
 
     fn is_document_edited(&self) -> bool {
         self.window().is_document_edited()
     }
 
-    fn set_document_edited(&self, edited: bool) {
-        self.window().set_document_edited(edited)
+    fn set_document_edited(&self, edited: bool) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(async move {
+            self.cocoa_op(WindowOp::SetDocumentEdited(edited)).await;
+        })
     }
 
-    fn set_option_as_alt(&self, option_as_alt: OptionAsAlt) {
-        self.window().set_option_as_alt(option_as_alt)
+    fn set_option_as_alt(
+        &self,
+        option_as_alt: OptionAsAlt,
+    ) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(async move {
+            self.cocoa_op(WindowOp::SetOptionAsAlt(option_as_alt)).await;
+        })
     }
 
     fn option_as_alt(&self) -> OptionAsAlt {
@@ -171,6 +205,12 @@ pub trait WindowBuilderExtMacOS: sealed::WindowBuilderPrivate {
     ///
     /// See [`WindowExtMacOS::set_option_as_alt`] for details on what this means if set.
     fn with_option_as_alt(self, option_as_alt: OptionAsAlt) -> WindowBuilder;
+
+    /// Sets the tabbing identifier of the window.
+    ///
+    /// Windows with the same tabbing identifier are grouped together in the same native tab group.
+    /// By default, windows are grouped by their class.
+    fn with_tabbing_identifier(self, identifier: &str) -> WindowBuilder;
 }
 
 impl WindowBuilderExtMacOS for WindowBuilder {
@@ -217,6 +257,11 @@ impl WindowBuilderExtMacOS for WindowBuilder {
         self
     }
 
+    fn with_tabbing_identifier(mut self, identifier: &str) -> WindowBuilder {
+        self.platform.tabbing_identifier = Some(identifier.to_string());
+        self
+    }
+
     fn with_title_hidden(mut self, title_hidden: bool) -> WindowBuilder {
         self.platform.title_hidden = Some(title_hidden);
         self
@@ -300,6 +345,397 @@ impl EventLoopBuilderExtMacOS for EventLoopBuilder {
     }
 }
 
+/// Additional methods on [`EventLoopWindowTarget`] that are specific to MacOS.
+///
+/// These affect the whole application and have to run on the main thread, so they are sent to the
+/// event loop. The returned futures complete once the operation has been run.
+///
+/// [`EventLoopWindowTarget`]: crate::event_loop::EventLoopWindowTarget
+pub trait EventLoopWindowTargetExtMacOS<TS: ThreadSafety>:
+    sealed::EventLoopWindowTargetPrivate
+{
+    /// Hide the entire application.
+    ///
+    /// This is the same as choosing "Hide" from the application menu, or pressing Cmd+H.
+    fn hide_application(&self) -> Pin<Box<dyn Future<Output = ()> + '_>>;
+
+    /// Hide all other applications.
+    ///
+    /// This is the same as choosing "Hide Others" from the application menu, or pressing
+    /// Cmd+Option+H.
+    fn hide_other_applications(&self) -> Pin<Box<dyn Future<Output = ()> + '_>>;
+
+    /// Change the activation policy of the application.
+    ///
+    /// This can be used to, for instance, hide the application's Dock icon while it has no visible
+    /// windows. The initial policy is set with [`EventLoopBuilderExtMacOS::with_activation_policy`].
+    fn set_activation_policy(
+        &self,
+        activation_policy: ActivationPolicy,
+    ) -> Pin<Box<dyn Future<Output = ()> + '_>>;
+
+    /// Get the handler for when the application is reopened.
+    ///
+    /// This happens when the user clicks the application's Dock icon, or opens the application in
+    /// Finder, while it is already running. Applications usually open a new window here if they
+    /// have none.
+    fn reopen(&self) -> &Handler<(), TS>;
+
+    /// Get the handler for requests to open files.
+    ///
+    /// This happens when the user drops files onto the application's Dock icon, or opens files
+    /// with the application in Finder. Requests that arrive before anything listens to this
+    /// handler, like those that launch the application, are delivered once something does.
+    fn open_files(&self) -> &Handler<Vec<PathBuf>, TS>;
+
+    /// Get the handler for requests to open URLs.
+    ///
+    /// This happens when a URL with a scheme that the application is registered for is opened.
+    /// Like with [`open_files`], requests are kept until something listens to this handler.
+    ///
+    /// [`open_files`]: EventLoopWindowTargetExtMacOS::open_files
+    fn open_urls(&self) -> &Handler<Vec<String>, TS>;
+}
+
+impl<TS: ThreadSafety> EventLoopWindowTargetExtMacOS<TS> for EventLoopWindowTarget<TS> {
+    fn hide_application(&self) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(self.application_op(ApplicationOp::Hide))
+    }
+
+    fn hide_other_applications(&self) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(self.application_op(ApplicationOp::HideOthers))
+    }
+
+    fn set_activation_policy(
+        &self,
+        activation_policy: ActivationPolicy,
+    ) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(self.application_op(ApplicationOp::SetActivationPolicy(activation_policy)))
+    }
+
+    fn reopen(&self) -> &Handler<(), TS> {
+        &self.reactor.evl_registration.reopen
+    }
+
+    fn open_files(&self) -> &Handler<Vec<PathBuf>, TS> {
+        &self.reactor.evl_registration.open_files
+    }
+
+    fn open_urls(&self) -> &Handler<Vec<String>, TS> {
+        &self.reactor.evl_registration.open_urls
+    }
+}
+
+impl<TS: ThreadSafety> EventLoopWindowTarget<TS> {
+    async fn application_op(&self, op: ApplicationOp) {
+        let (tx, rx) = oneoff();
+        self.reactor
+            .push_event_loop_op_or_wait(EventLoopOp::Application { op, waker: tx })
+            .await;
+
+        rx.recv().await
+    }
+}
+
+/// An operation on a window's `NSWindow`.
+pub(crate) enum WindowOp {
+    SetSimpleFullscreen(bool),
+    SetHasShadow(bool),
+    SetDocumentEdited(bool),
+    SetOptionAsAlt(OptionAsAlt),
+}
+
+impl WindowOp {
+    /// Run the operation on the window, returning whether it succeeded.
+    pub(crate) fn run(self, window: &winit::window::Window) -> bool {
+        match self {
+            WindowOp::SetSimpleFullscreen(fullscreen) => {
+                return window.set_simple_fullscreen(fullscreen);
+            }
+            WindowOp::SetHasShadow(has_shadow) => window.set_has_shadow(has_shadow),
+            WindowOp::SetDocumentEdited(edited) => window.set_document_edited(edited),
+            WindowOp::SetOptionAsAlt(option_as_alt) => window.set_option_as_alt(option_as_alt),
+        }
+
+        true
+    }
+}
+
+/// An operation on the shared `NSApplication`.
+pub(crate) enum ApplicationOp {
+    Hide,
+    HideOthers,
+    SetActivationPolicy(ActivationPolicy),
+}
+
+impl ApplicationOp {
+    /// Run the operation. This must be called on the main thread.
+    pub(crate) fn run(self) {
+        // SAFETY: `NSApplication` is always available on macOS, and event loop operations run on
+        // the main thread.
+        #[allow(clippy::let_unit_value)]
+        unsafe {
+            let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            let nil: *mut Object = std::ptr::null_mut();
+
+            match self {
+                ApplicationOp::Hide => {
+                    let _: () = msg_send![app, hide: nil];
+                }
+                ApplicationOp::HideOthers => {
+                    let _: () = msg_send![app, hideOtherApplications: nil];
+                }
+                ApplicationOp::SetActivationPolicy(activation_policy) => {
+                    // Values of `NSApplicationActivationPolicy`.
+                    let policy: isize = match activation_policy {
+                        ActivationPolicy::Regular => 0,
+                        ActivationPolicy::Accessory => 1,
+                        ActivationPolicy::Prohibited => 2,
+                    };
+                    let _: Bool = msg_send![app, setActivationPolicy: policy];
+                }
+            }
+        }
+    }
+}
+
+/// An Apple Event that has been received, but not delivered yet.
+enum AppleEvent {
+    Reopen,
+    OpenFiles(Vec<PathBuf>),
+    OpenUrls(Vec<String>),
+}
+
+/// Apple Events are received outside of `winit`'s callback, so they are queued here until the
+/// next event is posted to the reactor.
+static PENDING_APPLE_EVENTS: Mutex<Vec<AppleEvent>> = Mutex::new(Vec::new());
+static HAS_PENDING_APPLE_EVENTS: AtomicBool = AtomicBool::new(false);
+
+fn push_apple_event(event: AppleEvent) {
+    PENDING_APPLE_EVENTS.lock().unwrap().push(event);
+    HAS_PENDING_APPLE_EVENTS.store(true, Ordering::Release);
+}
+
+/// Deliver the Apple Events that have been received so far.
+///
+/// Events whose handler has no listeners are kept for later.
+pub(crate) async fn dispatch_apple_events<TS: ThreadSafety>(registration: &GlobalRegistration<TS>) {
+    if !HAS_PENDING_APPLE_EVENTS.swap(false, Ordering::Acquire) {
+        return;
+    }
+
+    let events = mem::take(&mut *PENDING_APPLE_EVENTS.lock().unwrap());
+    let mut undelivered = Vec::new();
+
+    for event in events {
+        match event {
+            AppleEvent::Reopen if registration.reopen.is_listening() => {
+                registration.reopen.run_with(&mut ()).await;
+            }
+            AppleEvent::OpenFiles(mut paths) if registration.open_files.is_listening() => {
+                registration.open_files.run_with(&mut paths).await;
+            }
+            AppleEvent::OpenUrls(mut urls) if registration.open_urls.is_listening() => {
+                registration.open_urls.run_with(&mut urls).await;
+            }
+            event => undelivered.push(event),
+        }
+    }
+
+    if !undelivered.is_empty() {
+        let mut pending = PENDING_APPLE_EVENTS.lock().unwrap();
+        undelivered.append(&mut pending);
+        *pending = undelivered;
+    }
+}
+
+/// Start listening for Apple Events.
+///
+/// The handlers have to be installed once the application is about to finish launching. AppKit
+/// installs its own handlers before that, and the events that launched the application are sent
+/// right after.
+pub(crate) fn listen_for_apple_events() {
+    static LISTEN: Once = Once::new();
+
+    LISTEN.call_once(|| {
+        // SAFETY: The handler object is never released, so it outlives the notification observer.
+        #[allow(clippy::let_unit_value)]
+        unsafe {
+            let handler: *mut Object = msg_send![AppleEventHandler::class(), new];
+            let center: *mut Object = msg_send![class!(NSNotificationCenter), defaultCenter];
+            let name = NSString::from_str("NSApplicationWillFinishLaunchingNotification");
+            let _: () = msg_send![
+                center,
+                addObserver: handler,
+                selector: sel!(applicationWillFinishLaunching:),
+                name: &*name,
+                object: std::ptr::null_mut::<Object>(),
+            ];
+        }
+    });
+}
+
+// Four-character codes from the Apple Event Manager.
+const KEY_DIRECT_OBJECT: u32 = u32::from_be_bytes(*b"----");
+const CORE_EVENT_CLASS: u32 = u32::from_be_bytes(*b"aevt");
+const OPEN_DOCUMENTS: u32 = u32::from_be_bytes(*b"odoc");
+const REOPEN_APPLICATION: u32 = u32::from_be_bytes(*b"rapp");
+const INTERNET_EVENT_CLASS: u32 = u32::from_be_bytes(*b"GURL");
+const GET_URL: u32 = u32::from_be_bytes(*b"GURL");
+const TYPE_FILE_URL: u32 = u32::from_be_bytes(*b"furl");
+
+declare_class!(
+    struct AppleEventHandler {}
+
+    unsafe impl ClassType for AppleEventHandler {
+        type Super = NSObject;
+        const NAME: &'static str = "AsyncWinitAppleEventHandler";
+    }
+
+    unsafe impl AppleEventHandler {
+        #[sel(applicationWillFinishLaunching:)]
+        fn will_finish_launching(&self, _notification: *mut Object) {
+            let handlers = [
+                (
+                    sel!(handleOpenDocuments:withReplyEvent:),
+                    CORE_EVENT_CLASS,
+                    OPEN_DOCUMENTS,
+                ),
+                (
+                    sel!(handleReopenApplication:withReplyEvent:),
+                    CORE_EVENT_CLASS,
+                    REOPEN_APPLICATION,
+                ),
+                (
+                    sel!(handleGetURL:withReplyEvent:),
+                    INTERNET_EVENT_CLASS,
+                    GET_URL,
+                ),
+            ];
+
+            #[allow(clippy::let_unit_value)]
+            unsafe {
+                let manager: *mut Object =
+                    msg_send![class!(NSAppleEventManager), sharedAppleEventManager];
+
+                for (selector, class, id) in handlers {
+                    let _: () = msg_send![
+                        manager,
+                        setEventHandler: self,
+                        andSelector: selector,
+                        forEventClass: class,
+                        andEventID: id,
+                    ];
+                }
+            }
+        }
+
+        #[sel(handleOpenDocuments:withReplyEvent:)]
+        fn handle_open_documents(&self, event: *mut Object, _reply: *mut Object) {
+            let paths = unsafe {
+                let list: *mut Object =
+                    msg_send![event, paramDescriptorForKeyword: KEY_DIRECT_OBJECT];
+                if list.is_null() {
+                    return;
+                }
+
+                // Descriptor lists are indexed from one.
+                let count: isize = msg_send![list, numberOfItems];
+                (1..=count)
+                    .filter_map(|i| {
+                        let item: *mut Object = msg_send![list, descriptorAtIndex: i];
+                        file_path(item)
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            if !paths.is_empty() {
+                push_apple_event(AppleEvent::OpenFiles(paths));
+            }
+        }
+
+        #[sel(handleReopenApplication:withReplyEvent:)]
+        fn handle_reopen_application(&self, _event: *mut Object, _reply: *mut Object) {
+            push_apple_event(AppleEvent::Reopen);
+        }
+
+        #[sel(handleGetURL:withReplyEvent:)]
+        fn handle_get_url(&self, event: *mut Object, _reply: *mut Object) {
+            let url = unsafe {
+                let url: *mut Object =
+                    msg_send![event, paramDescriptorForKeyword: KEY_DIRECT_OBJECT];
+                if url.is_null() {
+                    return;
+                }
+
+                let url: Option<&NSString> = msg_send![url, stringValue];
+                url.map(|url| url.to_string())
+            };
+
+            if let Some(url) = url {
+                push_apple_event(AppleEvent::OpenUrls(vec![url]));
+            }
+        }
+    }
+);
+
+/// Get the file path out of an Apple Event descriptor.
+///
+/// # Safety
+///
+/// `item` must be null or point to an `NSAppleEventDescriptor`.
+unsafe fn file_path(item: *mut Object) -> Option<PathBuf> {
+    if item.is_null() {
+        return None;
+    }
+
+    let url: *mut Object = msg_send![item, coerceToDescriptorType: TYPE_FILE_URL];
+    if url.is_null() {
+        return None;
+    }
+
+    let url: Option<&NSString> = msg_send![url, stringValue];
+    let url: *mut Object = msg_send![class!(NSURL), URLWithString: url?];
+    if url.is_null() {
+        return None;
+    }
+
+    let path: Option<&NSString> = msg_send![url, path];
+    path.map(|path| PathBuf::from(path.to_string()))
+}
+
+/// Build a window, joining the tab group with the builder's tabbing identifier if there is one.
+///
+/// `winit` can't set the tabbing identifier up front, so the window is built hidden and only shown
+/// once the identifier is set. Otherwise, it would be placed before it knows its tab group.
+pub(crate) fn build_window<T: 'static>(
+    mut builder: WindowBuilder,
+    target: &winit::event_loop::EventLoopWindowTarget<T>,
+) -> Result<winit::window::Window, OsError> {
+    let identifier = match builder.platform.tabbing_identifier.take() {
+        Some(identifier) => identifier,
+        None => return builder.into_winit_builder().build(target),
+    };
+
+    let visible = builder.attributes().visible;
+    let builder = builder.with_visible(false).into_winit_builder();
+    let window = builder.build(target)?;
+
+    // SAFETY: The window is alive and we are on the main thread.
+    #[allow(clippy::let_unit_value)]
+    unsafe {
+        let ns_window = window.ns_window() as *mut Object;
+        let identifier = NSString::from_str(&identifier);
+        let _: () = msg_send![ns_window, setTabbingIdentifier: &*identifier];
+    }
+
+    if visible {
+        window.set_visible(true);
+    }
+
+    Ok(window)
+}
+
 #[derive(Default)]
 pub(crate) struct PlatformSpecific {
     movable_by_window_background: Option<bool>,
@@ -312,6 +748,7 @@ pub(crate) struct PlatformSpecific {
     has_shadow: Option<bool>,
     accepts_first_mouse: Option<bool>,
     option_as_alt: Option<OptionAsAlt>,
+    tabbing_identifier: Option<String>,
 }
 
 impl PlatformSpecific {
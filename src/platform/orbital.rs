@@ -19,7 +19,11 @@ Public License along with `async-winit`. If not, see <https://www.gnu.org/licens
 // This file is partially derived from `winit`, which was originally created by Pierre Krieger and
 // contributers. It was originally released under the MIT license.
 
-// No platform-specific orbital code yet.
+//! Orbital-specific code.
+//!
+//! `winit` does not provide any Orbital-specific extension traits yet, so there are no
+//! extension traits here either. Windows on Redox are built using only the cross-platform
+//! [`WindowBuilder`](crate::window::WindowBuilder) options.
 
 use winit::window::WindowBuilder;
 
@@ -47,8 +47,10 @@ impl<TS: ThreadSafety> EventLoopExtRunReturn for EventLoop<TS> {
     {
         use winit::platform::run_return::EventLoopExtRunReturn as _;
 
+        let reactor = self.reactor.clone();
         let inner = &mut self.inner;
 
+        let fut = async move { reactor.run_tasks(fut).await };
         pin!(fut);
 
         let mut filter = Filter::<TS>::new(inner);
@@ -28,6 +28,7 @@ use crate::window::{Window, WindowBuilder};
 
 use std::os::raw;
 
+use raw_window_handle::{HasRawDisplayHandle, RawDisplayHandle};
 use winit::platform::wayland::{
     EventLoopBuilderExtWayland as _, WindowBuilderExtWayland as _, WindowExtWayland as _,
 };
@@ -61,7 +62,10 @@ impl<TS: ThreadSafety> EventLoopWindowTargetExtWayland for EventLoopWindowTarget
 
     #[inline]
     fn wayland_display(&self) -> Option<*mut raw::c_void> {
-        todo!()
+        match self.raw_display_handle() {
+            RawDisplayHandle::Wayland(handle) => Some(handle.display),
+            _ => None,
+        }
     }
 }
 
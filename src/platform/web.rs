@@ -0,0 +1,217 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+// This file is partially derived from `winit`, which was originally created by Pierre Krieger and
+// contributers. It was originally released under the MIT license.
+
+//! Platform-specific web features.
+//!
+//! The browser's main thread can't be blocked, so [`EventLoop::block_on`] can't be used to run the
+//! event loop in the usual way. Use [`EventLoopExtWebSys::spawn`] instead, which hands the event
+//! loop over to the browser and returns immediately.
+//!
+//! Since the event loop can't block, it keeps polling while an event handler holds an event (for
+//! instance, through [`Waiter::hold`]). Event handlers should not hold an event across `await`
+//! points that depend on other browser events.
+//!
+//! [`EventLoop::block_on`]: crate::event_loop::EventLoop::block_on
+//! [`Waiter::hold`]: crate::Waiter::hold
+
+use super::__private as sealed;
+use crate::event_loop::EventLoop;
+use crate::filter::Filter;
+use crate::sync::ThreadSafety;
+use crate::window::{Window, WindowBuilder};
+
+use std::convert::Infallible;
+use std::future::Future;
+
+use wasm_bindgen::JsCast;
+use web_sys::HtmlCanvasElement;
+use winit::platform::web::EventLoopExtWebSys as _;
+
+/// Additional methods on [`EventLoop`] that are specific to the web.
+pub trait EventLoopExtWebSys: sealed::EventLoopPrivate {
+    /// Run the event loop on the browser's event loop, driving the given future.
+    ///
+    /// Unlike [`EventLoop::block_on`], this function returns immediately. The future, along with
+    /// any windows and timers, is driven by the browser's event loop from then on. The reactor is
+    /// woken up through the browser as well, so wakeups from other tasks work as usual.
+    fn spawn<F>(self, future: F)
+    where
+        F: Future<Output = Infallible> + 'static;
+}
+
+impl<TS: ThreadSafety> EventLoopExtWebSys for EventLoop<TS> {
+    fn spawn<F>(self, future: F)
+    where
+        F: Future<Output = Infallible> + 'static,
+    {
+        let reactor = self.reactor.clone();
+        let mut future = Box::pin(async move { reactor.run_tasks(future).await });
+        let mut filter = Filter::<TS>::new(&self.inner);
+
+        // Keep the reactor alive for as long as the browser runs the event loop.
+        let (inner, guard) = self.into_parts();
+
+        inner.spawn(move |event, elwt, flow| {
+            let _guard = &guard;
+            filter.handle_event(future.as_mut(), event, elwt, flow);
+        });
+    }
+}
+
+/// Additional methods on [`Window`] that are specific to the web.
+pub trait WindowExtWebSys: sealed::WindowPrivate {
+    /// Get the canvas element that backs this window.
+    ///
+    /// This can be used to style or resize the canvas, or to attach other DOM event listeners to
+    /// it. Returns `None` if the window has no canvas.
+    fn canvas(&self) -> Option<HtmlCanvasElement>;
+
+    /// Use the image at the given URL as the cursor while it is over this window.
+    ///
+    /// `hotspot` is the point in the image, in pixels, that the cursor points to. This uses the
+    /// CSS `cursor` property, so any image format the browser supports can be used. Calling
+    /// [`Window::set_cursor_icon`] replaces the custom cursor.
+    fn set_cursor_url(&self, url: &str, hotspot: (u32, u32));
+}
+
+impl<TS: ThreadSafety> WindowExtWebSys for Window<TS> {
+    fn canvas(&self) -> Option<HtmlCanvasElement> {
+        use winit::platform::web::WindowExtWebSys as _;
+
+        Some(self.window().canvas())
+    }
+
+    fn set_cursor_url(&self, url: &str, hotspot: (u32, u32)) {
+        if let Some(canvas) = self.canvas() {
+            let url = url.replace('\\', "\\\\").replace('"', "\\\"");
+            let cursor = format!("url(\"{}\") {} {}, auto", url, hotspot.0, hotspot.1);
+            canvas.style().set_property("cursor", &cursor).ok();
+        }
+    }
+}
+
+/// Additional methods on [`WindowBuilder`] that are specific to the web.
+pub trait WindowBuilderExtWebSys: sealed::WindowBuilderPrivate {
+    /// Use the given canvas element instead of creating a new one.
+    ///
+    /// By default, a new canvas element is created for the window.
+    fn with_canvas(self, canvas: Option<HtmlCanvasElement>) -> WindowBuilder;
+
+    /// Whether `event.preventDefault` should be called on events that the window handles.
+    ///
+    /// This keeps the browser from, for instance, scrolling the page when the arrow keys are
+    /// pressed. Defaults to `true`.
+    fn with_prevent_default(self, prevent_default: bool) -> WindowBuilder;
+
+    /// Whether the canvas should be focusable with the `tabindex` attribute.
+    ///
+    /// Defaults to `true`.
+    fn with_focusable(self, focusable: bool) -> WindowBuilder;
+
+    /// Append the canvas to the document's body once it is created.
+    ///
+    /// This only has an effect if no canvas was provided through [`with_canvas`]. Defaults to
+    /// `false`, in which case the canvas needs to be inserted into the page manually.
+    ///
+    /// [`with_canvas`]: WindowBuilderExtWebSys::with_canvas
+    fn with_append(self, append: bool) -> WindowBuilder;
+}
+
+impl WindowBuilderExtWebSys for WindowBuilder {
+    fn with_canvas(mut self, canvas: Option<HtmlCanvasElement>) -> WindowBuilder {
+        self.platform.canvas = canvas.map(Canvas);
+        self
+    }
+
+    fn with_prevent_default(mut self, prevent_default: bool) -> WindowBuilder {
+        self.platform.prevent_default = Some(prevent_default);
+        self
+    }
+
+    fn with_focusable(mut self, focusable: bool) -> WindowBuilder {
+        self.platform.focusable = Some(focusable);
+        self
+    }
+
+    fn with_append(mut self, append: bool) -> WindowBuilder {
+        self.platform.append = append;
+        self
+    }
+}
+
+/// A canvas element that can be stored in a [`WindowBuilder`].
+///
+/// Window builders are sent to the event loop to be built, so they need to be `Send`.
+struct Canvas(HtmlCanvasElement);
+
+// SAFETY: Without the `atomics` target feature, WebAssembly is single-threaded, so the canvas can
+// never actually be sent to another thread.
+#[cfg(not(target_feature = "atomics"))]
+unsafe impl Send for Canvas {}
+
+#[derive(Default)]
+pub(crate) struct PlatformSpecific {
+    canvas: Option<Canvas>,
+    prevent_default: Option<bool>,
+    focusable: Option<bool>,
+    append: bool,
+}
+
+impl PlatformSpecific {
+    pub(crate) fn apply_to(
+        self,
+        mut wb: winit::window::WindowBuilder,
+    ) -> winit::window::WindowBuilder {
+        use winit::platform::web::WindowBuilderExtWebSys as _;
+
+        let canvas = match self.canvas {
+            Some(Canvas(canvas)) => Some(canvas),
+            None if self.append => append_canvas(),
+            None => None,
+        };
+
+        if canvas.is_some() {
+            wb = wb.with_canvas(canvas);
+        }
+
+        if let Some(prevent_default) = self.prevent_default {
+            wb = wb.with_prevent_default(prevent_default);
+        }
+
+        if let Some(focusable) = self.focusable {
+            wb = wb.with_focusable(focusable);
+        }
+
+        wb
+    }
+}
+
+/// Create a new canvas and append it to the document's body.
+fn append_canvas() -> Option<HtmlCanvasElement> {
+    let document = web_sys::window()?.document()?;
+    let canvas = document
+        .create_element("canvas")
+        .ok()?
+        .dyn_into::<HtmlCanvasElement>()
+        .ok()?;
+    document.body()?.append_child(&canvas).ok()?;
+    Some(canvas)
+}
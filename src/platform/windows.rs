@@ -31,7 +31,18 @@ use crate::event_loop::EventLoopBuilder;
 use crate::window::{Icon, Window, WindowBuilder};
 use crate::ThreadSafety;
 
+use std::cell::RefCell;
+use std::future::Future;
+use std::mem;
 use std::os::raw::c_void;
+use std::pin::Pin;
+
+use windows_sys::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+use windows_sys::Win32::Graphics::Dwm::{DwmSetWindowAttribute, DWMWINDOWATTRIBUTE};
+use windows_sys::Win32::UI::Shell::{
+    DefSubclassProc, GetWindowSubclass, RemoveWindowSubclass, SetWindowSubclass,
+};
+use windows_sys::Win32::UI::WindowsAndMessaging::WM_NCDESTROY;
 
 use winit::platform::windows::{
     EventLoopBuilderExtWindows as _, WindowBuilderExtWindows as _, WindowExtWindows as _,
@@ -79,6 +90,15 @@ pub trait EventLoopBuilderExtWindows: sealed::EventLoopBuilderPrivate {
     fn with_msg_hook<F>(&mut self, callback: F) -> &mut Self
     where
         F: FnMut(*const c_void) -> bool + 'static;
+
+    /// Whether to raise the resolution of the system timer while the event loop is alive.
+    ///
+    /// By default, Windows only wakes up sleeping threads every ~15ms, which makes
+    /// [`Timer`]-paced animations jitter. Enabling this raises the timer resolution to 1ms using
+    /// `timeBeginPeriod`, at the cost of increased power usage.
+    ///
+    /// [`Timer`]: crate::Timer
+    fn with_high_resolution_timer(&mut self, high_resolution: bool) -> &mut Self;
 }
 
 impl EventLoopBuilderExtWindows for EventLoopBuilder {
@@ -99,9 +119,50 @@ impl EventLoopBuilderExtWindows for EventLoopBuilder {
         self.inner.with_msg_hook(callback);
         self
     }
+
+    fn with_high_resolution_timer(&mut self, high_resolution: bool) -> &mut Self {
+        self.high_resolution_timer = high_resolution;
+        self
+    }
+}
+
+/// Raises the resolution of the system timer until dropped.
+pub(crate) struct HighResolutionTimer {
+    _private: (),
+}
+
+impl HighResolutionTimer {
+    /// The timer resolution to request, in milliseconds.
+    const PERIOD: u32 = 1;
+
+    /// Raise the timer resolution, returning `None` if it could not be raised.
+    pub(crate) fn new() -> Option<Self> {
+        use windows_sys::Win32::Media::{timeBeginPeriod, TIMERR_NOERROR};
+
+        // SAFETY: timeBeginPeriod has no safety requirements.
+        if unsafe { timeBeginPeriod(Self::PERIOD) } == TIMERR_NOERROR {
+            Some(Self { _private: () })
+        } else {
+            None
+        }
+    }
+}
+
+impl Drop for HighResolutionTimer {
+    fn drop(&mut self) {
+        use windows_sys::Win32::Media::timeEndPeriod;
+
+        // SAFETY: Every call to timeEndPeriod is matched with a call to timeBeginPeriod.
+        unsafe {
+            timeEndPeriod(Self::PERIOD);
+        }
+    }
 }
 
 /// Additional methods on `Window` that are specific to Windows.
+///
+/// The setters have to run on the thread that created the window, so they are sent to the event
+/// loop. The returned futures complete once the change has been made.
 pub trait WindowExtWindows: sealed::WindowPrivate {
     /// Returns the HINSTANCE of the window
     fn hinstance(&self) -> HINSTANCE;
@@ -123,18 +184,51 @@ pub trait WindowExtWindows: sealed::WindowPrivate {
     ///
     /// For more information, see <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-enablewindow#remarks>
     /// and <https://docs.microsoft.com/en-us/windows/win32/winmsg/window-features#disabled-windows>
-    fn set_enable(&self, enabled: bool);
+    fn set_enable(&self, enabled: bool) -> Pin<Box<dyn Future<Output = ()> + '_>>;
 
     /// This sets `ICON_BIG`. A good ceiling here is 256x256.
-    fn set_taskbar_icon(&self, taskbar_icon: Option<Icon>);
+    fn set_taskbar_icon(
+        &self,
+        taskbar_icon: Option<Icon>,
+    ) -> Pin<Box<dyn Future<Output = ()> + '_>>;
 
     /// Whether to show or hide the window icon in the taskbar.
-    fn set_skip_taskbar(&self, skip: bool);
+    fn set_skip_taskbar(&self, skip: bool) -> Pin<Box<dyn Future<Output = ()> + '_>>;
 
     /// Shows or hides the background drop shadow for undecorated windows.
     ///
     /// Enabling the shadow causes a thin 1px line to appear on the top of the window.
-    fn set_undecorated_shadow(&self, shadow: bool);
+    fn set_undecorated_shadow(&self, shadow: bool) -> Pin<Box<dyn Future<Output = ()> + '_>>;
+
+    /// Sets whether the title bar uses the dark theme.
+    ///
+    /// This only has an effect on Windows 10 20H1 and later.
+    fn set_dark_titlebar(&self, dark: bool) -> Pin<Box<dyn Future<Output = ()> + '_>>;
+
+    /// Sets the system-drawn backdrop material of the window.
+    ///
+    /// The backdrop only shows through where the window is transparent. This only has an effect
+    /// on Windows 11 22H2 and later.
+    fn set_system_backdrop(&self, backdrop: BackdropType)
+        -> Pin<Box<dyn Future<Output = ()> + '_>>;
+
+    /// Sets how the corners of the window are rounded.
+    ///
+    /// This only has an effect on Windows 11 and later.
+    fn set_corner_preference(
+        &self,
+        preference: CornerPreference,
+    ) -> Pin<Box<dyn Future<Output = ()> + '_>>;
+
+    /// Installs a hook that sees every message sent to this window's procedure.
+    ///
+    /// Unlike [`EventLoopBuilderExtWindows::with_msg_hook`], which sees the messages of every
+    /// window before they are dispatched, this hook is installed by subclassing only this window.
+    /// If the hook returns `Some`, the message is considered handled and the value is returned
+    /// from the window procedure. Otherwise, the message is passed on to `winit`.
+    ///
+    /// Installing a hook replaces the previous one. Passing `None` removes the hook.
+    fn set_msg_hook(&self, hook: Option<MsgHook>) -> Pin<Box<dyn Future<Output = ()> + '_>>;
 }
 
 impl<TS: ThreadSafety> WindowExtWindows for Window<TS> {
@@ -146,20 +240,253 @@ impl<TS: ThreadSafety> WindowExtWindows for Window<TS> {
         self.window().hinstance()
     }
 
-    fn set_enable(&self, enabled: bool) {
-        self.window().set_enable(enabled);
+    fn set_enable(&self, enabled: bool) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(self.win32_op(WindowOp::SetEnable(enabled)))
     }
 
-    fn set_taskbar_icon(&self, taskbar_icon: Option<Icon>) {
-        self.window().set_taskbar_icon(taskbar_icon);
+    fn set_taskbar_icon(
+        &self,
+        taskbar_icon: Option<Icon>,
+    ) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(self.win32_op(WindowOp::SetTaskbarIcon(taskbar_icon)))
     }
 
-    fn set_skip_taskbar(&self, skip: bool) {
-        self.window().set_skip_taskbar(skip);
+    fn set_skip_taskbar(&self, skip: bool) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(self.win32_op(WindowOp::SetSkipTaskbar(skip)))
     }
 
-    fn set_undecorated_shadow(&self, shadow: bool) {
-        self.window().set_undecorated_shadow(shadow);
+    fn set_undecorated_shadow(&self, shadow: bool) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(self.win32_op(WindowOp::SetUndecoratedShadow(shadow)))
+    }
+
+    fn set_dark_titlebar(&self, dark: bool) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(self.win32_op(WindowOp::SetDarkTitlebar(dark)))
+    }
+
+    fn set_system_backdrop(
+        &self,
+        backdrop: BackdropType,
+    ) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(self.win32_op(WindowOp::SetSystemBackdrop(backdrop)))
+    }
+
+    fn set_corner_preference(
+        &self,
+        preference: CornerPreference,
+    ) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(self.win32_op(WindowOp::SetCornerPreference(preference)))
+    }
+
+    fn set_msg_hook(&self, hook: Option<MsgHook>) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(self.win32_op(WindowOp::SetMsgHook(hook)))
+    }
+}
+
+/// A raw Win32 message sent to a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawMsg {
+    /// The window that the message was sent to.
+    pub hwnd: HWND,
+
+    /// The message identifier, like `WM_SIZE`.
+    pub msg: u32,
+
+    /// The `WPARAM` of the message.
+    pub wparam: usize,
+
+    /// The `LPARAM` of the message.
+    pub lparam: isize,
+}
+
+/// A per-window message hook.
+///
+/// See [`WindowExtWindows::set_msg_hook`].
+pub type MsgHook = Box<dyn FnMut(&RawMsg) -> Option<isize> + Send + 'static>;
+
+/// The system-drawn backdrop material of a window.
+///
+/// See [`WindowExtWindows::set_system_backdrop`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BackdropType {
+    /// Let the system decide.
+    #[default]
+    Auto,
+
+    /// Draw no backdrop.
+    None,
+
+    /// The Mica material, meant for long-lived windows.
+    MainWindow,
+
+    /// The Acrylic material, meant for transient windows like popups.
+    TransientWindow,
+
+    /// The tabbed variant of the Mica material, meant for windows with tabs in the title bar.
+    TabbedWindow,
+}
+
+/// How the corners of a window are rounded.
+///
+/// See [`WindowExtWindows::set_corner_preference`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CornerPreference {
+    /// Let the system decide.
+    #[default]
+    Default,
+
+    /// Never round the corners.
+    DoNotRound,
+
+    /// Round the corners.
+    Round,
+
+    /// Round the corners with a small radius.
+    RoundSmall,
+}
+
+/// A Windows-specific operation on a window, run in the event loop.
+pub(crate) enum WindowOp {
+    SetEnable(bool),
+    SetTaskbarIcon(Option<Icon>),
+    SetSkipTaskbar(bool),
+    SetUndecoratedShadow(bool),
+    SetDarkTitlebar(bool),
+    SetSystemBackdrop(BackdropType),
+    SetCornerPreference(CornerPreference),
+    SetMsgHook(Option<MsgHook>),
+}
+
+impl WindowOp {
+    /// Run the operation on the window.
+    pub(crate) fn run(self, window: &winit::window::Window) {
+        match self {
+            WindowOp::SetEnable(enabled) => window.set_enable(enabled),
+            WindowOp::SetTaskbarIcon(taskbar_icon) => window.set_taskbar_icon(taskbar_icon),
+            WindowOp::SetSkipTaskbar(skip) => window.set_skip_taskbar(skip),
+            WindowOp::SetUndecoratedShadow(shadow) => window.set_undecorated_shadow(shadow),
+            WindowOp::SetDarkTitlebar(dark) => {
+                set_dwm_attribute(window, DWMWA_USE_IMMERSIVE_DARK_MODE, dark as i32);
+            }
+            WindowOp::SetSystemBackdrop(backdrop) => {
+                let backdrop = match backdrop {
+                    BackdropType::Auto => 0,
+                    BackdropType::None => 1,
+                    BackdropType::MainWindow => 2,
+                    BackdropType::TransientWindow => 3,
+                    BackdropType::TabbedWindow => 4,
+                };
+                set_dwm_attribute(window, DWMWA_SYSTEMBACKDROP_TYPE, backdrop);
+            }
+            WindowOp::SetCornerPreference(preference) => {
+                let preference = match preference {
+                    CornerPreference::Default => 0,
+                    CornerPreference::DoNotRound => 1,
+                    CornerPreference::Round => 2,
+                    CornerPreference::RoundSmall => 3,
+                };
+                set_dwm_attribute(window, DWMWA_WINDOW_CORNER_PREFERENCE, preference);
+            }
+            WindowOp::SetMsgHook(hook) => set_msg_hook(window.hwnd(), hook),
+        }
+    }
+}
+
+/// The ID of the subclass that runs the message hook.
+const MSG_HOOK_SUBCLASS: usize = 0x6173_796e_6377_696e;
+
+/// The state of a message hook, which is passed to the subclass procedure.
+struct MsgHookState {
+    /// The hook itself.
+    ///
+    /// The hook may cause messages to be sent to the window while it runs. Those messages skip
+    /// the hook instead of calling into it again.
+    hook: RefCell<MsgHook>,
+}
+
+/// Install or remove the message hook of a window. This must be called on the window's thread.
+fn set_msg_hook(hwnd: HWND, hook: Option<MsgHook>) {
+    // SAFETY: The subclass data is always a leaked `MsgHookState`, which is reclaimed once it is
+    // replaced or removed.
+    unsafe {
+        let mut old = 0;
+        if GetWindowSubclass(hwnd, Some(msg_hook_proc), MSG_HOOK_SUBCLASS, &mut old) != 0 {
+            drop(Box::from_raw(old as *mut MsgHookState));
+        }
+
+        match hook {
+            Some(hook) => {
+                let state = Box::new(MsgHookState {
+                    hook: RefCell::new(hook),
+                });
+                let state = Box::into_raw(state) as usize;
+                if SetWindowSubclass(hwnd, Some(msg_hook_proc), MSG_HOOK_SUBCLASS, state) == 0 {
+                    drop(Box::from_raw(state as *mut MsgHookState));
+                }
+            }
+            None => {
+                RemoveWindowSubclass(hwnd, Some(msg_hook_proc), MSG_HOOK_SUBCLASS);
+            }
+        }
+    }
+}
+
+unsafe extern "system" fn msg_hook_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: usize,
+    state: usize,
+) -> LRESULT {
+    let state = state as *mut MsgHookState;
+
+    // The window is going away, so the hook has to go with it. If the hook itself destroyed the
+    // window, it is still running, so its state is leaked instead.
+    if msg == WM_NCDESTROY {
+        RemoveWindowSubclass(hwnd, Some(msg_hook_proc), MSG_HOOK_SUBCLASS);
+        if (*state).hook.try_borrow_mut().is_ok() {
+            drop(Box::from_raw(state));
+        }
+        return DefSubclassProc(hwnd, msg, wparam, lparam);
+    }
+
+    let raw = RawMsg {
+        hwnd,
+        msg,
+        wparam,
+        lparam,
+    };
+    let handled = match (*state).hook.try_borrow_mut() {
+        Ok(mut hook) => (*hook)(&raw),
+        Err(_) => None,
+    };
+
+    match handled {
+        Some(result) => result,
+        None => DefSubclassProc(hwnd, msg, wparam, lparam),
+    }
+}
+
+// Window attributes that `winit` doesn't cover, from `dwmapi.h`.
+const DWMWA_USE_IMMERSIVE_DARK_MODE: DWMWINDOWATTRIBUTE = 20;
+const DWMWA_WINDOW_CORNER_PREFERENCE: DWMWINDOWATTRIBUTE = 33;
+const DWMWA_SYSTEMBACKDROP_TYPE: DWMWINDOWATTRIBUTE = 38;
+
+/// Set a DWM attribute on a window.
+///
+/// Older versions of Windows reject attributes they don't know about, which is ignored.
+fn set_dwm_attribute(window: &winit::window::Window, attribute: DWMWINDOWATTRIBUTE, value: i32) {
+    // SAFETY: The window handle is valid while the window is alive, and the value lives for the
+    // duration of the call.
+    unsafe {
+        DwmSetWindowAttribute(
+            window.hwnd(),
+            attribute,
+            &value as *const i32 as *const c_void,
+            mem::size_of::<i32>() as u32,
+        );
     }
 }
 
@@ -200,6 +527,12 @@ pub trait WindowBuilderExtWindows: sealed::WindowBuilderPrivate {
     /// COM API regardless of this option. Currently only fullscreen mode does that, but there may be more in the future.
     /// If you need COM API with `COINIT_MULTITHREADED` you must initialize it before calling any winit functions.
     /// See <https://docs.microsoft.com/en-us/windows/win32/api/objbase/nf-objbase-coinitialize#remarks> for more information.
+    ///
+    /// The built-in drop target only reports file paths through the `HoveredFile`, `DroppedFile`
+    /// and `HoveredFileCancelled` events. It does not report the cursor position while hovering, and
+    /// always accepts files with `DROPEFFECT_COPY`. Applications that need drop-target feedback can
+    /// disable drag and drop here and register their own `IDropTarget` with `RegisterDragDrop` on
+    /// the window's `HWND`.
     fn with_drag_and_drop(self, flag: bool) -> WindowBuilder;
 
     /// Whether show or hide the window icon in the taskbar.
@@ -212,6 +545,43 @@ pub trait WindowBuilderExtWindows: sealed::WindowBuilderPrivate {
     fn with_undecorated_shadow(self, shadow: bool) -> WindowBuilder;
 }
 
+impl WindowBuilderExtWindows for WindowBuilder {
+    fn with_owner_window(mut self, parent: HWND) -> WindowBuilder {
+        self.platform.owner_window = Some(parent);
+        self
+    }
+
+    fn with_menu(mut self, menu: HMENU) -> WindowBuilder {
+        self.platform.menu = Some(menu);
+        self
+    }
+
+    fn with_taskbar_icon(mut self, taskbar_icon: Option<Icon>) -> WindowBuilder {
+        self.platform.taskbar_icon = taskbar_icon;
+        self
+    }
+
+    fn with_no_redirection_bitmap(mut self, flag: bool) -> WindowBuilder {
+        self.platform.no_redirection_bitmap = Some(flag);
+        self
+    }
+
+    fn with_drag_and_drop(mut self, flag: bool) -> WindowBuilder {
+        self.platform.drag_and_drop = Some(flag);
+        self
+    }
+
+    fn with_skip_taskbar(mut self, skip: bool) -> WindowBuilder {
+        self.platform.skip_taskbar = Some(skip);
+        self
+    }
+
+    fn with_undecorated_shadow(mut self, shadow: bool) -> WindowBuilder {
+        self.platform.undecorated_shadow = Some(shadow);
+        self
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct PlatformSpecific {
     owner_window: Option<HWND>,
@@ -27,9 +27,14 @@ use crate::sync::ThreadSafety;
 use crate::window::{Window, WindowBuilder};
 
 use std::os::raw;
+use std::ptr;
 
+use once_cell::sync::OnceCell;
+use raw_window_handle::{HasRawDisplayHandle, RawDisplayHandle, RawWindowHandle, XlibWindowHandle};
 use winit::dpi::Size;
 use winit::platform::x11::{EventLoopBuilderExtX11 as _, WindowExtX11 as _};
+use x11_dl::xlib::XVisualInfo;
+use x11_dl::xlib_xcb::Xlib_xcb;
 
 #[doc(inline)]
 pub use winit::platform::x11::{register_xlib_error_hook, XWindowType, XlibErrorHook};
@@ -40,6 +45,28 @@ pub use winit::platform::x11::{register_xlib_error_hook, XWindowType, XlibErrorH
 pub trait EventLoopWindowTargetExtX11: sealed::EventLoopWindowTargetPrivate {
     /// True if the [`EventLoopWindowTarget`] uses X11.
     fn is_x11(&self) -> bool;
+
+    /// Returns a pointer to the `Display` object of xlib that is used by this
+    /// [`EventLoopWindowTarget`].
+    ///
+    /// Returns `None` if the [`EventLoopWindowTarget`] doesn't use xlib (if it uses wayland for
+    /// example).
+    ///
+    /// The pointer will become invalid when the [`EventLoop`] is destroyed.
+    ///
+    /// [`EventLoop`]: crate::event_loop::EventLoop
+    fn xlib_display(&self) -> Option<*mut raw::c_void>;
+
+    /// Returns the underlying `xcb_connection_t` of the xlib `Display` used by this
+    /// [`EventLoopWindowTarget`].
+    ///
+    /// Returns `None` if the [`EventLoopWindowTarget`] doesn't use xlib (if it uses wayland for
+    /// example), or if `libX11-xcb` could not be loaded.
+    ///
+    /// The pointer will become invalid when the [`EventLoop`] is destroyed.
+    ///
+    /// [`EventLoop`]: crate::event_loop::EventLoop
+    fn xcb_connection(&self) -> Option<*mut raw::c_void>;
 }
 
 impl<TS: ThreadSafety> EventLoopWindowTargetExtX11 for EventLoopWindowTarget<TS> {
@@ -47,8 +74,40 @@ impl<TS: ThreadSafety> EventLoopWindowTargetExtX11 for EventLoopWindowTarget<TS>
     fn is_x11(&self) -> bool {
         !self.is_wayland
     }
+
+    #[inline]
+    fn xlib_display(&self) -> Option<*mut raw::c_void> {
+        match self.raw_display_handle() {
+            RawDisplayHandle::Xlib(handle) => Some(handle.display),
+            _ => None,
+        }
+    }
+
+    fn xcb_connection(&self) -> Option<*mut raw::c_void> {
+        let display = self.xlib_display()?;
+        let xlib_xcb = XLIB_XCB.get_or_init(|| Xlib_xcb::open().ok().map(XlibXcb));
+        let xlib_xcb = &xlib_xcb.as_ref()?.0;
+
+        // SAFETY: The display is a valid xlib display while the event loop is alive.
+        let connection = unsafe { (xlib_xcb.XGetXCBConnection)(display.cast()) };
+        if connection.is_null() {
+            None
+        } else {
+            Some(connection.cast())
+        }
+    }
 }
 
+/// The `libX11-xcb` library, loaded on first use.
+static XLIB_XCB: OnceCell<Option<XlibXcb>> = OnceCell::new();
+
+struct XlibXcb(Xlib_xcb);
+
+// SAFETY: The library only holds function pointers and the library handle, which are never
+// modified after it is loaded.
+unsafe impl Send for XlibXcb {}
+unsafe impl Sync for XlibXcb {}
+
 /// Additional methods on [`EventLoopBuilder`] that are specific to X11.
 ///
 /// [`EventLoopBuilder`]: crate::event_loop::EventLoopBuilder
@@ -125,6 +184,16 @@ impl<TS: ThreadSafety> WindowExtX11 for Window<TS> {
 ///
 /// [`WindowBuilder`]: crate::window::WindowBuilder
 pub trait WindowBuilderExtX11: sealed::WindowBuilderPrivate {
+    /// Build the window with the given X11 visual.
+    ///
+    /// `visual_infos` must point to a valid `XVisualInfo` structure. It is copied immediately,
+    /// so it does not need to outlive this call. A colormap for the visual is created
+    /// automatically when the window is built.
+    ///
+    /// This is useful for creating windows with a specific depth, for example for transparency
+    /// or for a GL configuration that requires a particular visual.
+    fn with_x11_visual<T>(self, visual_infos: *const T) -> Self;
+
     fn with_x11_screen(self, screen_id: i32) -> Self;
 
     /// Build window with the given `general` and `instance` names.
@@ -155,9 +224,23 @@ pub trait WindowBuilderExtX11: sealed::WindowBuilderPrivate {
     /// WindowBuilder::new().with_base_size(PhysicalSize::new(400, 200));
     /// ```
     fn with_base_size<S: Into<Size>>(self, base_size: S) -> Self;
+
+    /// Embed this window into another parent window.
+    ///
+    /// The window is created as a child of the X11 window with the given ID and is confined to
+    /// its client area. This can be used to host a window inside of another application, such as
+    /// a plugin UI or a panel.
+    fn with_embed_parent_window(self, parent_window_id: raw::c_ulong) -> Self;
 }
 
 impl WindowBuilderExtX11 for WindowBuilder {
+    fn with_x11_visual<T>(mut self, visual_infos: *const T) -> Self {
+        // SAFETY: The caller guarantees that this points to a valid `XVisualInfo`.
+        let visual_infos = unsafe { ptr::read(visual_infos.cast::<XVisualInfo>()) };
+        self.platform.set_x11_visual(VisualInfo(visual_infos));
+        self
+    }
+
     fn with_x11_screen(mut self, screen_id: i32) -> Self {
         self.platform.set_x11_screen_id(screen_id);
         self
@@ -183,18 +266,29 @@ impl WindowBuilderExtX11 for WindowBuilder {
         self.platform.set_x11_base_size(base_size.into());
         self
     }
+
+    fn with_embed_parent_window(mut self, parent_window_id: raw::c_ulong) -> Self {
+        self.platform.set_x11_embed_parent(parent_window_id);
+        self
+    }
 }
 
 #[derive(Default)]
 pub(crate) struct PlatformSpecific {
+    pub x11_visual: Option<VisualInfo>,
     pub x11_window_type: Vec<XWindowType>,
     pub x11_name: Option<(String, String)>,
     pub x11_screen_id: Option<i32>,
     pub x11_override_redirect: bool,
     pub x11_base_size: Option<Size>,
+    pub x11_embed_parent: Option<raw::c_ulong>,
 }
 
 impl PlatformSpecific {
+    pub(crate) fn set_x11_visual(&mut self, x11_visual: VisualInfo) {
+        self.x11_visual = Some(x11_visual);
+    }
+
     pub(crate) fn set_x11_window_type(&mut self, x11_window_type: Vec<XWindowType>) {
         self.x11_window_type = x11_window_type;
     }
@@ -215,6 +309,10 @@ impl PlatformSpecific {
         self.x11_base_size = Some(x11_base_size);
     }
 
+    pub(crate) fn set_x11_embed_parent(&mut self, x11_embed_parent: raw::c_ulong) {
+        self.x11_embed_parent = Some(x11_embed_parent);
+    }
+
     pub(crate) fn apply_to(
         self,
         window_builder: winit::window::WindowBuilder,
@@ -222,6 +320,9 @@ impl PlatformSpecific {
         use winit::platform::x11::WindowBuilderExtX11 as _;
 
         let mut window_builder = window_builder;
+        if let Some(VisualInfo(visual_infos)) = self.x11_visual {
+            window_builder = window_builder.with_x11_visual(&visual_infos as *const XVisualInfo);
+        }
         if let Some(screen_id) = self.x11_screen_id {
             window_builder = window_builder.with_x11_screen(screen_id);
         }
@@ -234,6 +335,23 @@ impl PlatformSpecific {
         if let Some(base_size) = self.x11_base_size {
             window_builder = window_builder.with_base_size(base_size);
         }
+        if let Some(parent) = self.x11_embed_parent {
+            let mut handle = XlibWindowHandle::empty();
+            handle.window = parent;
+
+            // SAFETY: X11 reports an invalid parent window as a protocol error rather than
+            // invoking undefined behavior.
+            window_builder =
+                unsafe { window_builder.with_parent_window(Some(RawWindowHandle::Xlib(handle))) };
+        }
         window_builder
     }
 }
+
+/// A copy of an `XVisualInfo` that can be sent to the event loop thread.
+pub(crate) struct VisualInfo(XVisualInfo);
+
+// SAFETY: The `Visual` pointer refers to data owned by the X server connection, which outlives
+// the window builder, and is never dereferenced by us.
+unsafe impl Send for VisualInfo {}
+unsafe impl Sync for VisualInfo {}
@@ -18,31 +18,47 @@ Public License along with `async-winit`. If not, see <https://www.gnu.org/licens
 
 //! The shared reactor used by the runtime.
 
-use crate::filter::ReactorWaker;
+use crate::filter::{FlowOptions, ReactorWaker};
 use crate::handler::Handler;
+use crate::metrics::Metrics;
 use crate::oneoff::Complete;
 use crate::sync::{ThreadSafety, __private::*};
+use crate::watchdog::{self, Culprit, Watchdog};
 use crate::window::registration::Registration as WinRegistration;
-use crate::window::WindowBuilder;
+use crate::window::{WindowBuilder, WindowInner};
 
-use std::collections::{BTreeMap, HashMap};
+use std::cmp;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::fmt;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::task::Waker;
-use std::time::{Duration, Instant};
+use std::thread::{self, ThreadId};
+use std::time::Duration;
 
+use instant::Instant;
 use winit::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
 use winit::error::{ExternalError, NotSupportedError, OsError};
-use winit::event_loop::DeviceEventFilter;
+use winit::event_loop::{DeviceEventFilter, EventLoopClosed};
 use winit::monitor::MonitorHandle;
 use winit::window::{
     CursorGrabMode, CursorIcon, Fullscreen, Icon, ImePurpose, ResizeDirection, Theme,
     UserAttentionType, Window, WindowId, WindowLevel,
 };
 
+/// The number of shards that the timer wheel is split into.
+///
+/// Each shard has its own lock, so that timers can be inserted and removed from multiple threads
+/// without contending on a single lock.
+const TIMER_SHARDS: usize = 16;
+
 const NEEDS_EXIT: i64 = 0x1;
-const EXIT_CODE_SHIFT: u32 = 1;
+const EXIT_ANNOUNCED: i64 = 0x2;
+const EXIT_PENDING: i64 = 0x4;
+const EXIT_CODE_SHIFT: u32 = 3;
 
 #[doc(hidden)]
 pub struct Reactor<T: ThreadSafety> {
@@ -50,54 +66,193 @@ pub struct Reactor<T: ThreadSafety> {
     exit_code: T::AtomicI64,
 
     /// The channel used to send event loop operation requests.
+    ///
+    /// This holds up to 1024 operations for both thread safety modes. Once it is full, callers wait
+    /// for the event loop to make room instead of growing the queue.
     evl_ops: (T::Sender<EventLoopOp<T>>, T::Receiver<EventLoopOp<T>>),
 
     /// The list of windows.
-    windows: T::Mutex<HashMap<WindowId, T::Rc<WinRegistration<T>>>>,
+    ///
+    /// This is read for almost every event, but only written to when windows are created or
+    /// destroyed.
+    windows: T::RwLock<HashMap<WindowId, WindowEntry<T>>>,
+
+    /// The window that most recently received an event.
+    ///
+    /// Events tend to come in runs for the same window (and most applications only have one
+    /// window), so this lets us skip the window list entirely. This is only written to with the
+    /// window list locked, so it can't go stale.
+    last_window: T::Mutex<Option<LastWindow<T>>>,
+
+    /// The number of windows in the window list.
+    ///
+    /// This can be read without locking the window list.
+    window_count: T::AtomicUsize,
+
+    /// Windows that have been created, but not yet announced through `window_created`.
+    created_windows: T::Mutex<Vec<WindowId>>,
+
+    /// Nonzero if `created_windows` may be non-empty.
+    ///
+    /// This lets us avoid locking `created_windows` for every event.
+    has_created_windows: T::AtomicUsize,
 
     /// The event loop proxy.
     ///
     /// Used to wake up the event loop.
     proxy: T::OnceLock<Arc<ReactorWaker>>,
 
-    /// The timer wheel.
-    timers: T::Mutex<BTreeMap<(Instant, usize), Waker>>,
+    /// The thread that the event loop is running on.
+    event_loop_thread: T::OnceLock<ThreadId>,
+
+    /// Options for choosing the control flow.
+    flow_options: T::Mutex<FlowOptions>,
 
-    /// Queue of timer operations.
-    timer_op_queue: T::ConcurrentQueue<TimerOp>,
+    /// The time at which this reactor was created.
+    ///
+    /// Used as a reference point for coalescing timers.
+    epoch: Instant,
+
+    /// The timer wheel, split into shards.
+    ///
+    /// Timers are assigned to a shard based on their ID.
+    timers: Box<[T::Mutex<TimerShard>]>,
 
     /// The last timer ID we used.
     timer_id: T::AtomicUsize,
 
+    /// The shard to start firing timers from, so that no shard is starved by the timer budget.
+    timer_cursor: T::AtomicUsize,
+
+    /// The maximum number of timers to fire per pass.
+    timer_budget: T::AtomicUsize,
+
+    /// The maximum number of event loop operations to run per pass.
+    op_budget: T::AtomicUsize,
+
+    /// The number of expired timers whose firing was deferred by the timer budget.
+    deferred_timers: T::AtomicUsize,
+
+    /// The number of event loop operations that were deferred by the operation budget.
+    deferred_ops: T::AtomicUsize,
+
+    /// The number of operations at the front of the queue that were already counted as deferred.
+    counted_ops: T::AtomicUsize,
+
+    /// The buffer that event loop operations are drained into before being run.
+    op_batch: T::Mutex<Vec<EventLoopOp<T>>>,
+
+    /// Functions that can cancel exit requests.
+    exit_interceptors: T::Mutex<Vec<T::ExitInterceptor>>,
+
+    /// The number of times the application has been resumed.
+    resume_count: T::AtomicUsize,
+
+    /// The number of times the application has been suspended.
+    suspend_count: T::AtomicUsize,
+
+    /// The windows that are currently occluded.
+    occluded_windows: T::Mutex<HashSet<WindowId>>,
+
+    /// The number of windows in `occluded_windows`.
+    occluded_count: T::AtomicUsize,
+
+    /// Wakers waiting for the application to come back into the foreground.
+    background_wakers: T::Mutex<Vec<Waker>>,
+
+    /// Whether or not latency metrics are being collected.
+    metrics_enabled: T::AtomicUsize,
+
+    /// The latency metrics collected so far.
+    metrics: T::Mutex<Metrics>,
+
+    /// Whether or not the watchdog is enabled.
+    watchdog_enabled: T::AtomicUsize,
+
+    /// The watchdog that reports slow events and operations.
+    watchdog: T::Mutex<Option<Watchdog>>,
+
     /// Registration for event loop events.
     pub(crate) evl_registration: GlobalRegistration<T>,
+
+    /// The executor used to run spawned tasks.
+    #[cfg(feature = "executor")]
+    pub(crate) executor: T::Executor,
+
+    /// The Android app that the event loop was created with.
+    #[cfg(android_platform)]
+    pub(crate) android_app: T::OnceLock<crate::platform::android::activity::AndroidApp>,
+
+    /// The last content rectangle of the Android app that was seen.
+    #[cfg(android_platform)]
+    content_rect: T::Mutex<Option<crate::platform::android::activity::Rect>>,
 }
 
-enum TimerOp {
-    /// Add a new timer.
-    InsertTimer(Instant, usize, Waker),
+/// The ID and registration of the window that most recently received an event.
+type LastWindow<T> = (WindowId, <T as __ThreadSafety>::Rc<WinRegistration<T>>);
+
+/// A window in the window list, along with its registration.
+type RegisteredWindow<T> = (
+    <T as __ThreadSafety>::Rc<WindowInner<T>>,
+    <T as __ThreadSafety>::Rc<WinRegistration<T>>,
+);
 
-    /// Delete an existing timer.
-    RemoveTimer(Instant, usize),
+/// A window in the window list.
+struct WindowEntry<T: ThreadSafety> {
+    /// A weak reference to the window.
+    ///
+    /// This is weak so that the window list doesn't keep the window alive.
+    window: <T::Rc<WindowInner<T>> as Rc<WindowInner<T>>>::Weak,
+
+    /// The registration for the window.
+    registration: T::Rc<WinRegistration<T>>,
 }
 
 impl<TS: ThreadSafety> Reactor<TS> {
     /// Create an empty reactor.
+    ///
+    /// Only the global instance, managed through `get()` and `reset()`, should be created.
     pub(crate) fn new() -> Self {
-        static ALREADY_EXISTS: AtomicBool = AtomicBool::new(false);
-        if ALREADY_EXISTS.swap(true, Ordering::SeqCst) {
-            panic!("Only one instance of `Reactor` can exist at a time");
-        }
-
         Reactor {
             exit_code: <TS::AtomicI64>::new(0),
             proxy: TS::OnceLock::new(),
+            event_loop_thread: TS::OnceLock::new(),
             evl_ops: TS::channel_bounded(1024),
-            windows: TS::Mutex::new(HashMap::new()),
-            timers: TS::Mutex::new(BTreeMap::new()),
-            timer_op_queue: TS::ConcurrentQueue::bounded(1024),
+            windows: TS::RwLock::new(HashMap::new()),
+            last_window: TS::Mutex::new(None),
+            window_count: TS::AtomicUsize::new(0),
+            created_windows: TS::Mutex::new(Vec::new()),
+            has_created_windows: TS::AtomicUsize::new(0),
+            flow_options: TS::Mutex::new(FlowOptions::default()),
+            epoch: Instant::now(),
+            timers: (0..TIMER_SHARDS)
+                .map(|_| TS::Mutex::new(TimerShard::default()))
+                .collect(),
             timer_id: TS::AtomicUsize::new(1),
+            timer_cursor: TS::AtomicUsize::new(0),
+            timer_budget: TS::AtomicUsize::new(usize::MAX),
+            op_budget: TS::AtomicUsize::new(usize::MAX),
+            deferred_timers: TS::AtomicUsize::new(0),
+            deferred_ops: TS::AtomicUsize::new(0),
+            counted_ops: TS::AtomicUsize::new(0),
+            op_batch: TS::Mutex::new(Vec::new()),
+            exit_interceptors: TS::Mutex::new(Vec::new()),
+            resume_count: TS::AtomicUsize::new(0),
+            suspend_count: TS::AtomicUsize::new(0),
+            occluded_windows: TS::Mutex::new(HashSet::new()),
+            occluded_count: TS::AtomicUsize::new(0),
+            background_wakers: TS::Mutex::new(Vec::new()),
+            metrics_enabled: TS::AtomicUsize::new(0),
+            metrics: TS::Mutex::new(Metrics::default()),
+            watchdog_enabled: TS::AtomicUsize::new(0),
+            watchdog: TS::Mutex::new(None),
             evl_registration: GlobalRegistration::new(),
+            #[cfg(feature = "executor")]
+            executor: TS::Executor::new(),
+            #[cfg(android_platform)]
+            android_app: TS::OnceLock::new(),
+            #[cfg(android_platform)]
+            content_rect: TS::Mutex::new(None),
         }
     }
 
@@ -106,9 +261,211 @@ impl<TS: ThreadSafety> Reactor<TS> {
         TS::get_reactor()
     }
 
+    /// Discard the global instance of this reactor.
+    ///
+    /// The next call to `get()` creates a fresh reactor. Any handles to the old reactor that are
+    /// still alive (e.g. timers or windows from the previous event loop) keep it alive, but they
+    /// will no longer receive events.
+    pub(crate) fn reset() {
+        TS::reset_reactor()
+    }
+
+    /// Run a future alongside the tasks spawned onto this reactor.
+    ///
+    /// If the `executor` feature is disabled, this just runs the future.
+    pub(crate) async fn run_tasks<F: Future>(&self, future: F) -> F::Output {
+        #[cfg(feature = "executor")]
+        {
+            self.executor.run(Box::pin(future)).await
+        }
+
+        #[cfg(not(feature = "executor"))]
+        {
+            future.await
+        }
+    }
+
+    /// Run a future until the application is suspended.
+    ///
+    /// Returns `None` if the future was cancelled by a suspension. The suspension is held until the
+    /// future has been dropped.
+    #[cfg(feature = "executor")]
+    pub(crate) async fn until_suspend<F: Future>(&self, future: F) -> Option<F::Output> {
+        use futures_lite::FutureExt;
+
+        let mut suspended = self.evl_registration.suspended.wait();
+
+        // Drop the future before releasing the hold guard.
+        let result = async { Ok(future.await) }
+            .or(async { Err(suspended.hold().await) })
+            .await;
+
+        result.ok()
+    }
+
+    /// Run a future created by `factory` until the application is suspended, then create it again
+    /// once the application is resumed.
+    ///
+    /// Returns once one of the futures completes without being cancelled.
+    #[cfg(feature = "executor")]
+    pub(crate) async fn resumable<Fut: Future>(&self, mut factory: impl FnMut() -> Fut) {
+        loop {
+            // Set up the listener first to avoid missing a `Resumed` event.
+            let resumed = self.evl_registration.resumed.wait();
+
+            if self.until_suspend(factory()).await.is_some() {
+                return;
+            }
+
+            resumed.await;
+        }
+    }
+
+    /// Wait until the application has been resumed at least once.
+    ///
+    /// Windows can only be created after the first `Resumed` event on some platforms.
+    pub(crate) async fn wait_for_first_resume(&self) {
+        // Set up the listener before checking the count, so the event can't be missed.
+        let resumed = self.evl_registration.resumed.wait();
+
+        if self.resume_count.load(Ordering::SeqCst) == 0 {
+            resumed.await;
+        }
+    }
+
+    /// Get the options for choosing the control flow.
+    pub(crate) fn flow_options(&self) -> FlowOptions {
+        *self.flow_options.lock().unwrap()
+    }
+
+    /// Set the options for choosing the control flow.
+    pub(crate) fn set_flow_options(&self, options: FlowOptions) {
+        *self.flow_options.lock().unwrap() = options;
+
+        // Keep the budgets where they can be read without locking.
+        self.timer_budget.store(
+            options.timer_budget.map_or(usize::MAX, NonZeroUsize::get),
+            Ordering::Relaxed,
+        );
+        self.op_budget.store(
+            options.op_budget.map_or(usize::MAX, NonZeroUsize::get),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// The number of expired timers whose firing has been deferred by the timer budget.
+    pub(crate) fn deferred_timers(&self) -> usize {
+        self.deferred_timers.load(Ordering::Relaxed)
+    }
+
+    /// The number of event loop operations that have been deferred by the operation budget.
+    pub(crate) fn deferred_ops(&self) -> usize {
+        self.deferred_ops.load(Ordering::Relaxed)
+    }
+
+    /// Enable or disable the collection of latency metrics.
+    pub(crate) fn set_metrics_enabled(&self, enabled: bool) {
+        self.metrics_enabled
+            .store(enabled as usize, Ordering::SeqCst);
+    }
+
+    /// Get a snapshot of the latency metrics, if they are being collected.
+    pub(crate) fn metrics(&self) -> Option<Metrics> {
+        if self.metrics_enabled.load(Ordering::Relaxed) == 0 {
+            return None;
+        }
+
+        Some(self.metrics.lock().unwrap().clone())
+    }
+
+    /// Set the watchdog used to report slow events and operations.
+    pub(crate) fn set_watchdog(&self, watchdog: Option<Watchdog>) {
+        let enabled = watchdog.is_some();
+        *self.watchdog.lock().unwrap() = watchdog;
+        self.watchdog_enabled
+            .store(enabled as usize, Ordering::SeqCst);
+    }
+
+    /// Report a dispatch that started at `start` to the watchdog, if it took too long.
+    fn check_watchdog(&self, culprit: Culprit, start: Instant) {
+        if let Some(watchdog) = &mut *self.watchdog.lock().unwrap() {
+            watchdog.check(culprit, start);
+        }
+    }
+
+    /// The number of times that the application has been suspended.
+    #[cfg(any(feature = "glutin", feature = "softbuffer", feature = "wgpu"))]
+    pub(crate) fn suspend_count(&self) -> usize {
+        self.suspend_count.load(Ordering::SeqCst)
+    }
+
+    /// Whether the application is in the background.
+    ///
+    /// The application is in the background if it is suspended (or hasn't been resumed yet), or if
+    /// all of its windows are occluded.
+    pub(crate) fn in_background(&self) -> bool {
+        let resumed = self.resume_count.load(Ordering::SeqCst);
+        let suspended = self.suspend_count.load(Ordering::SeqCst);
+        if resumed == suspended {
+            return true;
+        }
+
+        // Use the counts, so that we don't have to lock the window list.
+        let windows = self.window_count.load(Ordering::SeqCst);
+        windows != 0 && self.occluded_count.load(Ordering::SeqCst) >= windows
+    }
+
+    /// Mark a window as occluded or not.
+    fn set_occluded(&self, id: WindowId, occluded: bool) {
+        let mut occluded_windows = self.occluded_windows.lock().unwrap();
+        if occluded {
+            occluded_windows.insert(id);
+        } else {
+            occluded_windows.remove(&id);
+        }
+
+        self.occluded_count
+            .store(occluded_windows.len(), Ordering::SeqCst);
+    }
+
+    /// Register a waker to be woken up once the application may have come back into the
+    /// foreground.
+    pub(crate) fn register_background_waker(&self, waker: &Waker) {
+        let mut wakers = self.background_wakers.lock().unwrap();
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+
+    /// Wake up everything that is waiting for the application to come back into the foreground.
+    fn wake_background_wakers(&self) {
+        let wakers = std::mem::take(&mut *self.background_wakers.lock().unwrap());
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
     /// Set the event loop proxy.
+    ///
+    /// This is called from the event loop thread.
     pub(crate) fn set_proxy(&self, proxy: Arc<ReactorWaker>) {
         self.proxy.set(proxy).ok();
+        self.event_loop_thread.set(thread::current().id()).ok();
+    }
+
+    /// Whether or not we are currently on the event loop thread.
+    ///
+    /// This is only needed by the features that can block on the event loop.
+    #[cfg_attr(
+        not(any(
+            feature = "thread_safe",
+            feature = "arboard",
+            feature = "glutin",
+            feature = "wgpu",
+            feature = "ash"
+        )),
+        allow(dead_code)
+    )]
+    pub(crate) fn on_event_loop_thread(&self) -> bool {
+        self.event_loop_thread.get() == Some(&thread::current().id())
     }
 
     /// Get whether or not we need to exit, and the code as well.
@@ -121,9 +478,37 @@ impl<TS: ThreadSafety> Reactor<TS> {
         }
     }
 
+    /// Get the exit code if an exit has been requested, but not yet announced to the
+    /// `exit_requested` handler.
+    fn take_exit_announcement(&self) -> Option<i32> {
+        let mut value = self.exit_code.load(Ordering::SeqCst);
+
+        loop {
+            if value & NEEDS_EXIT == 0 || value & EXIT_ANNOUNCED != 0 {
+                return None;
+            }
+
+            // Only announce the exit if nobody else changed the state in the meantime.
+            match self.exit_code.compare_exchange(
+                value,
+                value | EXIT_ANNOUNCED,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => return Some((value >> EXIT_CODE_SHIFT) as i32),
+                Err(actual) => value = actual,
+            }
+        }
+    }
+
     /// Request that the event loop exit.
+    ///
+    /// The exit is not committed until the exit interceptors have approved it.
     pub(crate) fn request_exit(&self, code: i32) {
-        let value = NEEDS_EXIT | (code as i64) << EXIT_CODE_SHIFT;
+        let value = EXIT_PENDING | (code as i64) << EXIT_CODE_SHIFT;
+
+        #[cfg(feature = "log")]
+        log::debug!("exit requested with code {}", code);
 
         // Set the exit code.
         self.exit_code.store(value, Ordering::SeqCst);
@@ -132,97 +517,301 @@ impl<TS: ThreadSafety> Reactor<TS> {
         self.notify();
     }
 
+    /// Register a function that can cancel exit requests.
+    pub(crate) fn intercept_exit(&self, interceptor: TS::ExitInterceptor) {
+        self.exit_interceptors.lock().unwrap().push(interceptor);
+    }
+
+    /// Run the exit interceptors for a pending exit request, and commit it if they all approve.
+    async fn process_exit_request(&self) {
+        let value = self.exit_code.load(Ordering::SeqCst);
+        if value & EXIT_PENDING == 0 {
+            return;
+        }
+        let code = (value >> EXIT_CODE_SHIFT) as i32;
+
+        // Run the interceptors outside of the lock.
+        let mut interceptors = std::mem::take(&mut *self.exit_interceptors.lock().unwrap());
+        let mut approved = true;
+        for interceptor in &mut interceptors {
+            if !interceptor.intercept(code).await {
+                approved = false;
+                break;
+            }
+        }
+
+        // Put the interceptors back, keeping any that were registered in the meantime.
+        {
+            let mut current = self.exit_interceptors.lock().unwrap();
+            interceptors.append(&mut current);
+            *current = interceptors;
+        }
+
+        // Only commit if nobody requested a different exit while the interceptors were running.
+        let new = if approved {
+            NEEDS_EXIT | (code as i64) << EXIT_CODE_SHIFT
+        } else {
+            0
+        };
+
+        if self
+            .exit_code
+            .compare_exchange(value, new, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            #[cfg(feature = "log")]
+            if approved {
+                log::info!("exiting with code {}", code);
+            } else {
+                log::debug!("exit with code {} was cancelled by an interceptor", code);
+            }
+        }
+    }
+
     /// Insert a new timer into the timer wheel.
-    pub(crate) fn insert_timer(&self, deadline: Instant, waker: &Waker) -> usize {
+    ///
+    /// The timer may fire up to `slack` later than `deadline`, so that it can be coalesced with
+    /// other timers.
+    pub(crate) fn insert_timer(&self, deadline: Instant, slack: Duration, waker: &Waker) -> usize {
+        let deadline = self.coalesce(deadline, slack);
+
         // Generate a new ID.
         let id = self.timer_id.fetch_add(1, Ordering::Relaxed);
 
         // Insert the timer into the timer wheel.
-        let mut op = TimerOp::InsertTimer(deadline, id, waker.clone());
-        while let Err(e) = self.timer_op_queue.push(op) {
-            // Process incoming timer operations.
-            let mut timers = self.timers.lock().unwrap();
-            self.process_timer_ops(&mut timers);
-            op = e;
-        }
+        self.timer_shard(id)
+            .lock()
+            .unwrap()
+            .insert(deadline, id, waker.clone());
 
-        // Notify that we have new timers.
-        self.notify();
+        // Notify that we have new timers, unless the event loop is going to wake up in time anyway.
+        if let Some(proxy) = self.proxy.get() {
+            proxy.notify_by(deadline);
+        }
 
         // Return the ID.
         id
     }
 
     /// Remove a timer from the timer wheel.
-    pub(crate) fn remove_timer(&self, deadline: Instant, id: usize) {
-        let mut op = TimerOp::RemoveTimer(deadline, id);
-        while let Err(e) = self.timer_op_queue.push(op) {
-            // Process incoming timer operations.
-            let mut timers = self.timers.lock().unwrap();
-            self.process_timer_ops(&mut timers);
-            op = e;
+    pub(crate) fn remove_timer(&self, id: usize) {
+        let waker = self.timer_shard(id).lock().unwrap().remove(id);
+
+        if let Some(waker) = waker {
+            // Don't let a waker that panics on drop blow everything up.
+            std::panic::catch_unwind(|| drop(waker)).ok();
+        }
+    }
+
+    /// Replace the waker of a registered timer.
+    ///
+    /// Returns `false` if the timer is no longer registered.
+    pub(crate) fn update_timer_waker(&self, id: usize, waker: &Waker) -> bool {
+        let old = self.timer_shard(id).lock().unwrap().update(id, waker);
+
+        match old {
+            Some(old) => {
+                // Don't let a waker that panics on drop blow everything up.
+                std::panic::catch_unwind(|| drop(old)).ok();
+                true
+            }
+            None => false,
         }
     }
 
+    /// Get the shard of the timer wheel that a timer belongs to.
+    fn timer_shard(&self, id: usize) -> &TS::Mutex<TimerShard> {
+        &self.timers[id % self.timers.len()]
+    }
+
+    /// Round a deadline up to the next multiple of `slack` since the reactor was created.
+    ///
+    /// Timers with the same slack and nearby deadlines end up with the same wakeup time, so they
+    /// can be handled in a single wakeup.
+    fn coalesce(&self, deadline: Instant, slack: Duration) -> Instant {
+        if slack.is_zero() {
+            return deadline;
+        }
+
+        let since_epoch = match deadline.checked_duration_since(self.epoch) {
+            Some(since_epoch) => since_epoch.as_nanos(),
+            None => return deadline,
+        };
+        let slack = slack.as_nanos();
+        let rounded = (since_epoch + slack - 1) / slack * slack;
+
+        u64::try_from(rounded)
+            .ok()
+            .and_then(|rounded| self.epoch.checked_add(Duration::from_nanos(rounded)))
+            .unwrap_or(deadline)
+    }
+
     /// Insert a window into the window list.
-    pub(crate) fn insert_window(&self, id: WindowId) -> TS::Rc<WinRegistration<TS>> {
-        let mut windows = self.windows.lock().unwrap();
+    pub(crate) fn insert_window(
+        &self,
+        window: &TS::Rc<WindowInner<TS>>,
+    ) -> TS::Rc<WinRegistration<TS>> {
         let registration = TS::Rc::new(WinRegistration::new());
-        windows.insert(id, registration.clone());
+        {
+            let mut windows = self.windows.write().unwrap();
+            windows.insert(
+                window.id(),
+                WindowEntry {
+                    window: TS::Rc::downgrade(window),
+                    registration: registration.clone(),
+                },
+            );
+            self.window_count.store(windows.len(), Ordering::SeqCst);
+        }
+
+        #[cfg(feature = "log")]
+        log::debug!("registered window {:?}", window.id());
+
+        // Announce the new window from the event loop.
+        self.created_windows.lock().unwrap().push(window.id());
+        self.has_created_windows.store(1, Ordering::SeqCst);
+        self.notify();
+
         registration
     }
 
+    /// Get a window and its registration from the window list.
+    pub(crate) fn window(&self, id: WindowId) -> Option<RegisteredWindow<TS>> {
+        let windows = self.windows.read().unwrap();
+        let entry = windows.get(&id)?;
+        let window = TS::Rc::upgrade(&entry.window)?;
+        Some((window, entry.registration.clone()))
+    }
+
+    /// Get all of the windows in the window list, along with their registrations.
+    pub(crate) fn windows(&self) -> Vec<RegisteredWindow<TS>> {
+        let windows = self.windows.read().unwrap();
+        windows
+            .values()
+            .filter_map(|entry| {
+                let window = TS::Rc::upgrade(&entry.window)?;
+                Some((window, entry.registration.clone()))
+            })
+            .collect()
+    }
+
+    /// Get the registration for a window.
+    fn registration(&self, id: WindowId) -> Option<TS::Rc<WinRegistration<TS>>> {
+        // Fast path: the event is for the same window as the last one.
+        if let Some((last_id, registration)) = &*self.last_window.lock().unwrap() {
+            if *last_id == id {
+                return Some(registration.clone());
+            }
+        }
+
+        let windows = self.windows.read().unwrap();
+        let registration = windows.get(&id)?.registration.clone();
+        *self.last_window.lock().unwrap() = Some((id, registration.clone()));
+
+        Some(registration)
+    }
+
     /// Remove a window from the window list.
     pub(crate) fn remove_window(&self, id: WindowId) {
-        let mut windows = self.windows.lock().unwrap();
-        windows.remove(&id);
+        self.remove_window_entry(id, |_| true);
     }
 
-    /// Process pending timer operations.
-    fn process_timer_ops(&self, timers: &mut BTreeMap<(Instant, usize), Waker>) {
-        // Limit the number of operations we process at once to avoid starving other tasks.
-        let limit = self.timer_op_queue.capacity();
+    /// Remove a window once the last handle to it has been dropped.
+    ///
+    /// By then the window may have been destroyed and its ID reused by a new window, so the entry
+    /// is only removed if the window it refers to no longer exists.
+    pub(crate) fn release_window(&self, id: WindowId) {
+        self.remove_window_entry(id, |entry| TS::Rc::upgrade(&entry.window).is_none());
+    }
 
-        self.timer_op_queue
-            .try_iter()
-            .take(limit)
-            .for_each(|op| match op {
-                TimerOp::InsertTimer(deadline, id, waker) => {
-                    timers.insert((deadline, id), waker);
-                }
-                TimerOp::RemoveTimer(deadline, id) => {
-                    if let Some(waker) = timers.remove(&(deadline, id)) {
-                        // Don't let a waker that panics on drop blow everything up.
-                        std::panic::catch_unwind(|| drop(waker)).ok();
-                    }
-                }
-            });
+    /// Remove a window from the window list if `should_remove` returns `true` for its entry.
+    fn remove_window_entry(
+        &self,
+        id: WindowId,
+        should_remove: impl FnOnce(&WindowEntry<TS>) -> bool,
+    ) {
+        let registration = {
+            let mut windows = self.windows.write().unwrap();
+            if !windows.get(&id).map_or(false, should_remove) {
+                return;
+            }
+
+            let entry = windows.remove(&id);
+            self.window_count.store(windows.len(), Ordering::SeqCst);
+
+            // Make sure the fast path doesn't find the removed window.
+            let mut last_window = self.last_window.lock().unwrap();
+            if matches!(&*last_window, Some((last_id, _)) if *last_id == id) {
+                *last_window = None;
+            }
+
+            entry.map(|entry| entry.registration)
+        };
+
+        // Close the window outside of the lock.
+        if let Some(registration) = registration {
+            #[cfg(feature = "log")]
+            log::debug!("removed window {:?}", id);
+
+            registration.close();
+        }
+
+        self.set_occluded(id, false);
+        self.wake_background_wakers();
     }
 
     /// Process timers and return the amount of time to wait.
+    ///
+    /// At most `timer_budget` timers are fired. Any other expired timers are set aside to be fired
+    /// first on the next pass, and the returned deadline is `now` so that the pass comes soon.
     pub(crate) fn process_timers(&self, wakers: &mut Vec<Waker>) -> Option<Instant> {
-        // Process incoming timer operations.
-        let mut timers = self.timers.lock().unwrap();
-        self.process_timer_ops(&mut timers);
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("process_timers").entered();
 
         let now = Instant::now();
+        let mut deadline: Option<Instant> = None;
+        let mut budget = self.timer_budget.load(Ordering::Relaxed);
+        #[cfg(feature = "tracing")]
+        let total = budget;
+
+        // Start from a different shard each time, so the budget doesn't favor the first shards.
+        let start = self.timer_cursor.fetch_add(1, Ordering::Relaxed);
+
+        for i in 0..self.timers.len() {
+            let mut timers = self.timers[(start + i) % self.timers.len()].lock().unwrap();
+
+            // Fire the ready timers that fit in the budget.
+            let fired = timers.fire(now, budget, wakers);
+            budget -= fired;
+
+            // Set aside any ready timers that didn't fit in the budget, counting each one once.
+            if budget == 0 {
+                let deferred = timers.defer(now);
+                if deferred > 0 {
+                    self.deferred_timers.fetch_add(deferred, Ordering::Relaxed);
+                }
+            }
 
-        // Split timers into pending and ready timers.
-        let pending = timers.split_off(&(now + Duration::from_nanos(1), 0));
-        let ready = std::mem::replace(&mut *timers, pending);
+            // Figure out how long it will be until the next timer in this shard is ready.
+            //
+            // Deferred timers are already expired, so they make the deadline `now` or earlier.
+            let shard_deadline = if fired > 0 {
+                // There are timers ready to fire now.
+                Some(now)
+            } else {
+                timers.next_deadline()
+            };
 
-        // Figure out how long it will be until the next timer is ready.
-        let deadline = if ready.is_empty() {
-            timers.keys().next().map(|(deadline, _)| *deadline)
-        } else {
-            // There are timers ready to fire now.
-            Some(now)
-        };
+            drop(timers);
 
-        drop(timers);
+            deadline = match (deadline, shard_deadline) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        }
 
-        // Push wakers for ready timers.
-        wakers.extend(ready.into_values());
+        #[cfg(feature = "tracing")]
+        tracing::trace!(fired = total - budget, ?deadline, "processed timers");
 
         deadline
     }
@@ -235,13 +824,43 @@ impl<TS: ThreadSafety> Reactor<TS> {
     }
 
     /// Push an event loop operation.
-    pub(crate) async fn push_event_loop_op(&self, op: EventLoopOp<TS>) {
-        if self.evl_ops.0.send(op).await.is_err() {
-            panic!("Failed to push event loop operation");
+    ///
+    /// Fails if the event loop has exited, since nothing will ever run the operation.
+    pub(crate) async fn push_event_loop_op(
+        &self,
+        op: EventLoopOp<TS>,
+    ) -> Result<(), EventLoopClosed<()>> {
+        #[cfg(feature = "log")]
+        if self.evl_ops.1.len() >= self.evl_ops.1.capacity() {
+            log::warn!(
+                "the event loop operation queue is full, waiting for {} to be sent",
+                op.name()
+            );
+        }
+
+        if let Err(_e) = self.evl_ops.0.send(op).await {
+            #[cfg(feature = "log")]
+            log::debug!("failed to push event loop operation: {}", _e);
+
+            return Err(EventLoopClosed(()));
         }
 
         // Notify the event loop that there is a new operation.
+        //
+        // This has to happen on every push. Concurrent senders, or a sender that waited on a full
+        // queue, can't tell whether someone else has already notified the event loop.
         self.notify();
+        Ok(())
+    }
+
+    /// Push an event loop operation whose caller has no way to report that the event loop exited.
+    ///
+    /// If the event loop has exited, the operation can never complete, so this waits forever like
+    /// any other task left behind by the event loop.
+    pub(crate) async fn push_event_loop_op_or_wait(&self, op: EventLoopOp<TS>) {
+        if self.push_event_loop_op(op).await.is_err() {
+            std::future::pending::<()>().await;
+        }
     }
 
     /// Drain the event loop operation queue.
@@ -249,50 +868,431 @@ impl<TS: ThreadSafety> Reactor<TS> {
         &self,
         elwt: &winit::event_loop::EventLoopWindowTarget<T>,
     ) {
-        for _ in 0..self.evl_ops.1.capacity() {
-            if let Some(op) = self.evl_ops.1.try_recv() {
+        let watching = self.watchdog_enabled.load(Ordering::Relaxed) != 0;
+        let mut batch = self.take_op_batch();
+
+        for op in batch.drain(..) {
+            #[cfg(feature = "tracing")]
+            let _span =
+                tracing::trace_span!("op", name = op.name(), window = ?op.window_id()).entered();
+
+            if watching {
+                let name = op.name();
+                let start = Instant::now();
                 op.run(elwt);
+                self.check_watchdog(Culprit::Op(name), start);
             } else {
-                break;
+                op.run(elwt);
             }
         }
+
+        self.finish_op_batch(batch);
+    }
+
+    /// Take the next batch of event loop operations out of the queue.
+    ///
+    /// At most `op_budget` operations are taken, and operations on the same window are grouped
+    /// together between operations that aren't tied to a window.
+    fn take_op_batch(&self) -> Vec<EventLoopOp<TS>> {
+        let budget = cmp::min(
+            self.op_budget.load(Ordering::Relaxed),
+            self.evl_ops.1.capacity(),
+        );
+
+        // Take the whole batch out of the queue before running anything, so that we aren't
+        // contending with the senders while the operations run.
+        let mut batch = std::mem::take(&mut *self.op_batch.lock().unwrap());
+        while batch.len() < budget {
+            match self.evl_ops.1.try_recv() {
+                Some(op) => batch.push(op),
+                None => break,
+            }
+        }
+
+        // Operations that were deferred last time are at the front of the queue, so they are taken
+        // first.
+        let counted = self.counted_ops.load(Ordering::Relaxed);
+        self.counted_ops
+            .store(counted.saturating_sub(batch.len()), Ordering::Relaxed);
+
+        // Run operations on the same window back to back. Operations that aren't tied to a window
+        // can depend on anything sent before them, so they stay where they are and only the runs
+        // between them are sorted. The sort is stable, so the operations on each window still run
+        // in the order they were sent.
+        for run in batch.split_mut(|op| op.window_id().is_none()) {
+            run.sort_by_key(|op| op.window_id());
+        }
+
+        batch
+    }
+
+    /// Put back the buffer of a batch that has been run, and account for the deferred operations.
+    fn finish_op_batch(&self, batch: Vec<EventLoopOp<TS>>) {
+        debug_assert!(batch.is_empty());
+
+        // Keep the buffer around for the next batch.
+        *self.op_batch.lock().unwrap() = batch;
+
+        // If we ran out of budget, make sure that the rest of the operations are run soon.
+        let queued = self.evl_ops.1.len();
+        if queued > 0 {
+            #[cfg(feature = "log")]
+            log::debug!(
+                "deferred {} event loop operations to the next iteration",
+                queued
+            );
+
+            // Only count the operations that weren't already deferred by an earlier pass.
+            let counted = self.counted_ops.swap(queued, Ordering::Relaxed);
+            self.deferred_ops
+                .fetch_add(queued.saturating_sub(counted), Ordering::Relaxed);
+            self.notify();
+        }
     }
 
     pub fn evl_ops_len(&self) -> usize {
         self.evl_ops.1.len()
     }
 
+    /// Fire `content_rect_changed` if the app's content rectangle has changed.
+    ///
+    /// `winit` drops the `ContentRectChanged` and `InsetsChanged` events, but they still wake up the
+    /// event loop, so the rectangle is compared once per iteration instead.
+    #[cfg(android_platform)]
+    async fn check_content_rect(&self) {
+        let app = match self.android_app.get() {
+            Some(app) => app,
+            None => return,
+        };
+
+        let mut rect = app.content_rect();
+        let changed = {
+            let mut last = self.content_rect.lock().unwrap();
+            let changed = last.as_ref() != Some(&rect);
+            *last = Some(rect.clone());
+            changed
+        };
+
+        if changed {
+            self.evl_registration
+                .content_rect_changed
+                .run_with(&mut rect)
+                .await;
+        }
+    }
+
     /// Post an event to the reactor.
     pub(crate) async fn post_event<T: 'static>(&self, event: winit::event::Event<'_, T>) {
-        use winit::event::Event;
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
+            use winit::event::Event;
+
+            let window = match &event {
+                Event::WindowEvent { window_id, .. } => Some(*window_id),
+                Event::RedrawRequested(window_id) => Some(*window_id),
+                _ => None,
+            };
+            let span = tracing::trace_span!(
+                "dispatch",
+                event = watchdog::event_name(&event),
+                window = ?window
+            );
+
+            self.dispatch(event).instrument(span).await
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        self.dispatch(event).await
+    }
+
+    /// Deliver an event to the handlers that are listening for it.
+    async fn dispatch<T: 'static>(&self, event: winit::event::Event<'_, T>) {
+        use winit::event::{Event, StartCause, WindowEvent};
+
+        // Stamp the event with the time it entered the reactor.
+        let measuring = self.metrics_enabled.load(Ordering::Relaxed) != 0;
+        let watching = self.watchdog_enabled.load(Ordering::Relaxed) != 0;
+        let start = if measuring || watching {
+            Some(Instant::now())
+        } else {
+            None
+        };
+        let name = if watching {
+            Some(watchdog::event_name(&event))
+        } else {
+            None
+        };
+
+        // Keep track of how late the OS woke us up for a timer.
+        if measuring {
+            if let Event::NewEvents(StartCause::ResumeTimeReached {
+                start: actual,
+                requested_resume,
+            }) = event
+            {
+                self.metrics
+                    .lock()
+                    .unwrap()
+                    .record_wakeup(requested_resume, actual);
+            }
+        }
+
+        // Decide whether or not a pending exit request goes through.
+        self.process_exit_request().await;
+
+        // Let listeners know that the event loop is going to exit.
+        if let Some(mut code) = self.take_exit_announcement() {
+            self.evl_registration
+                .exit_requested
+                .run_with(&mut code)
+                .await;
+        }
+
+        // Announce any windows that have been created since the last event.
+        if self.has_created_windows.swap(0, Ordering::SeqCst) != 0 {
+            let created = std::mem::take(&mut *self.created_windows.lock().unwrap());
+            for mut id in created {
+                self.evl_registration.window_created.run_with(&mut id).await;
+            }
+        }
+
+        // Deliver any Apple Events that arrived outside of the event loop's callback.
+        #[cfg(macos_platform)]
+        crate::platform::macos::dispatch_apple_events(&self.evl_registration).await;
 
         match event {
             Event::WindowEvent { window_id, event } => {
-                let registration = {
-                    let windows = self.windows.lock().unwrap();
-                    windows.get(&window_id).cloned()
-                };
+                let destroyed = matches!(event, WindowEvent::Destroyed);
+
+                // On Android, `winit` only reports configuration changes as scale factor changes.
+                #[cfg(android_platform)]
+                let config_changed = matches!(event, WindowEvent::ScaleFactorChanged { .. });
+
+                // On iOS, the safe area can only change along with the size of the window.
+                #[cfg(ios_platform)]
+                let resized = matches!(event, WindowEvent::Resized(_));
+
+                // Keep track of which windows are occluded.
+                if let WindowEvent::Occluded(occluded) = event {
+                    self.set_occluded(window_id, occluded);
+                    if !occluded {
+                        self.wake_background_wakers();
+                    }
+                }
+
+                // Don't hold the window list lock while the handlers run.
+                let registration = self.registration(window_id);
+
+                #[cfg(feature = "log")]
+                if registration.is_none() {
+                    log::trace!("dropped an event for unknown window {:?}", window_id);
+                }
 
                 if let Some(registration) = registration {
                     registration.signal(event).await;
                 }
+
+                // The window no longer exists, so remove it from the window list.
+                if destroyed {
+                    self.remove_window(window_id);
+                }
+
+                #[cfg(ios_platform)]
+                if resized {
+                    if let Some((window, registration)) = self.window(window_id) {
+                        registration.check_safe_area(&window).await;
+                    }
+                }
+
+                #[cfg(android_platform)]
+                if config_changed {
+                    if let Some(app) = self.android_app.get() {
+                        self.evl_registration
+                            .config_changed
+                            .run_with(&mut app.config())
+                            .await;
+                    }
+                }
             }
             Event::Resumed => {
+                #[cfg(feature = "log")]
+                log::info!("application resumed");
+
+                self.resume_count.fetch_add(1, Ordering::SeqCst);
+                self.wake_background_wakers();
                 self.evl_registration.resumed.run_with(&mut ()).await;
             }
-            Event::Suspended => self.evl_registration.suspended.run_with(&mut ()).await,
+            Event::Suspended => {
+                #[cfg(feature = "log")]
+                log::info!("application suspended");
+
+                self.suspend_count.fetch_add(1, Ordering::SeqCst);
+                self.evl_registration.suspended.run_with(&mut ()).await;
+            }
             Event::RedrawRequested(id) => {
-                let registration = {
-                    let windows = self.windows.lock().unwrap();
-                    windows.get(&id).cloned()
-                };
+                let registration = self.registration(id);
+
+                #[cfg(feature = "log")]
+                if registration.is_none() {
+                    log::trace!("dropped a redraw request for unknown window {:?}", id);
+                }
 
                 if let Some(registration) = registration {
                     registration.redraw_requested.run_with(&mut ()).await;
                 }
             }
+            Event::MainEventsCleared => {
+                #[cfg(android_platform)]
+                self.check_content_rect().await;
+
+                #[cfg(feature = "gamepad")]
+                crate::gamepad::poll_gamepads(self).await;
+
+                #[cfg(all(feature = "muda", any(windows, macos_platform)))]
+                crate::menu::poll_menu_events(self).await;
+            }
             _ => {}
         }
+
+        if let Some(start) = start {
+            if measuring {
+                self.metrics.lock().unwrap().record_dispatch(start);
+            }
+
+            if let Some(name) = name {
+                self.check_watchdog(Culprit::Event(name), start);
+            }
+        }
+    }
+}
+
+/// One shard of the timer wheel.
+///
+/// Firing timers only ever needs the soonest deadline, so the deadlines are kept in a binary heap.
+/// Timer IDs are never reused, so they double as generation counters: a timer is removed by
+/// dropping its waker from `wakers`, and a heap entry whose ID has no waker is stale and skipped.
+/// Expired timers that didn't fit in the timer budget are moved to `deferred`, so that they are
+/// fired first on the next pass and aren't counted twice.
+#[derive(Default)]
+struct TimerShard {
+    /// The deadlines of the timers, soonest first.
+    deadlines: BinaryHeap<Reverse<(Instant, usize)>>,
+
+    /// Expired timers that were deferred by the timer budget, in the order they expired.
+    deferred: VecDeque<(Instant, usize)>,
+
+    /// The wakers of the timers that are still registered.
+    wakers: HashMap<usize, Waker>,
+}
+
+impl TimerShard {
+    /// Insert a timer into this shard.
+    fn insert(&mut self, deadline: Instant, id: usize, waker: Waker) {
+        self.deadlines.push(Reverse((deadline, id)));
+        self.wakers.insert(id, waker);
+    }
+
+    /// Remove a timer from this shard, returning its waker.
+    fn remove(&mut self, id: usize) -> Option<Waker> {
+        let waker = self.wakers.remove(&id);
+
+        // Don't let stale entries pile up if timers are removed more often than they fire.
+        if self.deadlines.len() > 2 * self.wakers.len() + 16 {
+            let wakers = &self.wakers;
+            let deadlines = std::mem::take(&mut self.deadlines).into_vec();
+            self.deadlines = deadlines
+                .into_iter()
+                .filter(|Reverse((_, id))| wakers.contains_key(id))
+                .collect();
+        }
+
+        waker
+    }
+
+    /// Replace the waker of a timer in this shard, returning the old waker.
+    fn update(&mut self, id: usize, waker: &Waker) -> Option<Waker> {
+        self.wakers
+            .get_mut(&id)
+            .map(|current| std::mem::replace(current, waker.clone()))
+    }
+
+    /// Fire up to `budget` timers whose deadline is at or before `now`.
+    ///
+    /// Returns the number of timers that were fired.
+    fn fire(&mut self, now: Instant, budget: usize, wakers: &mut Vec<Waker>) -> usize {
+        let mut fired = 0;
+
+        // Timers that were deferred on an earlier pass have waited the longest.
+        while fired < budget {
+            let (_, id) = match self.deferred.pop_front() {
+                Some(entry) => entry,
+                None => break,
+            };
+
+            if let Some(waker) = self.wakers.remove(&id) {
+                wakers.push(waker);
+                fired += 1;
+            }
+        }
+
+        while fired < budget {
+            match self.deadlines.peek() {
+                Some(Reverse((deadline, _))) if *deadline <= now => {}
+                _ => break,
+            }
+
+            let Reverse((_, id)) = self.deadlines.pop().unwrap();
+            if let Some(waker) = self.wakers.remove(&id) {
+                wakers.push(waker);
+                fired += 1;
+            }
+        }
+
+        fired
+    }
+
+    /// Set aside the registered timers whose deadline is at or before `now`.
+    ///
+    /// Returns the number of timers that were newly deferred.
+    fn defer(&mut self, now: Instant) -> usize {
+        let mut deferred = 0;
+
+        while let Some(Reverse((deadline, _))) = self.deadlines.peek() {
+            if *deadline > now {
+                break;
+            }
+
+            let Reverse(entry) = self.deadlines.pop().unwrap();
+            if self.wakers.contains_key(&entry.1) {
+                self.deferred.push_back(entry);
+                deferred += 1;
+            }
+        }
+
+        deferred
+    }
+
+    /// Get the soonest deadline of a registered timer, discarding stale entries.
+    fn next_deadline(&mut self) -> Option<Instant> {
+        // Deferred timers have already expired, so they come before anything in the heap.
+        while let Some((deadline, id)) = self.deferred.front() {
+            if self.wakers.contains_key(id) {
+                return Some(*deadline);
+            }
+
+            self.deferred.pop_front();
+        }
+
+        while let Some(Reverse((deadline, id))) = self.deadlines.peek() {
+            if self.wakers.contains_key(id) {
+                return Some(*deadline);
+            }
+
+            self.deadlines.pop();
+        }
+
+        None
     }
 }
 
@@ -797,6 +1797,85 @@ pub(crate) enum EventLoopOp<TS: ThreadSafety> {
         /// Wake up the task.
         waker: Complete<Option<MonitorHandle>, TS>,
     },
+
+    /// Create a `wgpu` surface for a window.
+    #[cfg(feature = "wgpu")]
+    CreateWgpuSurface {
+        /// The window.
+        window: TS::Rc<Window>,
+
+        /// The instance to create the surface with.
+        instance: std::sync::Arc<wgpu::Instance>,
+
+        /// Wake up the task.
+        waker: Complete<Result<wgpu::Surface, wgpu::CreateSurfaceError>, TS>,
+    },
+
+    /// Create a Vulkan surface for a window.
+    #[cfg(feature = "ash")]
+    CreateVulkanSurface {
+        /// The window.
+        window: TS::Rc<Window>,
+
+        /// The entry that the instance was created from.
+        entry: ash::Entry,
+
+        /// The instance to create the surface with.
+        instance: ash::Instance,
+
+        /// Wake up the task.
+        waker: Complete<ash::prelude::VkResult<ash::vk::SurfaceKHR>, TS>,
+    },
+
+    /// Get the safe area insets of a window on iOS.
+    #[cfg(ios_platform)]
+    SafeAreaInsets {
+        /// The window.
+        window: TS::Rc<Window>,
+
+        /// Wake up the task.
+        waker: Complete<crate::platform::ios::SafeAreaInsets, TS>,
+    },
+
+    /// Run a Windows-specific operation on a window.
+    #[cfg(windows)]
+    Win32 {
+        /// The window.
+        window: TS::Rc<Window>,
+
+        /// The operation to run.
+        op: crate::platform::windows::WindowOp,
+
+        /// Wake up the task.
+        waker: Complete<(), TS>,
+    },
+
+    /// Run a macOS-specific operation on a window.
+    #[cfg(macos_platform)]
+    Cocoa {
+        /// The window.
+        window: TS::Rc<Window>,
+
+        /// The operation to run.
+        op: crate::platform::macos::WindowOp,
+
+        /// Wake up the task.
+        waker: Complete<bool, TS>,
+    },
+
+    /// Run an application-level operation on macOS.
+    #[cfg(macos_platform)]
+    Application {
+        /// The operation to run.
+        op: crate::platform::macos::ApplicationOp,
+
+        /// Wake up the task.
+        waker: Complete<(), TS>,
+    },
+
+    /// Run an operation on the clipboard.
+    #[cfg(feature = "arboard")]
+    Clipboard(crate::clipboard::ClipboardOp<TS>),
 }
 
 impl<TS: ThreadSafety> fmt::Debug for EventLoopOp<TS> {
@@ -867,11 +1946,154 @@ impl<TS: ThreadSafety> fmt::Debug for EventLoopOp<TS> {
 }
 
 impl<TS: ThreadSafety> EventLoopOp<TS> {
+    /// Get the name of this operation, for reporting.
+    fn name(&self) -> &'static str {
+        match self {
+            EventLoopOp::BuildWindow { .. } => "BuildWindow",
+            EventLoopOp::PrimaryMonitor(_) => "PrimaryMonitor",
+            EventLoopOp::AvailableMonitors(_) => "AvailableMonitors",
+            EventLoopOp::SetDeviceFilter { .. } => "SetDeviceFilter",
+            EventLoopOp::InnerPosition { .. } => "InnerPosition",
+            EventLoopOp::OuterPosition { .. } => "OuterPosition",
+            EventLoopOp::SetOuterPosition { .. } => "SetOuterPosition",
+            EventLoopOp::InnerSize { .. } => "InnerSize",
+            EventLoopOp::SetInnerSize { .. } => "SetInnerSize",
+            EventLoopOp::OuterSize { .. } => "OuterSize",
+            EventLoopOp::SetMinInnerSize { .. } => "SetMinInnerSize",
+            EventLoopOp::SetMaxInnerSize { .. } => "SetMaxInnerSize",
+            EventLoopOp::ResizeIncrements { .. } => "ResizeIncrements",
+            EventLoopOp::SetResizeIncrements { .. } => "SetResizeIncrements",
+            EventLoopOp::SetTitle { .. } => "SetTitle",
+            EventLoopOp::SetTransparent { .. } => "SetTransparent",
+            EventLoopOp::SetResizable { .. } => "SetResizable",
+            EventLoopOp::SetVisible { .. } => "SetVisible",
+            EventLoopOp::Resizable { .. } => "Resizable",
+            EventLoopOp::Visible { .. } => "Visible",
+            EventLoopOp::SetMinimized { .. } => "SetMinimized",
+            EventLoopOp::Minimized { .. } => "Minimized",
+            EventLoopOp::SetMaximized { .. } => "SetMaximized",
+            EventLoopOp::Maximized { .. } => "Maximized",
+            EventLoopOp::SetFullscreen { .. } => "SetFullscreen",
+            EventLoopOp::Fullscreen { .. } => "Fullscreen",
+            EventLoopOp::SetDecorated { .. } => "SetDecorated",
+            EventLoopOp::Decorated { .. } => "Decorated",
+            EventLoopOp::SetWindowLevel { .. } => "SetWindowLevel",
+            EventLoopOp::SetWindowIcon { .. } => "SetWindowIcon",
+            EventLoopOp::SetImePosition { .. } => "SetImePosition",
+            EventLoopOp::SetImeAllowed { .. } => "SetImeAllowed",
+            EventLoopOp::SetImePurpose { .. } => "SetImePurpose",
+            EventLoopOp::FocusWindow { .. } => "FocusWindow",
+            EventLoopOp::Focused { .. } => "Focused",
+            EventLoopOp::RequestUserAttention { .. } => "RequestUserAttention",
+            EventLoopOp::SetTheme { .. } => "SetTheme",
+            EventLoopOp::Theme { .. } => "Theme",
+            EventLoopOp::SetProtectedContent { .. } => "SetProtectedContent",
+            EventLoopOp::Title { .. } => "Title",
+            EventLoopOp::SetCursorIcon { .. } => "SetCursorIcon",
+            EventLoopOp::SetCursorPosition { .. } => "SetCursorPosition",
+            EventLoopOp::SetCursorGrab { .. } => "SetCursorGrab",
+            EventLoopOp::SetCursorVisible { .. } => "SetCursorVisible",
+            EventLoopOp::DragWindow { .. } => "DragWindow",
+            EventLoopOp::DragResizeWindow { .. } => "DragResizeWindow",
+            EventLoopOp::SetCursorHitTest { .. } => "SetCursorHitTest",
+            EventLoopOp::CurrentMonitor { .. } => "CurrentMonitor",
+            #[cfg(feature = "wgpu")]
+            EventLoopOp::CreateWgpuSurface { .. } => "CreateWgpuSurface",
+            #[cfg(feature = "ash")]
+            EventLoopOp::CreateVulkanSurface { .. } => "CreateVulkanSurface",
+            #[cfg(ios_platform)]
+            EventLoopOp::SafeAreaInsets { .. } => "SafeAreaInsets",
+            #[cfg(windows)]
+            EventLoopOp::Win32 { .. } => "Win32",
+            #[cfg(macos_platform)]
+            EventLoopOp::Cocoa { .. } => "Cocoa",
+            #[cfg(macos_platform)]
+            EventLoopOp::Application { .. } => "Application",
+            #[cfg(feature = "arboard")]
+            EventLoopOp::Clipboard(_) => "Clipboard",
+        }
+    }
+
+    /// Get the ID of the window that this operation targets, if any.
+    fn window_id(&self) -> Option<WindowId> {
+        match self {
+            EventLoopOp::InnerPosition { window, .. }
+            | EventLoopOp::OuterPosition { window, .. }
+            | EventLoopOp::SetOuterPosition { window, .. }
+            | EventLoopOp::InnerSize { window, .. }
+            | EventLoopOp::SetInnerSize { window, .. }
+            | EventLoopOp::OuterSize { window, .. }
+            | EventLoopOp::SetMinInnerSize { window, .. }
+            | EventLoopOp::SetMaxInnerSize { window, .. }
+            | EventLoopOp::ResizeIncrements { window, .. }
+            | EventLoopOp::SetResizeIncrements { window, .. }
+            | EventLoopOp::SetTitle { window, .. }
+            | EventLoopOp::SetTransparent { window, .. }
+            | EventLoopOp::SetResizable { window, .. }
+            | EventLoopOp::SetVisible { window, .. }
+            | EventLoopOp::Resizable { window, .. }
+            | EventLoopOp::Visible { window, .. }
+            | EventLoopOp::SetMinimized { window, .. }
+            | EventLoopOp::Minimized { window, .. }
+            | EventLoopOp::SetMaximized { window, .. }
+            | EventLoopOp::Maximized { window, .. }
+            | EventLoopOp::SetFullscreen { window, .. }
+            | EventLoopOp::Fullscreen { window, .. }
+            | EventLoopOp::SetDecorated { window, .. }
+            | EventLoopOp::Decorated { window, .. }
+            | EventLoopOp::SetWindowLevel { window, .. }
+            | EventLoopOp::SetWindowIcon { window, .. }
+            | EventLoopOp::SetImePosition { window, .. }
+            | EventLoopOp::SetImeAllowed { window, .. }
+            | EventLoopOp::SetImePurpose { window, .. }
+            | EventLoopOp::FocusWindow { window, .. }
+            | EventLoopOp::Focused { window, .. }
+            | EventLoopOp::RequestUserAttention { window, .. }
+            | EventLoopOp::SetTheme { window, .. }
+            | EventLoopOp::Theme { window, .. }
+            | EventLoopOp::SetProtectedContent { window, .. }
+            | EventLoopOp::Title { window, .. }
+            | EventLoopOp::SetCursorIcon { window, .. }
+            | EventLoopOp::SetCursorPosition { window, .. }
+            | EventLoopOp::SetCursorGrab { window, .. }
+            | EventLoopOp::SetCursorVisible { window, .. }
+            | EventLoopOp::DragWindow { window, .. }
+            | EventLoopOp::DragResizeWindow { window, .. }
+            | EventLoopOp::SetCursorHitTest { window, .. }
+            | EventLoopOp::CurrentMonitor { window, .. } => Some(window.id()),
+
+            #[cfg(feature = "wgpu")]
+            EventLoopOp::CreateWgpuSurface { window, .. } => Some(window.id()),
+            #[cfg(feature = "ash")]
+            EventLoopOp::CreateVulkanSurface { window, .. } => Some(window.id()),
+
+            #[cfg(ios_platform)]
+            EventLoopOp::SafeAreaInsets { window, .. } => Some(window.id()),
+
+            #[cfg(windows)]
+            EventLoopOp::Win32 { window, .. } => Some(window.id()),
+
+            #[cfg(macos_platform)]
+            EventLoopOp::Cocoa { window, .. } => Some(window.id()),
+
+            EventLoopOp::BuildWindow { .. }
+            | EventLoopOp::PrimaryMonitor(_)
+            | EventLoopOp::AvailableMonitors(_)
+            | EventLoopOp::SetDeviceFilter { .. } => None,
+
+            #[cfg(macos_platform)]
+            EventLoopOp::Application { .. } => None,
+
+            #[cfg(feature = "arboard")]
+            EventLoopOp::Clipboard(_) => None,
+        }
+    }
+
     /// Run this event loop operation on a window target.
     fn run<T: 'static>(self, target: &winit::event_loop::EventLoopWindowTarget<T>) {
         match self {
             EventLoopOp::BuildWindow { builder, waker } => {
-                waker.send(builder.into_winit_builder().build(target));
+                waker.send(builder.build_window(target));
             }
 
             EventLoopOp::PrimaryMonitor(waker) => {
@@ -1165,6 +2387,56 @@ impl<TS: ThreadSafety> EventLoopOp<TS> {
                 waker.send(window.current_monitor());
             }
 
+            #[cfg(feature = "wgpu")]
+            EventLoopOp::CreateWgpuSurface {
+                window,
+                instance,
+                waker,
+            } => {
+                // SAFETY: The caller wraps the surface in a `WgpuSurface`, which keeps the window
+                // alive.
+                waker.send(unsafe { instance.create_surface(&*window) });
+            }
+
+            #[cfg(feature = "ash")]
+            EventLoopOp::CreateVulkanSurface {
+                window,
+                entry,
+                instance,
+                waker,
+            } => {
+                // SAFETY: The caller wraps the surface in a `VulkanSurface`, which keeps the window
+                // alive, and promises that the instance outlives the request.
+                waker.send(unsafe {
+                    crate::window::create_vulkan_surface(&entry, &instance, &window)
+                });
+            }
+
+            #[cfg(ios_platform)]
+            EventLoopOp::SafeAreaInsets { window, waker } => {
+                waker.send(crate::platform::ios::SafeAreaInsets::of(&window));
+            }
+
+            #[cfg(windows)]
+            EventLoopOp::Win32 { window, op, waker } => {
+                op.run(&window);
+                waker.send(());
+            }
+
+            #[cfg(macos_platform)]
+            EventLoopOp::Cocoa { window, op, waker } => {
+                waker.send(op.run(&window));
+            }
+
+            #[cfg(macos_platform)]
+            EventLoopOp::Application { op, waker } => {
+                op.run();
+                waker.send(());
+            }
+
+            #[cfg(feature = "arboard")]
+            EventLoopOp::Clipboard(op) => op.run(),
+
             EventLoopOp::SetTransparent {
                 window,
                 transparent,
@@ -1201,6 +2473,28 @@ impl<TS: ThreadSafety> EventLoopOp<TS> {
 pub(crate) struct GlobalRegistration<T: ThreadSafety> {
     pub(crate) resumed: Handler<(), T>,
     pub(crate) suspended: Handler<(), T>,
+    pub(crate) window_created: Handler<WindowId, T>,
+    pub(crate) exit_requested: Handler<i32, T>,
+    #[cfg(android_platform)]
+    pub(crate) config_changed: Handler<crate::platform::android::activity::ConfigurationRef, T>,
+    #[cfg(android_platform)]
+    pub(crate) content_rect_changed: Handler<crate::platform::android::activity::Rect, T>,
+    #[cfg(macos_platform)]
+    pub(crate) reopen: Handler<(), T>,
+    #[cfg(macos_platform)]
+    pub(crate) open_files: Handler<Vec<std::path::PathBuf>, T>,
+    #[cfg(macos_platform)]
+    pub(crate) open_urls: Handler<Vec<String>, T>,
+    #[cfg(feature = "gamepad")]
+    pub(crate) gamepad_connected: Handler<crate::gamepad::GamepadId, T>,
+    #[cfg(feature = "gamepad")]
+    pub(crate) gamepad_disconnected: Handler<crate::gamepad::GamepadId, T>,
+    #[cfg(feature = "gamepad")]
+    pub(crate) gamepad_button: Handler<crate::gamepad::GamepadButton, T>,
+    #[cfg(feature = "gamepad")]
+    pub(crate) gamepad_axis: Handler<crate::gamepad::GamepadAxis, T>,
+    #[cfg(all(feature = "muda", any(windows, macos_platform)))]
+    pub(crate) menu_event: Handler<crate::menu::MenuEvent, T>,
 }
 
 impl<TS: ThreadSafety> GlobalRegistration<TS> {
@@ -1208,6 +2502,238 @@ impl<TS: ThreadSafety> GlobalRegistration<TS> {
         Self {
             resumed: Handler::new(),
             suspended: Handler::new(),
+            window_created: Handler::new(),
+            exit_requested: Handler::new(),
+            #[cfg(android_platform)]
+            config_changed: Handler::new(),
+            #[cfg(android_platform)]
+            content_rect_changed: Handler::new(),
+            #[cfg(macos_platform)]
+            reopen: Handler::new(),
+            #[cfg(macos_platform)]
+            open_files: Handler::new(),
+            #[cfg(macos_platform)]
+            open_urls: Handler::new(),
+            #[cfg(feature = "gamepad")]
+            gamepad_connected: Handler::new(),
+            #[cfg(feature = "gamepad")]
+            gamepad_disconnected: Handler::new(),
+            #[cfg(feature = "gamepad")]
+            gamepad_button: Handler::new(),
+            #[cfg(feature = "gamepad")]
+            gamepad_axis: Handler::new(),
+            #[cfg(all(feature = "muda", any(windows, macos_platform)))]
+            menu_event: Handler::new(),
+        }
+    }
+}
+
+/// Serializes the tests that use the global reactor.
+#[cfg(test)]
+static TEST_REACTOR_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Run a test against a fresh global reactor on its own thread.
+///
+/// The reactor is reset afterwards, so that the next test can claim it from another thread.
+#[cfg(test)]
+pub(crate) fn with_test_reactor(
+    f: impl FnOnce(std::rc::Rc<Reactor<crate::sync::ThreadUnsafe>>) + Send + 'static,
+) {
+    use crate::sync::ThreadUnsafe;
+
+    struct Reset;
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            Reactor::<ThreadUnsafe>::reset();
+        }
+    }
+
+    let _guard = TEST_REACTOR_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    thread::spawn(move || {
+        let _reset = Reset;
+        f(Reactor::<ThreadUnsafe>::get());
+    })
+    .join()
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oneoff::oneoff;
+    use crate::sync::{ThreadUnsafe, WakeFlag};
+
+    use futures_lite::future;
+
+    /// Create a reactor that isn't the global one, with the given budgets.
+    fn reactor(
+        timer_budget: Option<NonZeroUsize>,
+        op_budget: Option<NonZeroUsize>,
+    ) -> Reactor<ThreadUnsafe> {
+        let reactor = Reactor::new();
+        reactor.set_flow_options(FlowOptions {
+            timer_budget,
+            op_budget,
+            ..FlowOptions::default()
+        });
+        reactor
+    }
+
+    #[test]
+    fn timer_shard_fires_soonest_first() {
+        let (flag, waker) = WakeFlag::new();
+        let now = Instant::now();
+        let mut shard = TimerShard::default();
+
+        shard.insert(now - Duration::from_millis(1), 1, waker.clone());
+        shard.insert(now - Duration::from_millis(2), 2, waker.clone());
+        shard.insert(now + Duration::from_secs(1), 3, waker);
+
+        let mut wakers = Vec::new();
+        assert_eq!(shard.fire(now, 1, &mut wakers), 1);
+        assert_eq!(shard.defer(now), 1);
+        assert_eq!(shard.defer(now), 0);
+        assert_eq!(shard.next_deadline(), Some(now - Duration::from_millis(1)));
+        assert_eq!(shard.fire(now, usize::MAX, &mut wakers), 1);
+        assert_eq!(shard.next_deadline(), Some(now + Duration::from_secs(1)));
+
+        wakers.into_iter().for_each(Waker::wake);
+        assert!(flag.woken());
+    }
+
+    #[test]
+    fn timer_shard_skips_removed_timers() {
+        let (_, waker) = WakeFlag::new();
+        let now = Instant::now();
+        let mut shard = TimerShard::default();
+
+        shard.insert(now - Duration::from_millis(1), 1, waker.clone());
+        shard.insert(now + Duration::from_secs(1), 2, waker);
+        assert!(shard.remove(1).is_some());
+        assert!(shard.remove(1).is_none());
+
+        let mut wakers = Vec::new();
+        assert_eq!(shard.defer(now), 0);
+        assert_eq!(shard.fire(now, usize::MAX, &mut wakers), 0);
+        assert_eq!(shard.next_deadline(), Some(now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn timers_are_spread_across_shards() {
+        let reactor = reactor(None, None);
+        let (_, waker) = WakeFlag::new();
+        let deadline = Instant::now() + Duration::from_secs(1);
+
+        let ids = (0..TIMER_SHARDS)
+            .map(|_| reactor.insert_timer(deadline, Duration::ZERO, &waker))
+            .collect::<HashSet<_>>();
+        let shards = ids
+            .iter()
+            .map(|&id| reactor.timer_shard(id) as *const _)
+            .collect::<HashSet<_>>();
+        assert_eq!(shards.len(), TIMER_SHARDS);
+
+        for id in ids {
+            reactor.remove_timer(id);
+        }
+        assert_eq!(reactor.process_timers(&mut Vec::new()), None);
+    }
+
+    #[test]
+    fn timer_budget_defers_the_rest() {
+        let reactor = reactor(NonZeroUsize::new(5), None);
+        let (_, waker) = WakeFlag::new();
+        let deadline = Instant::now();
+
+        for _ in 0..20 {
+            reactor.insert_timer(deadline, Duration::ZERO, &waker);
         }
+
+        // Only the budget is fired, and the event loop is told to come back right away.
+        let mut wakers = Vec::new();
+        let next = reactor.process_timers(&mut wakers);
+        assert_eq!(wakers.len(), 5);
+        assert_eq!(reactor.deferred_timers(), 15);
+        assert!(next.map_or(false, |next| next <= Instant::now()));
+
+        // The deferred timers are fired on the following passes, without being counted again.
+        wakers.clear();
+        for _ in 0..3 {
+            reactor.process_timers(&mut wakers);
+        }
+        assert_eq!(wakers.len(), 15);
+        assert_eq!(reactor.deferred_timers(), 15);
+        assert_eq!(reactor.process_timers(&mut Vec::new()), None);
+    }
+
+    #[test]
+    fn op_budget_defers_the_rest() {
+        let reactor = reactor(None, NonZeroUsize::new(2));
+
+        for _ in 0..5 {
+            let (tx, _) = oneoff();
+            future::block_on(reactor.push_event_loop_op_or_wait(EventLoopOp::PrimaryMonitor(tx)));
+        }
+
+        let mut batch = reactor.take_op_batch();
+        assert_eq!(batch.len(), 2);
+        batch.clear();
+        reactor.finish_op_batch(batch);
+        assert_eq!(reactor.deferred_ops(), 3);
+        assert_eq!(reactor.evl_ops_len(), 3);
+
+        // The deferred operations are taken by the following passes, and only operations that
+        // are deferred for the first time are counted.
+        let (tx, _) = oneoff();
+        future::block_on(reactor.push_event_loop_op_or_wait(EventLoopOp::PrimaryMonitor(tx)));
+
+        let mut batch = reactor.take_op_batch();
+        assert_eq!(batch.len(), 2);
+        batch.clear();
+        reactor.finish_op_batch(batch);
+        assert_eq!(reactor.deferred_ops(), 4);
+
+        let mut batch = reactor.take_op_batch();
+        assert_eq!(batch.len(), 2);
+        batch.clear();
+        reactor.finish_op_batch(batch);
+        assert_eq!(reactor.deferred_ops(), 4);
+        assert_eq!(reactor.evl_ops_len(), 0);
+    }
+
+    #[test]
+    fn exit_is_committed_and_announced_once() {
+        let reactor = reactor(None, None);
+
+        reactor.request_exit(3);
+        assert_eq!(reactor.exit_requested(), None);
+
+        future::block_on(reactor.process_exit_request());
+        assert_eq!(reactor.exit_requested(), Some(3));
+
+        assert_eq!(reactor.take_exit_announcement(), Some(3));
+        assert_eq!(reactor.take_exit_announcement(), None);
+        assert_eq!(reactor.exit_requested(), Some(3));
+    }
+
+    #[test]
+    fn reset_discards_the_reactor() {
+        with_test_reactor(|reactor| {
+            let same = Reactor::<ThreadUnsafe>::get();
+            assert!(std::rc::Rc::ptr_eq(&reactor, &same));
+
+            Reactor::<ThreadUnsafe>::reset();
+            let fresh = Reactor::<ThreadUnsafe>::get();
+            assert!(!std::rc::Rc::ptr_eq(&reactor, &fresh));
+        });
+    }
+
+    #[test]
+    fn reset_releases_the_thread() {
+        // Each call runs on a different thread, which would panic if the reactor stayed claimed.
+        with_test_reactor(|_| {});
+        with_test_reactor(|_| {});
     }
 }
@@ -19,17 +19,20 @@ Public License along with `async-winit`. If not, see <https://www.gnu.org/licens
 use crate::reactor::Reactor;
 pub(crate) use __private::__ThreadSafety;
 
-use core::cell::{Cell, RefCell, RefMut};
+use core::cell::{Cell, Ref, RefCell, RefMut};
 use core::convert::Infallible;
 use core::future::Future;
-use core::ops::Add;
+use core::mem;
+use core::ops::{Add, Sub};
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
 
 use std::collections::VecDeque;
 use std::rc::Rc;
 use std::sync::atomic;
 use std::thread;
 
-use unsend::channel as us_channel;
+use instant::Instant;
 
 #[cfg(feature = "thread_safe")]
 pub use thread_safe::ThreadSafe;
@@ -40,12 +43,34 @@ type _DefaultTS = ThreadSafe;
 type _DefaultTS = ThreadUnsafe;
 
 /// The default thread safe type to use.
+///
+/// This is `ThreadSafe` if the `thread_safe` feature is enabled and [`ThreadUnsafe`] otherwise.
+/// Types like [`Window`](crate::window::Window) and [`Timer`](crate::Timer) use this as their
+/// default type parameter, so `Window` can be written instead of `Window<DefaultThreadSafety>`.
 pub type DefaultThreadSafety = _DefaultTS;
 
 /// A token that can be used to indicate whether the current implementation should be thread-safe or
 /// not.
 pub trait ThreadSafety: __ThreadSafety {}
 
+/// A type that is `Send` if `TS` is `ThreadSafe`.
+///
+/// This is implemented for all types when `TS` is [`ThreadUnsafe`], and for all `Send` types when
+/// `TS` is `ThreadSafe`. Code that is generic over [`ThreadSafety`] can use this as a bound for
+/// values that might be sent to another thread.
+pub trait MaybeSend<TS: ThreadSafety = DefaultThreadSafety> {}
+
+impl<T: ?Sized> MaybeSend<ThreadUnsafe> for T {}
+
+/// A type that is `Sync` if `TS` is `ThreadSafe`.
+///
+/// This is implemented for all types when `TS` is [`ThreadUnsafe`], and for all `Sync` types when
+/// `TS` is `ThreadSafe`. Code that is generic over [`ThreadSafety`] can use this as a bound for
+/// values that might be shared between threads.
+pub trait MaybeSync<TS: ThreadSafety = DefaultThreadSafety> {}
+
+impl<T: ?Sized> MaybeSync<ThreadUnsafe> for T {}
+
 /// Use thread-unsafe primitives.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ThreadUnsafe {
@@ -60,34 +85,36 @@ impl __ThreadSafety for ThreadUnsafe {
     type AtomicUsize = Cell<usize>;
     type AtomicU64 = Cell<u64>;
     type AtomicI64 = Cell<i64>;
+    type AtomicWaker = Cell<Option<Waker>>;
+    type Parker = StdParker;
 
-    type Receiver<T> = us_channel::Receiver<T>;
-    type Sender<T> = us_channel::Sender<T>;
+    type Receiver<T> = LocalReceiver<T>;
+    type Sender<T> = LocalSender<T>;
     type Rc<T> = Rc<T>;
 
     type ConcurrentQueue<T> = RefCell<VecDeque<T>>;
     type Mutex<T> = RefCell<T>;
+    type RwLock<T> = RefCell<T>;
     type OnceLock<T> = once_cell::unsync::OnceCell<T>;
 
-    fn channel_bounded<T>(_capacity: usize) -> (Self::Sender<T>, Self::Receiver<T>) {
-        us_channel::channel()
-    }
+    #[cfg(feature = "executor")]
+    type Executor = async_executor::LocalExecutor<'static>;
 
-    fn get_reactor() -> Self::Rc<Reactor<Self>> {
-        use once_cell::sync::OnceCell;
+    type ExitInterceptor = Box<dyn FnMut(i32) -> Pin<Box<dyn Future<Output = bool>>>>;
 
-        /// The thread ID of the thread that created the reactor.
-        static REACTOR_THREAD_ID: OnceCell<thread::ThreadId> = OnceCell::new();
-
-        std::thread_local! {
-            static REACTOR: RefCell<Option<std::rc::Rc<Reactor<ThreadUnsafe>>>> = RefCell::new(None);
-        }
+    fn channel_bounded<T>(capacity: usize) -> (Self::Sender<T>, Self::Receiver<T>) {
+        local_channel(capacity)
+    }
 
-        // Try to set the thread ID.
+    fn get_reactor() -> Self::Rc<Reactor<Self>> {
+        // Try to claim the reactor for this thread.
         let thread_id = thread_id();
-        let reactor_thread_id = REACTOR_THREAD_ID.get_or_init(|| thread_id);
+        let reactor_thread_id = *REACTOR_THREAD_ID
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert(thread_id);
 
-        if thread_id != *reactor_thread_id {
+        if thread_id != reactor_thread_id {
             panic!("The reactor must be created on the main thread");
         }
 
@@ -98,6 +125,35 @@ impl __ThreadSafety for ThreadUnsafe {
                 .clone()
         })
     }
+
+    fn reset_reactor() {
+        let old = {
+            let mut reactor_thread_id = REACTOR_THREAD_ID.lock().unwrap_or_else(|e| e.into_inner());
+
+            // Only the thread that owns the reactor can reset it.
+            if *reactor_thread_id != Some(thread_id()) {
+                return;
+            }
+
+            // Take out the current reactor and release the thread, so that a later call to
+            // `get_reactor` is not tied to this one.
+            *reactor_thread_id = None;
+            REACTOR
+                .try_with(|reactor| reactor.borrow_mut().take())
+                .ok()
+                .flatten()
+        };
+
+        // Drop the old reactor outside of the lock.
+        drop(old);
+    }
+}
+
+/// The thread ID of the thread that currently owns the reactor.
+static REACTOR_THREAD_ID: std::sync::Mutex<Option<thread::ThreadId>> = std::sync::Mutex::new(None);
+
+std::thread_local! {
+    static REACTOR: RefCell<Option<std::rc::Rc<Reactor<ThreadUnsafe>>>> = RefCell::new(None);
 }
 
 pub(crate) type MutexGuard<'a, T, TS> =
@@ -136,6 +192,29 @@ impl<T: Copy> __private::Atomic<T> for Cell<T> {
         self.set(value);
     }
 
+    fn swap(&self, value: T, _order: atomic::Ordering) -> T {
+        self.replace(value)
+    }
+
+    fn compare_exchange(
+        &self,
+        current: T,
+        new: T,
+        _success: atomic::Ordering,
+        _failure: atomic::Ordering,
+    ) -> Result<T, T>
+    where
+        T: PartialEq,
+    {
+        let old = self.get();
+        if old == current {
+            self.set(new);
+            Ok(old)
+        } else {
+            Err(old)
+        }
+    }
+
     fn fetch_add(&self, value: T, _order: atomic::Ordering) -> T
     where
         T: Add<Output = T>,
@@ -144,41 +223,351 @@ impl<T: Copy> __private::Atomic<T> for Cell<T> {
         self.set(old + value);
         old
     }
+
+    fn fetch_sub(&self, value: T, _order: atomic::Ordering) -> T
+    where
+        T: Sub<Output = T>,
+    {
+        let old = self.get();
+        self.set(old - value);
+        old
+    }
 }
 
-impl<T> __private::Sender<T> for us_channel::Sender<T> {
-    type Error = Infallible;
-    type Send<'a> = core::future::Ready<Result<(), Self::Error>> where Self: 'a;
+impl __private::AtomicWaker for Cell<Option<Waker>> {
+    fn new() -> Self {
+        Cell::new(None)
+    }
+
+    fn register(&self, waker: &Waker) {
+        // If the current waker is the same as ours, no need to clone.
+        let waker = match self.take() {
+            Some(current) if current.will_wake(waker) => current,
+            _ => waker.clone(),
+        };
+
+        self.set(Some(waker));
+    }
+
+    fn take(&self) -> Option<Waker> {
+        Cell::take(self)
+    }
+}
+
+/// A parker built on top of the standard library's synchronization primitives.
+///
+/// Event loop wakers can be sent to other threads even when the reactor is thread-unsafe, so we
+/// still need to be able to block the event loop thread. However, we don't need to pull in the
+/// `parking` crate to do it.
+#[doc(hidden)]
+pub struct StdParker {
+    inner: std::sync::Arc<StdParkerInner>,
+}
+
+#[doc(hidden)]
+pub struct StdUnparker {
+    inner: std::sync::Arc<StdParkerInner>,
+}
+
+struct StdParkerInner {
+    /// Whether or not we've been unparked.
+    notified: std::sync::Mutex<bool>,
+
+    /// Used to wake up the parked thread.
+    condvar: std::sync::Condvar,
+}
+
+impl StdParkerInner {
+    fn lock(&self) -> std::sync::MutexGuard<'_, bool> {
+        self.notified.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl __private::Parker for StdParker {
+    type Unparker = StdUnparker;
+
+    fn pair() -> (Self, Self::Unparker) {
+        let inner = std::sync::Arc::new(StdParkerInner {
+            notified: std::sync::Mutex::new(false),
+            condvar: std::sync::Condvar::new(),
+        });
+
+        (
+            StdParker {
+                inner: inner.clone(),
+            },
+            StdUnparker { inner },
+        )
+    }
+
+    fn park(&self) {
+        let mut notified = self.inner.lock();
+
+        while !*notified {
+            notified = self
+                .inner
+                .condvar
+                .wait(notified)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+
+        *notified = false;
+    }
+
+    fn park_deadline(&self, deadline: Instant) {
+        let mut notified = self.inner.lock();
+
+        while !*notified {
+            let now = Instant::now();
+            if now >= deadline {
+                return;
+            }
+
+            notified = self
+                .inner
+                .condvar
+                .wait_timeout(notified, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+
+        *notified = false;
+    }
+}
+
+impl __private::Unparker for StdUnparker {
+    fn unpark(&self) {
+        *self.inner.lock() = true;
+        self.inner.condvar.notify_one();
+    }
+}
+
+/// The shared state of a thread-unsafe channel.
+struct LocalChannel<T> {
+    /// The values in the channel.
+    queue: VecDeque<T>,
+
+    /// The maximum number of values in the channel.
+    capacity: usize,
+
+    /// The waker for the receiver.
+    recv_waker: Option<Waker>,
+
+    /// The wakers for senders waiting for space in the channel.
+    send_wakers: Vec<Waker>,
+
+    /// The number of senders that are still alive.
+    senders: usize,
+
+    /// Whether or not the receiver is still alive.
+    receiver: bool,
+}
+
+/// The sending half of a thread-unsafe channel.
+#[doc(hidden)]
+pub struct LocalSender<T> {
+    channel: Rc<RefCell<LocalChannel<T>>>,
+}
+
+/// The receiving half of a thread-unsafe channel.
+#[doc(hidden)]
+pub struct LocalReceiver<T> {
+    channel: Rc<RefCell<LocalChannel<T>>>,
+}
+
+/// Create a new thread-unsafe channel.
+fn local_channel<T>(capacity: usize) -> (LocalSender<T>, LocalReceiver<T>) {
+    let channel = Rc::new(RefCell::new(LocalChannel {
+        queue: VecDeque::new(),
+        capacity: capacity.max(1),
+        recv_waker: None,
+        send_wakers: Vec::new(),
+        senders: 1,
+        receiver: true,
+    }));
+
+    (
+        LocalSender {
+            channel: channel.clone(),
+        },
+        LocalReceiver { channel },
+    )
+}
+
+impl<T> LocalSender<T> {
+    /// Try to push a value into the channel.
+    fn push(&self, value: T) -> Result<(), __private::TrySendError<T>> {
+        let mut channel = self.channel.borrow_mut();
+
+        if !channel.receiver {
+            return Err(__private::TrySendError::Closed(value));
+        }
+
+        if channel.queue.len() >= channel.capacity {
+            return Err(__private::TrySendError::Full(value));
+        }
+
+        channel.queue.push_back(value);
+        let waker = channel.recv_waker.take();
+        drop(channel);
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Clone for LocalSender<T> {
+    fn clone(&self) -> Self {
+        self.channel.borrow_mut().senders += 1;
+        Self {
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+impl<T> Drop for LocalSender<T> {
+    fn drop(&mut self) {
+        let mut channel = self.channel.borrow_mut();
+        channel.senders -= 1;
+
+        // Let the receiver know that the channel is closed.
+        if channel.senders == 0 {
+            let waker = channel.recv_waker.take();
+            drop(channel);
+
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<T> LocalReceiver<T> {
+    /// Try to pop a value from the channel.
+    fn pop(&self) -> Option<T> {
+        let mut channel = self.channel.borrow_mut();
+        let value = channel.queue.pop_front()?;
+
+        // There is room in the channel now.
+        let wakers = mem::take(&mut channel.send_wakers);
+        drop(channel);
+        wakers.into_iter().for_each(Waker::wake);
+
+        Some(value)
+    }
+}
+
+impl<T> Drop for LocalReceiver<T> {
+    fn drop(&mut self) {
+        let mut channel = self.channel.borrow_mut();
+        channel.receiver = false;
+
+        // Let the senders know that the channel is closed.
+        let wakers = mem::take(&mut channel.send_wakers);
+        drop(channel);
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// The future returned by `LocalSender::send`.
+#[doc(hidden)]
+pub struct LocalSend<'a, T> {
+    sender: &'a LocalSender<T>,
+    value: Option<T>,
+}
+
+impl<T> Unpin for LocalSend<'_, T> {}
+
+impl<T> Future for LocalSend<'_, T> {
+    type Output = Result<(), __private::TrySendError<T>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let value = self.value.take().expect("polled after completion");
+
+        match self.sender.push(value) {
+            Err(__private::TrySendError::Full(value)) => {
+                // Wait for the receiver to make room.
+                self.value = Some(value);
+
+                let mut channel = self.sender.channel.borrow_mut();
+                if !channel.send_wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                    channel.send_wakers.push(cx.waker().clone());
+                }
+
+                Poll::Pending
+            }
+            result => Poll::Ready(result),
+        }
+    }
+}
+
+/// The future returned by `LocalReceiver::recv`.
+#[doc(hidden)]
+pub struct LocalRecv<'a, T> {
+    receiver: &'a LocalReceiver<T>,
+}
+
+impl<T> Future for LocalRecv<'_, T> {
+    type Output = Result<T, ()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(value) = self.receiver.pop() {
+            return Poll::Ready(Ok(value));
+        }
+
+        let mut channel = self.receiver.channel.borrow_mut();
+
+        // If all of the senders are gone, nothing else will be sent.
+        if channel.senders == 0 {
+            return Poll::Ready(Err(()));
+        }
+
+        match &channel.recv_waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            _ => channel.recv_waker = Some(cx.waker().clone()),
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<T> __private::Sender<T> for LocalSender<T> {
+    type Error = __private::TrySendError<T>;
+    type Send<'a> = LocalSend<'a, T> where Self: 'a;
 
     fn send(&self, value: T) -> Self::Send<'_> {
-        self.send(value).ok();
-        core::future::ready(Ok(()))
+        LocalSend {
+            sender: self,
+            value: Some(value),
+        }
     }
 
     fn try_send(&self, value: T) -> Result<(), Self::Error> {
-        self.send(value).ok();
-        Ok(())
+        self.push(value)
     }
 }
 
-impl<T> __private::Receiver<T> for us_channel::Receiver<T> {
+impl<T> __private::Receiver<T> for LocalReceiver<T> {
     type Error = ();
-    type Recv<'a> = std::pin::Pin<Box<dyn Future<Output = Result<T, Self::Error>> + 'a>> where Self: 'a;
+    type Recv<'a> = LocalRecv<'a, T> where Self: 'a;
 
     fn recv(&self) -> Self::Recv<'_> {
-        Box::pin(async move { self.recv().await.map_err(|_| ()) })
+        LocalRecv { receiver: self }
     }
 
     fn capacity(&self) -> usize {
-        usize::MAX
+        self.channel.borrow().capacity
     }
 
     fn try_recv(&self) -> Option<T> {
-        self.try_recv().ok()
+        self.pop()
     }
 
     fn len(&self) -> usize {
-        todo!()
+        self.channel.borrow().queue.len()
     }
 }
 
@@ -238,6 +627,24 @@ impl<T> __private::Mutex<T> for RefCell<T> {
     }
 }
 
+impl<T> __private::RwLock<T> for RefCell<T> {
+    type Error = Infallible;
+    type Read<'a> = Ref<'a, T> where Self: 'a;
+    type Write<'a> = RefMut<'a, T> where Self: 'a;
+
+    fn new(value: T) -> Self {
+        Self::new(value)
+    }
+
+    fn read(&self) -> Result<Self::Read<'_>, Self::Error> {
+        Ok(self.borrow())
+    }
+
+    fn write(&self) -> Result<Self::Write<'_>, Self::Error> {
+        Ok(self.borrow_mut())
+    }
+}
+
 impl<T> __private::OnceLock<T> for once_cell::unsync::OnceCell<T> {
     fn new() -> Self {
         Self::new()
@@ -260,9 +667,53 @@ impl<T> __private::OnceLock<T> for once_cell::unsync::OnceCell<T> {
 }
 
 impl<T> __private::Rc<T> for std::rc::Rc<T> {
+    type Weak = std::rc::Weak<T>;
+
     fn new(value: T) -> Self {
         Self::new(value)
     }
+
+    fn downgrade(this: &Self) -> Self::Weak {
+        Self::downgrade(this)
+    }
+
+    fn upgrade(weak: &Self::Weak) -> Option<Self> {
+        weak.upgrade()
+    }
+}
+
+#[cfg(feature = "executor")]
+impl __private::Executor for async_executor::LocalExecutor<'static> {
+    fn new() -> Self {
+        Self::new()
+    }
+
+    fn run<'a, T: 'a>(
+        &'a self,
+        future: core::pin::Pin<Box<dyn Future<Output = T> + 'a>>,
+    ) -> core::pin::Pin<Box<dyn Future<Output = T> + 'a>> {
+        Box::pin(self.run(future))
+    }
+}
+
+#[cfg(feature = "executor")]
+impl __private::Executor for async_executor::Executor<'static> {
+    fn new() -> Self {
+        Self::new()
+    }
+
+    fn run<'a, T: 'a>(
+        &'a self,
+        future: core::pin::Pin<Box<dyn Future<Output = T> + 'a>>,
+    ) -> core::pin::Pin<Box<dyn Future<Output = T> + 'a>> {
+        Box::pin(self.run(future))
+    }
+}
+
+impl __private::ExitInterceptor for Box<dyn FnMut(i32) -> Pin<Box<dyn Future<Output = bool>>>> {
+    fn intercept(&mut self, code: i32) -> Pin<Box<dyn Future<Output = bool>>> {
+        self(code)
+    }
 }
 
 #[cfg(feature = "thread_safe")]
@@ -270,8 +721,15 @@ pub(crate) mod thread_safe {
     use super::*;
 
     use concurrent_queue::ConcurrentQueue;
-    use std::sync::atomic;
-    use std::sync::{Arc, Mutex};
+    use std::sync::Arc;
+
+    // Under `--cfg loom`, use loom's primitives so that they can be model-checked.
+    #[cfg(loom)]
+    use loom::sync;
+    #[cfg(not(loom))]
+    use std::sync;
+
+    use sync::{atomic, Mutex, RwLock};
 
     /// Use thread-safe primitives.
     #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -281,21 +739,33 @@ pub(crate) mod thread_safe {
 
     impl ThreadSafety for ThreadSafe {}
 
+    impl<T: Send + ?Sized> MaybeSend<ThreadSafe> for T {}
+    impl<T: Sync + ?Sized> MaybeSync<ThreadSafe> for T {}
+
     impl __ThreadSafety for ThreadSafe {
         type Error = Box<dyn std::error::Error + Send + Sync>;
 
         type AtomicI64 = atomic::AtomicI64;
         type AtomicUsize = atomic::AtomicUsize;
         type AtomicU64 = atomic::AtomicU64;
+        type AtomicWaker = atomic_waker::AtomicWaker;
+        type Parker = parking::Parker;
 
         type Sender<T> = async_channel::Sender<T>;
         type Receiver<T> = async_channel::Receiver<T>;
 
         type ConcurrentQueue<T> = ConcurrentQueue<T>;
         type Mutex<T> = Mutex<T>;
+        type RwLock<T> = RwLock<T>;
         type OnceLock<T> = once_cell::sync::OnceCell<T>;
         type Rc<T> = Arc<T>;
 
+        #[cfg(feature = "executor")]
+        type Executor = async_executor::Executor<'static>;
+
+        type ExitInterceptor =
+            Box<dyn FnMut(i32) -> Pin<Box<dyn Future<Output = bool> + Send>> + Send>;
+
         fn channel_bounded<T>(capacity: usize) -> (Self::Sender<T>, Self::Receiver<T>) {
             async_channel::bounded(capacity)
         }
@@ -303,14 +773,28 @@ pub(crate) mod thread_safe {
         where
             Self: super::ThreadSafety,
         {
-            use once_cell::sync::OnceCell;
+            REACTOR
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .get_or_insert_with(|| Arc::new(Reactor::new()))
+                .clone()
+        }
 
-            static REACTOR: OnceCell<Arc<Reactor<ThreadSafe>>> = OnceCell::new();
+        fn reset_reactor()
+        where
+            Self: super::ThreadSafety,
+        {
+            let old = REACTOR.lock().unwrap_or_else(|e| e.into_inner()).take();
 
-            REACTOR.get_or_init(|| Arc::new(Reactor::new())).clone()
+            // Drop the old reactor outside of the lock.
+            drop(old);
         }
     }
 
+    /// The global reactor.
+    static REACTOR: std::sync::Mutex<Option<Arc<Reactor<ThreadSafe>>>> =
+        std::sync::Mutex::new(None);
+
     impl __private::Atomic<i64> for atomic::AtomicI64 {
         fn new(value: i64) -> Self {
             Self::new(value)
@@ -320,6 +804,10 @@ pub(crate) mod thread_safe {
             self.fetch_add(value, order)
         }
 
+        fn fetch_sub(&self, value: i64, order: atomic::Ordering) -> i64 {
+            self.fetch_sub(value, order)
+        }
+
         fn load(&self, order: atomic::Ordering) -> i64 {
             self.load(order)
         }
@@ -327,6 +815,20 @@ pub(crate) mod thread_safe {
         fn store(&self, value: i64, order: atomic::Ordering) {
             self.store(value, order)
         }
+
+        fn swap(&self, value: i64, order: atomic::Ordering) -> i64 {
+            self.swap(value, order)
+        }
+
+        fn compare_exchange(
+            &self,
+            current: i64,
+            new: i64,
+            success: atomic::Ordering,
+            failure: atomic::Ordering,
+        ) -> Result<i64, i64> {
+            self.compare_exchange(current, new, success, failure)
+        }
     }
 
     impl __private::Atomic<usize> for atomic::AtomicUsize {
@@ -338,6 +840,10 @@ pub(crate) mod thread_safe {
             self.fetch_add(value, order)
         }
 
+        fn fetch_sub(&self, value: usize, order: atomic::Ordering) -> usize {
+            self.fetch_sub(value, order)
+        }
+
         fn load(&self, order: atomic::Ordering) -> usize {
             self.load(order)
         }
@@ -345,6 +851,20 @@ pub(crate) mod thread_safe {
         fn store(&self, value: usize, order: atomic::Ordering) {
             self.store(value, order)
         }
+
+        fn swap(&self, value: usize, order: atomic::Ordering) -> usize {
+            self.swap(value, order)
+        }
+
+        fn compare_exchange(
+            &self,
+            current: usize,
+            new: usize,
+            success: atomic::Ordering,
+            failure: atomic::Ordering,
+        ) -> Result<usize, usize> {
+            self.compare_exchange(current, new, success, failure)
+        }
     }
 
     impl __private::Atomic<u64> for atomic::AtomicU64 {
@@ -356,6 +876,10 @@ pub(crate) mod thread_safe {
             self.fetch_add(value, order)
         }
 
+        fn fetch_sub(&self, value: u64, order: atomic::Ordering) -> u64 {
+            self.fetch_sub(value, order)
+        }
+
         fn load(&self, order: atomic::Ordering) -> u64 {
             self.load(order)
         }
@@ -363,18 +887,96 @@ pub(crate) mod thread_safe {
         fn store(&self, value: u64, order: atomic::Ordering) {
             self.store(value, order)
         }
+
+        fn swap(&self, value: u64, order: atomic::Ordering) -> u64 {
+            self.swap(value, order)
+        }
+
+        fn compare_exchange(
+            &self,
+            current: u64,
+            new: u64,
+            success: atomic::Ordering,
+            failure: atomic::Ordering,
+        ) -> Result<u64, u64> {
+            self.compare_exchange(current, new, success, failure)
+        }
+    }
+
+    impl __private::AtomicWaker for atomic_waker::AtomicWaker {
+        fn new() -> Self {
+            Self::new()
+        }
+
+        fn register(&self, waker: &Waker) {
+            self.register(waker)
+        }
+
+        fn take(&self) -> Option<Waker> {
+            self.take()
+        }
+    }
+
+    impl __private::Parker for parking::Parker {
+        type Unparker = parking::Unparker;
+
+        fn pair() -> (Self, Self::Unparker) {
+            parking::pair()
+        }
+
+        fn park(&self) {
+            self.park()
+        }
+
+        fn park_deadline(&self, deadline: Instant) {
+            // `parking` takes a `std::time::Instant`, which isn't our `Instant` on the web.
+            self.park_timeout(deadline.saturating_duration_since(Instant::now()));
+        }
+    }
+
+    impl __private::Unparker for parking::Unparker {
+        fn unpark(&self) {
+            self.unpark();
+        }
     }
 
     impl<T> __private::Sender<T> for async_channel::Sender<T> {
-        type Error = async_channel::SendError<T>;
-        type Send<'a> = async_channel::Send<'a, T> where Self: 'a;
+        type Error = __private::TrySendError<T>;
+        type Send<'a> = SendFuture<'a, T> where Self: 'a;
 
         fn send(&self, value: T) -> Self::Send<'_> {
-            self.send(value)
+            SendFuture {
+                inner: self.send(value),
+            }
         }
 
         fn try_send(&self, value: T) -> Result<(), Self::Error> {
-            self.try_send(value).map_err(|_e| todo!())
+            self.try_send(value).map_err(|e| match e {
+                async_channel::TrySendError::Full(value) => __private::TrySendError::Full(value),
+                async_channel::TrySendError::Closed(value) => {
+                    __private::TrySendError::Closed(value)
+                }
+            })
+        }
+    }
+
+    pin_project_lite::pin_project! {
+        /// The future returned by `Sender::send`.
+        #[doc(hidden)]
+        pub struct SendFuture<'a, T> {
+            #[pin]
+            inner: async_channel::Send<'a, T>,
+        }
+    }
+
+    impl<T> Future for SendFuture<'_, T> {
+        type Output = Result<(), __private::TrySendError<T>>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            self.project()
+                .inner
+                .poll(cx)
+                .map_err(|e| __private::TrySendError::Closed(e.into_inner()))
         }
     }
 
@@ -425,7 +1027,7 @@ pub(crate) mod thread_safe {
 
     impl<T> __private::Mutex<T> for Mutex<T> {
         type Error = Infallible;
-        type Lock<'a> = std::sync::MutexGuard<'a, T> where Self: 'a;
+        type Lock<'a> = sync::MutexGuard<'a, T> where Self: 'a;
 
         fn new(value: T) -> Self {
             Self::new(value)
@@ -436,6 +1038,24 @@ pub(crate) mod thread_safe {
         }
     }
 
+    impl<T> __private::RwLock<T> for RwLock<T> {
+        type Error = Infallible;
+        type Read<'a> = sync::RwLockReadGuard<'a, T> where Self: 'a;
+        type Write<'a> = sync::RwLockWriteGuard<'a, T> where Self: 'a;
+
+        fn new(value: T) -> Self {
+            Self::new(value)
+        }
+
+        fn read(&self) -> Result<Self::Read<'_>, Self::Error> {
+            Ok(self.read().unwrap_or_else(|e| e.into_inner()))
+        }
+
+        fn write(&self) -> Result<Self::Write<'_>, Self::Error> {
+            Ok(self.write().unwrap_or_else(|e| e.into_inner()))
+        }
+    }
+
     impl<T> __private::OnceLock<T> for once_cell::sync::OnceCell<T> {
         fn new() -> Self {
             Self::new()
@@ -458,17 +1078,93 @@ pub(crate) mod thread_safe {
     }
 
     impl<T> __private::Rc<T> for Arc<T> {
+        type Weak = std::sync::Weak<T>;
+
         fn new(value: T) -> Self {
             Self::new(value)
         }
+
+        fn downgrade(this: &Self) -> Self::Weak {
+            Self::downgrade(this)
+        }
+
+        fn upgrade(weak: &Self::Weak) -> Option<Self> {
+            weak.upgrade()
+        }
+    }
+
+    impl __private::ExitInterceptor
+        for Box<dyn FnMut(i32) -> Pin<Box<dyn Future<Output = bool> + Send>> + Send>
+    {
+        fn intercept(&mut self, code: i32) -> Pin<Box<dyn Future<Output = bool>>> {
+            self(code)
+        }
     }
+
+    /// Block the current thread on a future.
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        /// Unparks the blocked thread.
+        struct Unpark(parking::Unparker);
+
+        impl std::task::Wake for Unpark {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+
+            fn wake_by_ref(self: &Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let (parker, unparker) = parking::pair();
+        let waker = Waker::from(Arc::new(Unpark(unparker)));
+        let mut cx = Context::from_waker(&waker);
+
+        futures_lite::pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+
+            parker.park();
+        }
+    }
+
+    /// Make sure that event subscriptions and window operations can cross threads.
+    ///
+    /// None of these types implement `Send` or `Sync` manually; they get them from the primitives
+    /// above. These checks keep that from silently regressing.
+    const _: fn(&crate::window::Window<ThreadSafe>) = |window| {
+        use crate::event::{KeyboardInput, ScaleFactor};
+        use crate::handler::{Handler, HoldGuard, Waiter};
+        use crate::window::Window;
+        use crate::Timer;
+
+        fn assert_send<T: Send>(_: &T) {}
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Window<ThreadSafe>>();
+        assert_send_sync::<Handler<(), ThreadSafe>>();
+        assert_send_sync::<Handler<ScaleFactor, ThreadSafe>>();
+        assert_send_sync::<Waiter<'static, KeyboardInput, ThreadSafe>>();
+        assert_send_sync::<HoldGuard<'static, 'static, KeyboardInput, ThreadSafe>>();
+        assert_send_sync::<HoldGuard<'static, 'static, ScaleFactor, ThreadSafe>>();
+        assert_send_sync::<Timer<ThreadSafe>>();
+
+        assert_send(&window.inner_size());
+        assert_send(&window.set_title("title"));
+        assert_send(&window.current_monitor());
+        assert_send(&window.close_requested().wait());
+    };
 }
 
 pub(crate) mod __private {
     use core::fmt::{Debug, Display};
     use core::future::Future;
-    use core::ops::{Add, Deref, DerefMut};
+    use core::ops::{Add, Deref, DerefMut, Sub};
     use core::sync::atomic;
+    use core::task::Waker;
+    use instant::Instant;
 
     #[doc(hidden)]
     pub trait __ThreadSafety: Sized {
@@ -477,19 +1173,30 @@ pub(crate) mod __private {
         type AtomicI64: Atomic<i64>;
         type AtomicUsize: Atomic<usize>;
         type AtomicU64: Atomic<u64>;
+        type AtomicWaker: AtomicWaker;
+        type Parker: Parker;
 
         type Sender<T>: Sender<T>;
         type Receiver<T>: Receiver<T>;
 
         type ConcurrentQueue<T>: ConcurrentQueue<T>;
         type Mutex<T>: Mutex<T>;
+        type RwLock<T>: RwLock<T>;
         type OnceLock<T>: OnceLock<T>;
         type Rc<T>: Rc<T>;
 
+        #[cfg(feature = "executor")]
+        type Executor: Executor;
+
+        type ExitInterceptor: ExitInterceptor;
+
         fn channel_bounded<T>(capacity: usize) -> (Self::Sender<T>, Self::Receiver<T>);
         fn get_reactor() -> Self::Rc<crate::reactor::Reactor<Self>>
         where
             Self: super::ThreadSafety;
+        fn reset_reactor()
+        where
+            Self: super::ThreadSafety;
     }
 
     #[doc(hidden)]
@@ -497,14 +1204,87 @@ pub(crate) mod __private {
         fn new(value: T) -> Self;
         fn load(&self, order: atomic::Ordering) -> T;
         fn store(&self, value: T, order: atomic::Ordering);
+        fn swap(&self, value: T, order: atomic::Ordering) -> T;
+        fn compare_exchange(
+            &self,
+            current: T,
+            new: T,
+            success: atomic::Ordering,
+            failure: atomic::Ordering,
+        ) -> Result<T, T>
+        where
+            T: PartialEq;
         fn fetch_add(&self, value: T, order: atomic::Ordering) -> T
         where
             T: Add<Output = T>;
+        fn fetch_sub(&self, value: T, order: atomic::Ordering) -> T
+        where
+            T: Sub<Output = T>;
+    }
+
+    #[doc(hidden)]
+    pub trait AtomicWaker {
+        fn new() -> Self;
+        fn register(&self, waker: &Waker);
+        fn take(&self) -> Option<Waker>;
+    }
+
+    #[doc(hidden)]
+    pub trait Parker: Sized {
+        type Unparker: Unparker;
+
+        fn pair() -> (Self, Self::Unparker);
+        fn park(&self);
+        fn park_deadline(&self, deadline: Instant);
+    }
+
+    #[doc(hidden)]
+    pub trait Unparker: Send + Sync + 'static {
+        fn unpark(&self);
+    }
+
+    /// An error that occurs while sending into a channel.
+    #[doc(hidden)]
+    pub enum TrySendError<T> {
+        /// The channel is full.
+        Full(T),
+
+        /// The channel is closed.
+        Closed(T),
+    }
+
+    impl<T> TrySendError<T> {
+        /// Get the value that couldn't be sent.
+        pub fn into_inner(self) -> T {
+            match self {
+                Self::Full(value) | Self::Closed(value) => value,
+            }
+        }
+    }
+
+    impl<T> Debug for TrySendError<T> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            match self {
+                Self::Full(_) => f.write_str("Full(..)"),
+                Self::Closed(_) => f.write_str("Closed(..)"),
+            }
+        }
     }
 
+    impl<T> Display for TrySendError<T> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            match self {
+                Self::Full(_) => f.write_str("sending into a full channel"),
+                Self::Closed(_) => f.write_str("sending into a closed channel"),
+            }
+        }
+    }
+
+    impl<T> std::error::Error for TrySendError<T> {}
+
     #[doc(hidden)]
     pub trait Sender<T> {
-        type Error;
+        type Error: Debug + Display;
         type Send<'a>: Future<Output = Result<(), Self::Error>> + 'a
         where
             Self: 'a;
@@ -546,6 +1326,21 @@ pub(crate) mod __private {
         fn lock(&self) -> Result<Self::Lock<'_>, Self::Error>;
     }
 
+    #[doc(hidden)]
+    pub trait RwLock<T> {
+        type Error: Debug + Display;
+        type Read<'a>: Deref<Target = T> + 'a
+        where
+            Self: 'a;
+        type Write<'a>: DerefMut<Target = T> + 'a
+        where
+            Self: 'a;
+
+        fn new(value: T) -> Self;
+        fn read(&self) -> Result<Self::Read<'_>, Self::Error>;
+        fn write(&self) -> Result<Self::Write<'_>, Self::Error>;
+    }
+
     #[doc(hidden)]
     pub trait ConcurrentQueue<T> {
         type TryIter<'a>: Iterator<Item = T> + 'a
@@ -561,6 +1356,176 @@ pub(crate) mod __private {
 
     #[doc(hidden)]
     pub trait Rc<T>: Clone + Deref<Target = T> {
+        type Weak: Clone;
+
         fn new(value: T) -> Self;
+        fn downgrade(this: &Self) -> Self::Weak;
+        fn upgrade(weak: &Self::Weak) -> Option<Self>;
+    }
+
+    #[doc(hidden)]
+    pub trait ExitInterceptor {
+        fn intercept(&mut self, code: i32) -> core::pin::Pin<Box<dyn Future<Output = bool>>>;
+    }
+
+    #[cfg(feature = "executor")]
+    #[doc(hidden)]
+    pub trait Executor {
+        fn new() -> Self;
+        fn run<'a, T: 'a>(
+            &'a self,
+            future: core::pin::Pin<Box<dyn Future<Output = T> + 'a>>,
+        ) -> core::pin::Pin<Box<dyn Future<Output = T> + 'a>>;
+    }
+}
+
+/// A waker that records whether it was woken, for tests.
+#[cfg(test)]
+pub(crate) struct WakeFlag(std::sync::atomic::AtomicBool);
+
+#[cfg(test)]
+impl WakeFlag {
+    /// Create a flag, along with a waker that sets it.
+    pub(crate) fn new() -> (std::sync::Arc<Self>, Waker) {
+        let flag = std::sync::Arc::new(WakeFlag(std::sync::atomic::AtomicBool::new(false)));
+        (flag.clone(), Waker::from(flag))
+    }
+
+    /// Check whether the waker was woken since the last check.
+    pub(crate) fn woken(&self) -> bool {
+        self.0.swap(false, atomic::Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+impl std::task::Wake for WakeFlag {
+    fn wake(self: std::sync::Arc<Self>) {
+        self.0.store(true, atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::__private::{Receiver as _, Sender as _, TrySendError};
+    use super::*;
+
+    use futures_lite::future;
+
+    #[test]
+    fn local_channel_is_bounded() {
+        let (tx, rx) = local_channel(2);
+        assert_eq!(rx.capacity(), 2);
+
+        assert!(tx.try_send(1).is_ok());
+        assert!(tx.try_send(2).is_ok());
+        assert!(matches!(tx.try_send(3), Err(TrySendError::Full(3))));
+        assert_eq!(rx.len(), 2);
+
+        // Receiving makes room for another value.
+        assert_eq!(rx.try_recv(), Some(1));
+        assert!(tx.try_send(3).is_ok());
+        assert_eq!(rx.try_recv(), Some(2));
+        assert_eq!(rx.try_recv(), Some(3));
+        assert_eq!(rx.try_recv(), None);
+    }
+
+    #[test]
+    fn local_channel_holds_at_least_one_value() {
+        let (tx, rx) = local_channel(0);
+        assert_eq!(rx.capacity(), 1);
+
+        assert!(tx.try_send(1).is_ok());
+        assert!(matches!(tx.try_send(2), Err(TrySendError::Full(2))));
+    }
+
+    #[test]
+    fn local_channel_closes_without_receiver() {
+        let (tx, rx) = local_channel(1);
+        drop(rx);
+
+        assert!(matches!(tx.try_send(1), Err(TrySendError::Closed(1))));
+        assert!(matches!(
+            future::block_on(tx.send(2)),
+            Err(TrySendError::Closed(2))
+        ));
+    }
+
+    #[test]
+    fn local_channel_closes_without_senders() {
+        let (tx, rx) = local_channel(1);
+        let tx2 = tx.clone();
+
+        // The channel stays open while any sender is alive.
+        drop(tx);
+        assert!(tx2.try_send(1).is_ok());
+        assert_eq!(future::block_on(rx.recv()), Ok(1));
+
+        drop(tx2);
+        assert_eq!(future::block_on(rx.recv()), Err(()));
+    }
+
+    #[test]
+    fn full_send_wakes_once_there_is_room() {
+        let (tx, rx) = local_channel(1);
+        let (flag, waker) = WakeFlag::new();
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(tx.try_send(1).is_ok());
+        let mut send = tx.send(2);
+        assert!(Pin::new(&mut send).poll(&mut cx).is_pending());
+
+        assert_eq!(rx.try_recv(), Some(1));
+        assert!(flag.woken());
+        assert!(matches!(
+            Pin::new(&mut send).poll(&mut cx),
+            Poll::Ready(Ok(()))
+        ));
+        assert_eq!(rx.try_recv(), Some(2));
+    }
+
+    #[test]
+    fn full_send_wakes_on_close() {
+        let (tx, rx) = local_channel(1);
+        let (flag, waker) = WakeFlag::new();
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(tx.try_send(1).is_ok());
+        let mut send = tx.send(2);
+        assert!(Pin::new(&mut send).poll(&mut cx).is_pending());
+
+        drop(rx);
+        assert!(flag.woken());
+        assert!(matches!(
+            Pin::new(&mut send).poll(&mut cx),
+            Poll::Ready(Err(TrySendError::Closed(2)))
+        ));
+    }
+
+    #[test]
+    fn empty_recv_wakes_on_send() {
+        let (tx, rx) = local_channel(1);
+        let (flag, waker) = WakeFlag::new();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut recv = rx.recv();
+        assert!(Pin::new(&mut recv).poll(&mut cx).is_pending());
+
+        assert!(tx.try_send(1).is_ok());
+        assert!(flag.woken());
+        assert_eq!(Pin::new(&mut recv).poll(&mut cx), Poll::Ready(Ok(1)));
+    }
+
+    #[test]
+    fn empty_recv_wakes_on_close() {
+        let (tx, rx) = local_channel::<i32>(1);
+        let (flag, waker) = WakeFlag::new();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut recv = rx.recv();
+        assert!(Pin::new(&mut recv).poll(&mut cx).is_pending());
+
+        drop(tx);
+        assert!(flag.woken());
+        assert_eq!(Pin::new(&mut recv).poll(&mut cx), Poll::Ready(Err(())));
     }
 }
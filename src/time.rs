@@ -0,0 +1,137 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+//! Utilities for tracking time, built on top of the [`Timer`] type.
+//!
+//! These use the same timer wheel as the event loop, so they wake up the event loop correctly.
+//!
+//! ```no_run
+//! use async_winit::event_loop::EventLoop;
+//! use async_winit::time::{sleep, timeout};
+//! use async_winit::ThreadUnsafe;
+//!
+//! use std::time::Duration;
+//!
+//! EventLoop::<ThreadUnsafe>::new().block_on(async move {
+//!     sleep::<ThreadUnsafe>(Duration::from_secs(1)).await;
+//!
+//!     let result = timeout::<ThreadUnsafe, _>(
+//!         Duration::from_secs(1),
+//!         futures_lite::future::pending::<()>(),
+//!     )
+//!     .await;
+//!     assert!(result.is_err());
+//! #   futures_lite::future::pending().await
+//! });
+//! ```
+
+use crate::sync::ThreadSafety;
+use crate::timer::Timer;
+use crate::DefaultThreadSafety;
+
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use instant::Instant;
+
+/// Wait for the given duration to elapse.
+pub fn sleep<TS: ThreadSafety>(duration: Duration) -> Timer<TS> {
+    Timer::after(duration)
+}
+
+/// Wait until the given deadline.
+pub fn sleep_until<TS: ThreadSafety>(deadline: Instant) -> Timer<TS> {
+    Timer::at(deadline)
+}
+
+/// Run a future, failing if it doesn't complete within the given duration.
+pub fn timeout<TS: ThreadSafety, F: Future>(duration: Duration, future: F) -> Timeout<F, TS> {
+    Timeout {
+        future,
+        timer: Timer::after(duration),
+    }
+}
+
+/// Run a future, failing if it doesn't complete before the given deadline.
+pub fn timeout_at<TS: ThreadSafety, F: Future>(deadline: Instant, future: F) -> Timeout<F, TS> {
+    Timeout {
+        future,
+        timer: Timer::at(deadline),
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The future returned by [`timeout`] and [`timeout_at`].
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Timeout<F, TS: ThreadSafety = DefaultThreadSafety> {
+        #[pin]
+        future: F,
+        timer: Timer<TS>,
+    }
+}
+
+impl<F, TS: ThreadSafety> Timeout<F, TS> {
+    /// Get a reference to the underlying future.
+    pub fn get_ref(&self) -> &F {
+        &self.future
+    }
+
+    /// Get a mutable reference to the underlying future.
+    pub fn get_mut(&mut self) -> &mut F {
+        &mut self.future
+    }
+
+    /// Get the underlying future.
+    pub fn into_inner(self) -> F {
+        self.future
+    }
+}
+
+impl<F: Future, TS: ThreadSafety> Future for Timeout<F, TS> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        // Poll the future first, so it gets a chance to complete.
+        if let Poll::Ready(output) = this.future.poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+
+        match Pin::new(this.timer).poll(cx) {
+            Poll::Ready(_) => Poll::Ready(Err(Elapsed(()))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// The error returned when a [`timeout`] elapses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Elapsed(());
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("deadline has elapsed")
+    }
+}
+
+impl Error for Elapsed {}
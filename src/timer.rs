@@ -28,9 +28,11 @@ use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll, Waker};
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
+use futures_core::{FusedFuture, FusedStream};
 use futures_lite::stream::Stream;
+use instant::{Instant, SystemTime};
 
 /// A future or stream that emits timer events.
 ///
@@ -54,6 +56,44 @@ pub struct Timer<TS: ThreadSafety = crate::DefaultThreadSafety> {
 
     /// The period.
     period: Duration,
+
+    /// What to do when ticks are missed.
+    policy: TickPolicy,
+
+    /// Whether this timer has been paused, and the last waker that polled it while paused.
+    paused: Option<Option<Waker>>,
+
+    /// Whether to stop scheduling wakeups while the application is in the background.
+    pause_in_background: bool,
+
+    /// How much later than the deadline this timer is allowed to fire.
+    slack: Duration,
+
+    /// The offset from wall-clock period boundaries, if this timer is aligned to the wall clock.
+    alignment: Option<Duration>,
+}
+
+/// The slack used by coarse timers.
+const COARSE_SLACK: Duration = Duration::from_millis(50);
+
+/// What an interval [`Timer`] does when it is polled late and misses ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TickPolicy {
+    /// Fire all of the missed ticks as soon as possible.
+    ///
+    /// This keeps the timer aligned to the original schedule. This is the default.
+    #[default]
+    Burst,
+
+    /// Skip the missed ticks, and fire at the next deadline that is still in the future.
+    ///
+    /// This keeps the timer aligned to the original schedule.
+    Skip,
+
+    /// Schedule the next tick one period after the timer fired.
+    ///
+    /// This does not keep the timer aligned to the original schedule.
+    Delay,
 }
 
 impl<TS: ThreadSafety> fmt::Debug for Timer<TS> {
@@ -61,6 +101,11 @@ impl<TS: ThreadSafety> fmt::Debug for Timer<TS> {
         f.debug_struct("Timer")
             .field("deadline", &self.deadline)
             .field("period", &self.period)
+            .field("policy", &self.policy)
+            .field("paused", &self.paused.is_some())
+            .field("pause_in_background", &self.pause_in_background)
+            .field("slack", &self.slack)
+            .field("alignment", &self.alignment)
             .field("registered", &self.id_and_waker.is_some())
             .finish()
     }
@@ -76,6 +121,11 @@ impl<TS: ThreadSafety> Timer<TS> {
             id_and_waker: None,
             deadline: None,
             period: Duration::MAX,
+            policy: TickPolicy::default(),
+            paused: None,
+            pause_in_background: false,
+            slack: Duration::ZERO,
+            alignment: None,
         }
     }
 
@@ -84,6 +134,28 @@ impl<TS: ThreadSafety> Timer<TS> {
         self.deadline.is_some()
     }
 
+    /// Get the time at which this timer will next fire, if it will fire at all.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Get the period of this timer, if it fires on an interval.
+    pub fn period(&self) -> Option<Duration> {
+        if self.period == Duration::MAX {
+            None
+        } else {
+            Some(self.period)
+        }
+    }
+
+    /// Get the amount of time left until this timer next fires, if it will fire at all.
+    ///
+    /// Returns `Duration::ZERO` if the deadline has already passed.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
     /// Create a timer that fires after the given duration.
     pub fn after(duration: Duration) -> Self {
         Instant::now()
@@ -91,6 +163,17 @@ impl<TS: ThreadSafety> Timer<TS> {
             .map_or_else(Self::never, Self::at)
     }
 
+    /// Create a timer that fires after the given duration, but doesn't need to be precise.
+    ///
+    /// The timer may fire up to 50 milliseconds late, so that it can share a wakeup with other
+    /// coarse timers. This is useful for background tasks, since it avoids waking up the event loop
+    /// for every timer individually.
+    pub fn after_coarse(duration: Duration) -> Self {
+        let mut timer = Self::after(duration);
+        timer.slack = COARSE_SLACK;
+        timer
+    }
+
     /// Create a timer that fires at the given time.
     pub fn at(deadline: Instant) -> Self {
         Self::interval_at(deadline, Duration::MAX)
@@ -103,6 +186,23 @@ impl<TS: ThreadSafety> Timer<TS> {
             .map_or_else(Self::never, |deadline| Self::interval_at(deadline, period))
     }
 
+    /// Create a timer that fires on an interval that is aligned to the wall clock.
+    ///
+    /// The timer fires whenever the time since the Unix epoch, minus `offset`, is a multiple of
+    /// `period`. For instance, a `period` of one minute and an `offset` of zero fires every minute
+    /// on the minute. The next deadline is recomputed from the system clock every time the timer
+    /// fires, so the timer stays aligned even if the system clock is adjusted.
+    pub fn interval_aligned(period: Duration, offset: Duration) -> Self {
+        match next_aligned_deadline(period, offset, Duration::ZERO) {
+            Some(deadline) => {
+                let mut timer = Self::interval_at(deadline, period);
+                timer.alignment = Some(offset);
+                timer
+            }
+            None => Self::never(),
+        }
+    }
+
     /// Create a timer that fires on an interval starting at the given time.
     pub fn interval_at(start: Instant, period: Duration) -> Self {
         Self {
@@ -110,9 +210,78 @@ impl<TS: ThreadSafety> Timer<TS> {
             id_and_waker: None,
             deadline: Some(start),
             period,
+            policy: TickPolicy::default(),
+            paused: None,
+            pause_in_background: false,
+            slack: Duration::ZERO,
+            alignment: None,
+        }
+    }
+
+    /// Get what this timer does when it misses ticks.
+    pub fn tick_policy(&self) -> TickPolicy {
+        self.policy
+    }
+
+    /// Set what this timer does when it misses ticks.
+    ///
+    /// This only has an effect on interval timers.
+    pub fn set_tick_policy(&mut self, policy: TickPolicy) {
+        self.policy = policy;
+    }
+
+    /// Get how much later than its deadline this timer is allowed to fire.
+    pub fn slack(&self) -> Duration {
+        self.slack
+    }
+
+    /// Set how much later than its deadline this timer is allowed to fire.
+    ///
+    /// Timers with the same slack and nearby deadlines are coalesced into a single wakeup of the
+    /// event loop. By default, timers have no slack.
+    pub fn set_slack(&mut self, slack: Duration) {
+        // Re-register the timer with the new slack.
+        if let (Some(deadline), Some((id, waker))) = (self.deadline, self.id_and_waker.as_mut()) {
+            self.reactor.remove_timer(*id);
+            *id = self.reactor.insert_timer(deadline, slack, waker);
+        }
+
+        self.slack = slack;
+    }
+
+    /// Pause this timer.
+    ///
+    /// While the timer is paused, it does not fire and does not wake up the event loop. Once it is
+    /// resumed, it fires if its deadline has passed in the meantime.
+    pub fn pause(&mut self) {
+        if self.paused.is_none() {
+            self.deregister();
+            self.paused = Some(None);
+        }
+    }
+
+    /// Resume this timer after it has been paused.
+    pub fn resume(&mut self) {
+        if let Some(Some(waker)) = self.paused.take() {
+            waker.wake();
         }
     }
 
+    /// Returns `true` if this timer is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    /// Set whether this timer stops scheduling wakeups while the application is in the background.
+    ///
+    /// The application is in the background while it is suspended, or while all of its windows
+    /// are occluded. Once the application comes back into the foreground, the timer fires if its
+    /// deadline has passed in the meantime. This is useful for interval timers that drive
+    /// animations, which should not keep waking up the event loop while nothing is visible.
+    pub fn set_pause_in_background(&mut self, pause_in_background: bool) {
+        self.pause_in_background = pause_in_background;
+    }
+
     /// Set this timer to never fire.
     pub fn set_never(&mut self) {
         self.clear();
@@ -143,19 +312,28 @@ impl<TS: ThreadSafety> Timer<TS> {
     /// Set this timer to run on an interval starting at the given time.
     pub fn set_interval_at(&mut self, start: Instant, period: Duration) {
         self.clear();
+        self.alignment = None;
 
         self.deadline = Some(start);
         self.period = period;
 
         if let Some((id, waker)) = self.id_and_waker.as_mut() {
             // Re-register the timer into the reactor.
-            *id = self.reactor.insert_timer(start, waker);
+            *id = self.reactor.insert_timer(start, self.slack, waker);
+        }
+    }
+
+    /// Remove this timer from the reactor, without changing its deadline.
+    fn deregister(&mut self) {
+        if let Some((id, _)) = self.id_and_waker.take() {
+            self.reactor.remove_timer(id);
         }
     }
 
     fn clear(&mut self) {
-        if let (Some(deadline), Some((id, _))) = (self.deadline.take(), self.id_and_waker.take()) {
-            self.reactor.remove_timer(deadline, id);
+        self.deadline = None;
+        if let Some((id, _)) = self.id_and_waker.take() {
+            self.reactor.remove_timer(id);
         }
     }
 }
@@ -174,27 +352,75 @@ impl<TS: ThreadSafety> Future for Timer<TS> {
     }
 }
 
+impl<TS: ThreadSafety> FusedFuture for Timer<TS> {
+    fn is_terminated(&self) -> bool {
+        !self.will_fire()
+    }
+}
+
+impl<TS: ThreadSafety> FusedStream for Timer<TS> {
+    fn is_terminated(&self) -> bool {
+        !self.will_fire()
+    }
+}
+
 impl<TS: ThreadSafety> Stream for Timer<TS> {
     type Item = Instant;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.get_mut();
 
+        // Don't schedule any wakeups while we're paused.
+        if this.paused.is_some() {
+            this.deregister();
+            match &mut this.paused {
+                Some(Some(waker)) if waker.will_wake(cx.waker()) => {}
+                paused => *paused = Some(Some(cx.waker().clone())),
+            }
+            return Poll::Pending;
+        }
+
+        // Don't schedule any wakeups while the application is in the background.
+        if this.pause_in_background && this.reactor.in_background() {
+            this.deregister();
+            this.reactor.register_background_waker(cx.waker());
+
+            // Check again, in case the application came back while we were registering.
+            if this.reactor.in_background() {
+                return Poll::Pending;
+            }
+        }
+
         if let Some(ref mut deadline) = this.deadline {
             // Check if the timer is ready.
-            if *deadline < Instant::now() {
-                if let Some((id, _)) = this.id_and_waker.take() {
-                    this.reactor.remove_timer(*deadline, id);
+            let now = Instant::now();
+            if *deadline < now {
+                let old = this.id_and_waker.take();
+                if let Some((id, _)) = &old {
+                    this.reactor.remove_timer(*id);
                 }
 
                 let result_time = *deadline;
 
-                if let Some(next) = deadline.checked_add(this.period) {
+                let next = match this.alignment {
+                    // Skip the boundary we just fired for, in case the wall clock is slightly
+                    // behind the monotonic clock.
+                    Some(offset) => next_aligned_deadline(this.period, offset, this.period / 2),
+                    None => next_deadline(*deadline, now, this.period, this.policy),
+                };
+
+                if let Some(next) = next {
                     *deadline = next;
 
+                    // Reuse the waker we already have, if it's the right one.
+                    let waker = match old {
+                        Some((_, waker)) if waker.will_wake(cx.waker()) => waker,
+                        _ => cx.waker().clone(),
+                    };
+
                     // Register the timer into the reactor.
-                    let id = this.reactor.insert_timer(next, cx.waker());
-                    this.id_and_waker = Some((id, cx.waker().clone()));
+                    let id = this.reactor.insert_timer(next, this.slack, &waker);
+                    this.id_and_waker = Some((id, waker));
                 } else {
                     this.deadline = None;
                 }
@@ -202,20 +428,20 @@ impl<TS: ThreadSafety> Stream for Timer<TS> {
                 // Return the time that we fired at.
                 return Poll::Ready(Some(result_time));
             } else {
-                match &this.id_and_waker {
+                match &mut this.id_and_waker {
                     None => {
                         // This timer needs to be registered.
-                        let id = this.reactor.insert_timer(*deadline, cx.waker());
+                        let id = this.reactor.insert_timer(*deadline, this.slack, cx.waker());
                         this.id_and_waker = Some((id, cx.waker().clone()));
                     }
 
                     Some((id, w)) if !w.will_wake(cx.waker()) => {
-                        // Deregister timer and remove the old waker.
-                        this.reactor.remove_timer(*deadline, *id);
+                        // Swap out the waker in place, instead of re-registering the timer.
+                        if !this.reactor.update_timer_waker(*id, cx.waker()) {
+                            *id = this.reactor.insert_timer(*deadline, this.slack, cx.waker());
+                        }
 
-                        // Register the timer into the reactor.
-                        let id = this.reactor.insert_timer(*deadline, cx.waker());
-                        this.id_and_waker = Some((id, cx.waker().clone()));
+                        *w = cx.waker().clone();
                     }
 
                     _ => {}
@@ -226,3 +452,171 @@ impl<TS: ThreadSafety> Stream for Timer<TS> {
         Poll::Pending
     }
 }
+
+/// The number of nanoseconds in a second.
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Figure out when an interval timer that fired at `now` should fire next.
+///
+/// This only returns `None` if the next deadline can't be represented as an `Instant`.
+fn next_deadline(
+    deadline: Instant,
+    now: Instant,
+    period: Duration,
+    policy: TickPolicy,
+) -> Option<Instant> {
+    match policy {
+        TickPolicy::Burst => deadline.checked_add(period),
+        TickPolicy::Delay => now.checked_add(period),
+        TickPolicy::Skip => {
+            if period.is_zero() {
+                return Some(now);
+            }
+
+            // Skip over every tick that has already passed. Working with the time into the current
+            // period avoids counting the ticks, which can overflow for short periods.
+            let elapsed = now.saturating_duration_since(deadline);
+            let into_period = elapsed.as_nanos() % period.as_nanos();
+            let into_period = Duration::new(
+                (into_period / NANOS_PER_SEC) as u64,
+                (into_period % NANOS_PER_SEC) as u32,
+            );
+
+            now.checked_add(period - into_period)
+        }
+    }
+}
+
+/// Figure out when a timer aligned to the wall clock should fire next.
+///
+/// Only boundaries that are more than `margin` in the future are considered.
+fn next_aligned_deadline(period: Duration, offset: Duration, margin: Duration) -> Option<Instant> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()?;
+    let wait = aligned_wait(now, period, offset, margin)?;
+
+    Instant::now().checked_add(wait)
+}
+
+/// Figure out how long to wait from `now`, the time since the Unix epoch, until the next boundary
+/// that is more than `margin` away.
+fn aligned_wait(
+    now: Duration,
+    period: Duration,
+    offset: Duration,
+    margin: Duration,
+) -> Option<Duration> {
+    if period.is_zero() {
+        return None;
+    }
+
+    let period = period.as_nanos();
+    let offset = offset.as_nanos() % period;
+
+    let now = now.as_nanos();
+    let after = now + margin.as_nanos();
+    let next = (after + period - offset) / period * period + offset;
+
+    let wait = u64::try_from(next - now).ok()?;
+    Some(Duration::from_nanos(wait))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERIOD: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn burst_keeps_the_schedule() {
+        let start = Instant::now();
+        let now = start + PERIOD * 3;
+
+        let next = next_deadline(start, now, PERIOD, TickPolicy::Burst);
+        assert_eq!(next, Some(start + PERIOD));
+    }
+
+    #[test]
+    fn delay_restarts_from_now() {
+        let start = Instant::now();
+        let now = start + PERIOD * 3 + Duration::from_millis(4);
+
+        let next = next_deadline(start, now, PERIOD, TickPolicy::Delay);
+        assert_eq!(next, Some(now + PERIOD));
+    }
+
+    #[test]
+    fn skip_jumps_to_the_next_tick() {
+        let start = Instant::now();
+        let now = start + PERIOD * 3 + Duration::from_millis(4);
+
+        let next = next_deadline(start, now, PERIOD, TickPolicy::Skip);
+        assert_eq!(next, Some(start + PERIOD * 4));
+
+        // A tick that lands exactly on `now` has already passed.
+        let now = start + PERIOD * 3;
+        let next = next_deadline(start, now, PERIOD, TickPolicy::Skip);
+        assert_eq!(next, Some(start + PERIOD * 4));
+    }
+
+    #[test]
+    fn skip_survives_many_missed_ticks() {
+        // More ticks than fit in a `u32` have been missed.
+        let start = Instant::now();
+        let period = Duration::from_nanos(1);
+        let now = start + Duration::from_secs(5);
+
+        let next = next_deadline(start, now, period, TickPolicy::Skip);
+        assert_eq!(next, Some(now + period));
+    }
+
+    #[test]
+    fn skip_with_zero_period() {
+        let start = Instant::now();
+        let now = start + PERIOD;
+
+        let next = next_deadline(start, now, Duration::ZERO, TickPolicy::Skip);
+        assert_eq!(next, Some(now));
+    }
+
+    #[test]
+    fn aligned_offset_wraps_around_the_period() {
+        let now = PERIOD * 10;
+
+        let wrapped = aligned_wait(
+            now,
+            PERIOD,
+            PERIOD * 2 + Duration::from_millis(5),
+            Duration::ZERO,
+        );
+        assert_eq!(wrapped, Some(Duration::from_millis(5)));
+        assert_eq!(
+            wrapped,
+            aligned_wait(now, PERIOD, Duration::from_millis(5), Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn aligned_boundary_at_now_has_passed() {
+        let now = PERIOD * 10;
+
+        let wait = aligned_wait(now, PERIOD, Duration::ZERO, Duration::ZERO);
+        assert_eq!(wait, Some(PERIOD));
+    }
+
+    #[test]
+    fn aligned_skips_boundaries_within_the_margin() {
+        let margin = PERIOD / 2;
+
+        // The next boundary is further away than the margin.
+        let now = PERIOD * 10 + Duration::from_millis(3);
+        let wait = aligned_wait(now, PERIOD, Duration::ZERO, margin);
+        assert_eq!(wait, Some(Duration::from_millis(7)));
+
+        // The next boundary is too close, so the one after it is used.
+        let now = PERIOD * 10 + Duration::from_millis(6);
+        let wait = aligned_wait(now, PERIOD, Duration::ZERO, margin);
+        assert_eq!(wait, Some(Duration::from_millis(14)));
+    }
+}
@@ -0,0 +1,107 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+//! Running futures alongside a `tokio` runtime.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::task::LocalSet;
+
+pin_project_lite::pin_project! {
+    /// A future that is polled inside of a `tokio` runtime context.
+    struct EnterRuntime<F> {
+        handle: Handle,
+
+        #[pin]
+        future: F,
+    }
+}
+
+impl<F: Future> Future for EnterRuntime<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let _guard = this.handle.enter();
+        this.future.poll(cx)
+    }
+}
+
+/// Run a future inside of a `tokio` runtime context and a `LocalSet`.
+///
+/// This allows `tokio::spawn_local` to be used, and allows I/O and timer types from `tokio` to be
+/// used. The runtime's drivers must be run by another thread, so `handle` must come from a
+/// multi-threaded runtime.
+///
+/// # Panics
+///
+/// Panics if `handle` belongs to a current-thread runtime. Nothing would drive its I/O and time
+/// drivers while the event loop is running, so any `tokio` I/O or timer would hang forever.
+pub(crate) fn drive<F: Future>(handle: Handle, future: F) -> impl Future<Output = F::Output> {
+    assert!(
+        handle.runtime_flavor() == RuntimeFlavor::MultiThread,
+        "block_on_tokio requires a handle to a multi-threaded tokio runtime"
+    );
+
+    EnterRuntime {
+        handle,
+        future: async move {
+            let local = LocalSet::new();
+            local.run_until(future).await
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::drive;
+
+    use futures_lite::future;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn sleep_completes_on_a_multi_thread_runtime() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_time()
+            .build()
+            .unwrap();
+
+        let start = Instant::now();
+        let value = future::block_on(drive(runtime.handle().clone(), async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            tokio::task::spawn_local(async { 7 }).await.unwrap()
+        }));
+
+        assert_eq!(value, 7);
+        assert!(start.elapsed() >= Duration::from_millis(10));
+    }
+
+    #[test]
+    #[should_panic(expected = "multi-threaded")]
+    fn current_thread_runtime_is_rejected() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+
+        drop(drive(runtime.handle().clone(), async {}));
+    }
+}
@@ -0,0 +1,189 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Detect events and operations that hold the event loop for too long.
+//!
+//! Every event handler and every window operation runs on the same event loop thread. If one of
+//! them takes too long, the whole application freezes. When enabled through
+//! [`EventLoopBuilder::with_watchdog`], the reactor times each event dispatch and each operation
+//! and reports the ones that take longer than the threshold.
+//!
+//! By default, slow dispatches are reported as warnings through the `log` and `tracing` features,
+//! if they are enabled. A callback can be registered with
+//! [`EventLoopBuilder::with_watchdog_callback`] to report them somewhere else.
+//!
+//! [`EventLoopBuilder::with_watchdog`]: crate::event_loop::EventLoopBuilder::with_watchdog
+//! [`EventLoopBuilder::with_watchdog_callback`]: crate::event_loop::EventLoopBuilder::with_watchdog_callback
+
+use std::fmt;
+use std::time::Duration;
+
+use instant::Instant;
+use winit::event::{Event, WindowEvent};
+
+/// A callback that is invoked for every slow dispatch.
+pub(crate) type WatchdogCallback = Box<dyn FnMut(&SlowDispatch) + Send + 'static>;
+
+/// What held the event loop for too long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Culprit {
+    /// Dispatching an event to its listeners, named by its variant.
+    Event(&'static str),
+
+    /// Running a window or event loop operation, named by its variant.
+    Op(&'static str),
+}
+
+impl fmt::Display for Culprit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Culprit::Event(name) => write!(f, "event {}", name),
+            Culprit::Op(name) => write!(f, "operation {}", name),
+        }
+    }
+}
+
+/// A report of an event or operation that held the event loop for longer than the threshold.
+#[derive(Debug, Clone)]
+pub struct SlowDispatch {
+    /// What held the event loop.
+    culprit: Culprit,
+
+    /// How long it held the event loop for.
+    elapsed: Duration,
+
+    /// The threshold that was exceeded.
+    threshold: Duration,
+}
+
+impl SlowDispatch {
+    /// What held the event loop.
+    #[inline]
+    pub fn culprit(&self) -> Culprit {
+        self.culprit
+    }
+
+    /// How long the event loop was held for.
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The threshold that was exceeded.
+    #[inline]
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+}
+
+impl fmt::Display for SlowDispatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} held the event loop for {:?} (threshold is {:?})",
+            self.culprit, self.elapsed, self.threshold
+        )
+    }
+}
+
+/// The watchdog state kept by the reactor.
+pub(crate) struct Watchdog {
+    /// The longest an event or operation may hold the loop before it is reported.
+    threshold: Duration,
+
+    /// The callback to report slow dispatches to, or `None` to log them.
+    callback: Option<WatchdogCallback>,
+}
+
+impl Watchdog {
+    /// Create a new watchdog.
+    pub(crate) fn new(threshold: Duration, callback: Option<WatchdogCallback>) -> Self {
+        Self {
+            threshold,
+            callback,
+        }
+    }
+
+    /// Report a dispatch that started at `start`, if it took too long.
+    pub(crate) fn check(&mut self, culprit: Culprit, start: Instant) {
+        let elapsed = start.elapsed();
+        if elapsed <= self.threshold {
+            return;
+        }
+
+        let report = SlowDispatch {
+            culprit,
+            elapsed,
+            threshold: self.threshold,
+        };
+
+        if let Some(callback) = &mut self.callback {
+            callback(&report);
+        } else {
+            #[cfg(feature = "log")]
+            log::warn!("{}", report);
+
+            #[cfg(feature = "tracing")]
+            tracing::warn!("{}", report);
+        }
+    }
+}
+
+/// Get the name of an event, for reporting.
+pub(crate) fn event_name<T>(event: &Event<'_, T>) -> &'static str {
+    match event {
+        Event::NewEvents(_) => "NewEvents",
+        Event::WindowEvent { event, .. } => match event {
+            WindowEvent::Resized(_) => "WindowEvent::Resized",
+            WindowEvent::Moved(_) => "WindowEvent::Moved",
+            WindowEvent::CloseRequested => "WindowEvent::CloseRequested",
+            WindowEvent::Destroyed => "WindowEvent::Destroyed",
+            WindowEvent::DroppedFile(_) => "WindowEvent::DroppedFile",
+            WindowEvent::HoveredFile(_) => "WindowEvent::HoveredFile",
+            WindowEvent::HoveredFileCancelled => "WindowEvent::HoveredFileCancelled",
+            WindowEvent::ReceivedCharacter(_) => "WindowEvent::ReceivedCharacter",
+            WindowEvent::Focused(_) => "WindowEvent::Focused",
+            WindowEvent::KeyboardInput { .. } => "WindowEvent::KeyboardInput",
+            WindowEvent::ModifiersChanged(_) => "WindowEvent::ModifiersChanged",
+            WindowEvent::Ime(_) => "WindowEvent::Ime",
+            WindowEvent::CursorMoved { .. } => "WindowEvent::CursorMoved",
+            WindowEvent::CursorEntered { .. } => "WindowEvent::CursorEntered",
+            WindowEvent::CursorLeft { .. } => "WindowEvent::CursorLeft",
+            WindowEvent::MouseWheel { .. } => "WindowEvent::MouseWheel",
+            WindowEvent::MouseInput { .. } => "WindowEvent::MouseInput",
+            WindowEvent::TouchpadMagnify { .. } => "WindowEvent::TouchpadMagnify",
+            WindowEvent::SmartMagnify { .. } => "WindowEvent::SmartMagnify",
+            WindowEvent::TouchpadRotate { .. } => "WindowEvent::TouchpadRotate",
+            WindowEvent::TouchpadPressure { .. } => "WindowEvent::TouchpadPressure",
+            WindowEvent::AxisMotion { .. } => "WindowEvent::AxisMotion",
+            WindowEvent::Touch(_) => "WindowEvent::Touch",
+            WindowEvent::ScaleFactorChanged { .. } => "WindowEvent::ScaleFactorChanged",
+            WindowEvent::ThemeChanged(_) => "WindowEvent::ThemeChanged",
+            WindowEvent::Occluded(_) => "WindowEvent::Occluded",
+        },
+        Event::DeviceEvent { .. } => "DeviceEvent",
+        Event::UserEvent(_) => "UserEvent",
+        Event::Suspended => "Suspended",
+        Event::Resumed => "Resumed",
+        Event::MainEventsCleared => "MainEventsCleared",
+        Event::RedrawRequested(_) => "RedrawRequested",
+        Event::RedrawEventsCleared => "RedrawEventsCleared",
+        Event::LoopDestroyed => "LoopDestroyed",
+    }
+}
@@ -27,11 +27,66 @@ use crate::handler::Handler;
 use crate::oneoff::oneoff;
 use crate::reactor::{EventLoopOp, Reactor};
 use crate::sync::{ThreadSafety, __private::Rc};
-
+use crate::DefaultThreadSafety;
+
+mod frames;
+#[cfg(all(
+    feature = "glutin",
+    any(
+        windows,
+        macos_platform,
+        android_platform,
+        x11_platform,
+        wayland_platform
+    )
+))]
+mod gl;
+#[cfg(feature = "wgpu")]
+mod gpu;
+#[cfg(feature = "keyboard-types")]
+pub(crate) mod keyboard;
+mod lifecycle;
 pub(crate) mod registration;
+mod router;
+#[cfg(feature = "softbuffer")]
+mod software;
+#[cfg(feature = "egui")]
+mod ui;
+#[cfg(feature = "ash")]
+mod vulkan;
 
 use registration::Registration;
 
+#[cfg(feature = "ash")]
+pub(crate) use vulkan::create_surface as create_vulkan_surface;
+
+pub use frames::{AnimationFrames, Frame, FramePacer, GameLoop, GameLoopEvent};
+#[cfg(all(
+    feature = "glutin",
+    any(
+        windows,
+        macos_platform,
+        android_platform,
+        x11_platform,
+        wayland_platform
+    )
+))]
+pub use gl::GlSurfaceManager;
+#[cfg(feature = "wgpu")]
+pub use gpu::{SurfaceManager, WgpuSurface, WgpuSurfaceError};
+pub use lifecycle::{RenderSurface, SurfaceLifecycle};
+pub use router::WindowRouter;
+#[cfg(feature = "softbuffer")]
+pub use software::SoftbufferSurface;
+#[cfg(feature = "egui")]
+pub use ui::EguiAdapter;
+#[cfg(feature = "ash")]
+pub use vulkan::VulkanSurface;
+
+#[cfg(any(feature = "executor", feature = "thread_safe"))]
+use std::future::Future;
+use std::ops::Deref;
+
 use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::error::{ExternalError, NotSupportedError};
 use winit::event::DeviceId;
@@ -44,28 +99,68 @@ pub use winit::window::{
 };
 
 /// Attributes to use when creating a window.
+///
+/// With the `serde` feature, the attributes can be loaded from configuration files. Missing fields
+/// take their default values. The fullscreen mode and the window icon refer to resources that only
+/// exist at runtime, so they are skipped.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct WindowAttributes {
     pub inner_size: Option<Size>,
     pub min_inner_size: Option<Size>,
     pub max_inner_size: Option<Size>,
     pub position: Option<Position>,
     pub resizable: bool,
+    #[cfg_attr(feature = "serde", serde(with = "window_buttons"))]
     pub enabled_buttons: WindowButtons,
     pub title: String,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub fullscreen: Option<Fullscreen>,
     pub maximized: bool,
     pub visible: bool,
     pub transparent: bool,
     pub decorations: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub window_icon: Option<Icon>,
     pub preferred_theme: Option<Theme>,
     pub resize_increments: Option<Size>,
     pub content_protected: bool,
+    #[cfg_attr(feature = "serde", serde(with = "WindowLevelDef"))]
     pub window_level: WindowLevel,
     pub active: bool,
 }
 
+/// Serializes `WindowButtons` as its bits.
+#[cfg(feature = "serde")]
+mod window_buttons {
+    use super::WindowButtons;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        buttons: &WindowButtons,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        buttons.bits().serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<WindowButtons, D::Error> {
+        u32::deserialize(deserializer).map(WindowButtons::from_bits_truncate)
+    }
+}
+
+/// Mirror of `WindowLevel`, which `winit` doesn't implement `serde` traits for.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "WindowLevel")]
+enum WindowLevelDef {
+    AlwaysOnBottom,
+    Normal,
+    AlwaysOnTop,
+}
+
 impl Default for WindowAttributes {
     #[inline]
     fn default() -> WindowAttributes {
@@ -362,28 +457,52 @@ impl WindowBuilder {
     }
 
     /// Build a new window.
+    ///
+    /// If the application has not been resumed yet, the window is not created until the first
+    /// `Resumed` event is received.
     pub async fn build<TS: ThreadSafety>(self) -> Result<Window<TS>, OsError> {
         let (tx, rx) = oneoff();
         let reactor = TS::get_reactor();
+
+        // Windows can't be created before the application is resumed on some platforms.
+        reactor.wait_for_first_resume().await;
+
         reactor
-            .push_event_loop_op(EventLoopOp::BuildWindow {
+            .push_event_loop_op_or_wait(EventLoopOp::BuildWindow {
                 builder: Box::new(self),
                 waker: tx,
             })
             .await;
 
-        let inner = rx.recv().await?;
+        let inner = TS::Rc::new(WindowInner {
+            window: TS::Rc::new(rx.recv().await?),
+            reactor: reactor.clone(),
+        });
 
         // Insert the window into the global window map.
-        let registration = reactor.insert_window(inner.id());
+        let registration = reactor.insert_window(&inner);
 
         Ok(Window {
-            inner: TS::Rc::new(inner),
+            inner,
             registration,
             reactor,
         })
     }
 
+    /// Build the window on the event loop thread.
+    pub(crate) fn build_window<T: 'static>(
+        self,
+        target: &winit::event_loop::EventLoopWindowTarget<T>,
+    ) -> Result<winit::window::Window, OsError> {
+        cfg_if::cfg_if! {
+            if #[cfg(macos_platform)] {
+                crate::platform::macos::build_window(self, target)
+            } else {
+                self.into_winit_builder().build(target)
+            }
+        }
+    }
+
     pub(crate) fn into_winit_builder(self) -> winit::window::WindowBuilder {
         let mut builder = winit::window::WindowBuilder::new();
 
@@ -435,10 +554,9 @@ impl WindowBuilder {
 }
 
 /// A window.
-#[derive(Clone)]
-pub struct Window<TS: ThreadSafety> {
+pub struct Window<TS: ThreadSafety = DefaultThreadSafety> {
     /// Underlying window.
-    inner: TS::Rc<winit::window::Window>,
+    inner: TS::Rc<WindowInner<TS>>,
 
     /// Registration for the window.
     registration: TS::Rc<Registration<TS>>,
@@ -447,9 +565,39 @@ pub struct Window<TS: ThreadSafety> {
     reactor: TS::Rc<Reactor<TS>>,
 }
 
-impl<TS: ThreadSafety> Drop for Window<TS> {
+impl<TS: ThreadSafety> Clone for Window<TS> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            registration: self.registration.clone(),
+            reactor: self.reactor.clone(),
+        }
+    }
+}
+
+/// The state shared between every handle to a window.
+///
+/// This is dropped exactly once, after the last handle to the window is dropped, which removes the
+/// window from the window list.
+pub(crate) struct WindowInner<TS: ThreadSafety> {
+    /// Underlying window.
+    window: TS::Rc<winit::window::Window>,
+
+    /// Underlying window reactor.
+    reactor: TS::Rc<Reactor<TS>>,
+}
+
+impl<TS: ThreadSafety> Deref for WindowInner<TS> {
+    type Target = winit::window::Window;
+
+    fn deref(&self) -> &Self::Target {
+        &self.window
+    }
+}
+
+impl<TS: ThreadSafety> Drop for WindowInner<TS> {
     fn drop(&mut self) {
-        self.reactor.remove_window(self.inner.id());
+        self.reactor.release_window(self.window.id());
     }
 }
 
@@ -466,7 +614,23 @@ unsafe impl<TS: ThreadSafety> raw_window_handle::HasRawWindowHandle for Window<T
 }
 
 impl<TS: ThreadSafety> Window<TS> {
+    /// Create a handle to a window that is already in the window list.
+    pub(crate) fn from_parts(
+        inner: TS::Rc<WindowInner<TS>>,
+        registration: TS::Rc<Registration<TS>>,
+        reactor: TS::Rc<Reactor<TS>>,
+    ) -> Self {
+        Self {
+            inner,
+            registration,
+            reactor,
+        }
+    }
+
     /// Create a new window.
+    ///
+    /// If the application has not been resumed yet, the window is not created until the first
+    /// `Resumed` event is received.
     pub async fn new() -> Result<Window<TS>, OsError> {
         WindowBuilder::new().build().await
     }
@@ -476,6 +640,56 @@ impl<TS: ThreadSafety> Window<TS> {
         &self.inner
     }
 
+    /// Get the event handlers for this window.
+    #[cfg(ios_platform)]
+    pub(crate) fn registration(&self) -> &Registration<TS> {
+        &self.registration
+    }
+
+    /// Get the safe area insets of this window in the event loop.
+    #[cfg(ios_platform)]
+    pub(crate) async fn uikit_safe_area_insets(&self) -> crate::platform::ios::SafeAreaInsets {
+        let (tx, rx) = oneoff();
+        self.reactor
+            .push_event_loop_op_or_wait(EventLoopOp::SafeAreaInsets {
+                window: self.inner.window.clone(),
+                waker: tx,
+            })
+            .await;
+
+        rx.recv().await
+    }
+
+    /// Run a Windows-specific operation on this window in the event loop.
+    #[cfg(windows)]
+    pub(crate) async fn win32_op(&self, op: crate::platform::windows::WindowOp) {
+        let (tx, rx) = oneoff();
+        self.reactor
+            .push_event_loop_op_or_wait(EventLoopOp::Win32 {
+                window: self.inner.window.clone(),
+                op,
+                waker: tx,
+            })
+            .await;
+
+        rx.recv().await
+    }
+
+    /// Run a macOS-specific operation on this window in the event loop.
+    #[cfg(macos_platform)]
+    pub(crate) async fn cocoa_op(&self, op: crate::platform::macos::WindowOp) -> bool {
+        let (tx, rx) = oneoff();
+        self.reactor
+            .push_event_loop_op_or_wait(EventLoopOp::Cocoa {
+                window: self.inner.window.clone(),
+                op,
+                waker: tx,
+            })
+            .await;
+
+        rx.recv().await
+    }
+
     /// Get the ID of the window.
     pub fn id(&self) -> winit::window::WindowId {
         self.inner.id()
@@ -490,6 +704,48 @@ impl<TS: ThreadSafety> Window<TS> {
     pub fn request_redraw(&self) {
         self.inner.request_redraw();
     }
+
+    /// Get a stream that yields once per frame, for driving animations.
+    ///
+    /// The stream requests a redraw, waits for the `RedrawRequested` event, and then yields the
+    /// [`Frame`] along with the time since the previous frame. The next redraw is requested once
+    /// the stream is polled again. Note that the `RedrawRequested` event has already been handled
+    /// by the time the frame is yielded; to draw during the event itself, use
+    /// [`Waiter::hold`] on [`redraw_requested`] instead.
+    ///
+    /// The stream runs as fast as redraws are delivered. On Wayland, the backend does not wait for
+    /// `wl_surface` frame callbacks before delivering them, so the stream is not throttled by the
+    /// compositor and keeps running while the window is hidden. Use a [`FramePacer`] to limit the
+    /// frame rate, or a graphics API with vsync to block on presentation.
+    ///
+    /// [`Waiter::hold`]: crate::Waiter::hold
+    /// [`redraw_requested`]: Self::redraw_requested
+    pub fn animation_frames(&self) -> AnimationFrames<'_, TS> {
+        AnimationFrames::new(self)
+    }
+
+    /// Get a stream of events for a fixed-timestep game loop.
+    ///
+    /// For every frame of the window, the stream emits enough [`GameLoopEvent::Update`]s to
+    /// simulate the time since the last frame in steps of `1 / update_hz` seconds, followed by
+    /// a [`GameLoopEvent::Render`]. Time spent while the application is in the background is not
+    /// simulated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `update_hz` is not a positive, finite number.
+    pub fn game_loop(&self, update_hz: f64) -> GameLoop<'_, TS> {
+        GameLoop::new(self, update_hz)
+    }
+
+    /// Get a `softbuffer` surface for drawing pixels to this window.
+    ///
+    /// The surface is created lazily, follows the size of the window, and is recreated after the
+    /// application has been suspended. On macOS, it should only be used on the event loop thread.
+    #[cfg(feature = "softbuffer")]
+    pub fn softbuffer(&self) -> SoftbufferSurface<TS> {
+        SoftbufferSurface::new(self.clone())
+    }
 }
 
 impl<TS: ThreadSafety> Window<TS> {
@@ -497,8 +753,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn inner_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::InnerPosition {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::InnerPosition {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -510,8 +766,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn outer_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::OuterPosition {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::OuterPosition {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -523,8 +779,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_outer_position(&self, position: impl Into<Position>) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetOuterPosition {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetOuterPosition {
+                window: self.inner.window.clone(),
                 position: position.into(),
                 waker: tx,
             })
@@ -537,8 +793,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn inner_size(&self) -> PhysicalSize<u32> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::InnerSize {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::InnerSize {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -550,8 +806,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn outer_size(&self) -> PhysicalSize<u32> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::OuterSize {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::OuterSize {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -563,8 +819,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_inner_size(&self, size: impl Into<Size>) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetInnerSize {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetInnerSize {
+                window: self.inner.window.clone(),
                 size: size.into(),
                 waker: tx,
             })
@@ -577,8 +833,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_min_inner_size(&self, size: impl Into<Option<Size>>) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetMinInnerSize {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetMinInnerSize {
+                window: self.inner.window.clone(),
                 size: size.into(),
                 waker: tx,
             })
@@ -591,8 +847,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_max_inner_size(&self, size: impl Into<Option<Size>>) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetMaxInnerSize {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetMaxInnerSize {
+                window: self.inner.window.clone(),
                 size: size.into(),
                 waker: tx,
             })
@@ -605,8 +861,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn resize_increments(&self) -> Option<PhysicalSize<u32>> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::ResizeIncrements {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::ResizeIncrements {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -618,8 +874,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_resize_increments(&self, size: impl Into<Option<Size>>) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetResizeIncrements {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetResizeIncrements {
+                window: self.inner.window.clone(),
                 size: size.into(),
                 waker: tx,
             })
@@ -632,8 +888,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_title(&self, title: impl Into<String>) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetTitle {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetTitle {
+                window: self.inner.window.clone(),
                 title: title.into(),
                 waker: tx,
             })
@@ -646,8 +902,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_visible(&self, visible: bool) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetVisible {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetVisible {
+                window: self.inner.window.clone(),
                 visible,
                 waker: tx,
             })
@@ -660,8 +916,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn is_visible(&self) -> Option<bool> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::Visible {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::Visible {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -673,8 +929,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_transparent(&self, transparent: bool) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetTransparent {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetTransparent {
+                window: self.inner.window.clone(),
                 transparent,
                 waker: tx,
             })
@@ -687,8 +943,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_resizable(&self, resizable: bool) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetResizable {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetResizable {
+                window: self.inner.window.clone(),
                 resizable,
                 waker: tx,
             })
@@ -701,8 +957,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn is_resizable(&self) -> bool {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::Resizable {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::Resizable {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -714,8 +970,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_minimized(&self, minimized: bool) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetMinimized {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetMinimized {
+                window: self.inner.window.clone(),
                 minimized,
                 waker: tx,
             })
@@ -728,8 +984,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn is_minimized(&self) -> Option<bool> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::Minimized {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::Minimized {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -741,8 +997,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_maximized(&self, maximized: bool) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetMaximized {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetMaximized {
+                window: self.inner.window.clone(),
                 maximized,
                 waker: tx,
             })
@@ -755,8 +1011,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn is_maximized(&self) -> bool {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::Maximized {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::Maximized {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -768,8 +1024,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetFullscreen {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetFullscreen {
+                window: self.inner.window.clone(),
                 fullscreen,
                 waker: tx,
             })
@@ -782,8 +1038,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn fullscreen(&self) -> Option<Fullscreen> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::Fullscreen {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::Fullscreen {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -795,8 +1051,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_decorations(&self, decorations: bool) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetDecorated {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetDecorated {
+                window: self.inner.window.clone(),
                 decorated: decorations,
                 waker: tx,
             })
@@ -809,8 +1065,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn is_decorated(&self) -> bool {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::Decorated {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::Decorated {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -822,8 +1078,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_window_level(&self, level: WindowLevel) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetWindowLevel {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetWindowLevel {
+                window: self.inner.window.clone(),
                 level,
                 waker: tx,
             })
@@ -836,8 +1092,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_window_icon(&self, icon: Option<Icon>) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetWindowIcon {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetWindowIcon {
+                window: self.inner.window.clone(),
                 icon,
                 waker: tx,
             })
@@ -850,8 +1106,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_ime_position(&self, posn: impl Into<Position>) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetImePosition {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetImePosition {
+                window: self.inner.window.clone(),
                 position: posn.into(),
                 waker: tx,
             })
@@ -864,8 +1120,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_ime_allowed(&self, allowed: bool) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetImeAllowed {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetImeAllowed {
+                window: self.inner.window.clone(),
                 allowed,
                 waker: tx,
             })
@@ -878,8 +1134,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_ime_purpose(&self, purpose: ImePurpose) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetImePurpose {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetImePurpose {
+                window: self.inner.window.clone(),
                 purpose,
                 waker: tx,
             })
@@ -892,8 +1148,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn focus_window(&self) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::FocusWindow {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::FocusWindow {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -905,8 +1161,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn is_focused(&self) -> bool {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::Focused {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::Focused {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -918,8 +1174,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::RequestUserAttention {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::RequestUserAttention {
+                window: self.inner.window.clone(),
                 request_type,
                 waker: tx,
             })
@@ -929,11 +1185,19 @@ impl<TS: ThreadSafety> Window<TS> {
     }
 
     /// Set the window's theme.
+    ///
+    /// `None` goes back to following the system theme.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** This switches the theme of the client-side decorations, overriding the
+    ///   `WINIT_WAYLAND_CSD_THEME` environment variable. It only has an effect if one of the
+    ///   `wayland-csd-adwaita` features is enabled.
     pub async fn set_theme(&self, theme: Option<Theme>) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetTheme {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetTheme {
+                window: self.inner.window.clone(),
                 theme,
                 waker: tx,
             })
@@ -946,8 +1210,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn theme(&self) -> Option<Theme> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::Theme {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::Theme {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -959,8 +1223,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_content_protected(&self, protected: bool) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetProtectedContent {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetProtectedContent {
+                window: self.inner.window.clone(),
                 protected,
                 waker: tx,
             })
@@ -973,8 +1237,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn title(&self) -> String {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::Title {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::Title {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -986,8 +1250,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_cursor_icon(&self, icon: CursorIcon) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetCursorIcon {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetCursorIcon {
+                window: self.inner.window.clone(),
                 icon,
                 waker: tx,
             })
@@ -1003,8 +1267,8 @@ impl<TS: ThreadSafety> Window<TS> {
     ) -> Result<(), ExternalError> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetCursorPosition {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetCursorPosition {
+                window: self.inner.window.clone(),
                 position: posn.into(),
                 waker: tx,
             })
@@ -1017,8 +1281,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), ExternalError> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetCursorGrab {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetCursorGrab {
+                window: self.inner.window.clone(),
                 mode,
                 waker: tx,
             })
@@ -1031,8 +1295,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_cursor_visible(&self, visible: bool) {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetCursorVisible {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetCursorVisible {
+                window: self.inner.window.clone(),
                 visible,
                 waker: tx,
             })
@@ -1045,8 +1309,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn drag_window(&self) -> Result<(), ExternalError> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::DragWindow {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::DragWindow {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -1061,8 +1325,8 @@ impl<TS: ThreadSafety> Window<TS> {
     ) -> Result<(), ExternalError> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::DragResizeWindow {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::DragResizeWindow {
+                window: self.inner.window.clone(),
                 direction,
                 waker: tx,
             })
@@ -1075,8 +1339,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn set_cursor_hittest(&self, hit_test: bool) -> Result<(), ExternalError> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::SetCursorHitTest {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::SetCursorHitTest {
+                window: self.inner.window.clone(),
                 hit_test,
                 waker: tx,
             })
@@ -1089,8 +1353,8 @@ impl<TS: ThreadSafety> Window<TS> {
     pub async fn current_monitor(&self) -> Option<MonitorHandle> {
         let (tx, rx) = oneoff();
         self.reactor
-            .push_event_loop_op(EventLoopOp::CurrentMonitor {
-                window: self.inner.clone(),
+            .push_event_loop_op_or_wait(EventLoopOp::CurrentMonitor {
+                window: self.inner.window.clone(),
                 waker: tx,
             })
             .await;
@@ -1099,6 +1363,191 @@ impl<TS: ThreadSafety> Window<TS> {
     }
 }
 
+#[cfg(feature = "executor")]
+impl Window<crate::ThreadUnsafe> {
+    /// Spawn a task that is scoped to this window.
+    ///
+    /// The task is cancelled once the window is destroyed or dropped. This is useful for tasks that
+    /// draw to or handle input for the window, which should not outlive it.
+    pub fn spawn_scoped<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        Registration::<crate::ThreadUnsafe>::spawn_scoped(&self.registration, future, |future| {
+            self.reactor.executor.spawn(future)
+        });
+    }
+}
+
+#[cfg(all(feature = "executor", feature = "thread_safe"))]
+impl Window<crate::ThreadSafe> {
+    /// Spawn a task that is scoped to this window.
+    ///
+    /// The task is cancelled once the window is destroyed or dropped. This is useful for tasks that
+    /// draw to or handle input for the window, which should not outlive it.
+    pub fn spawn_scoped<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        Registration::<crate::ThreadSafe>::spawn_scoped(&self.registration, future, |future| {
+            self.reactor.executor.spawn(future)
+        });
+    }
+}
+
+/// Blocking versions of the window getters.
+///
+/// These are useful for threads that aren't running an executor, like audio or FFI callbacks.
+/// Each of them sends the request to the event loop and blocks the current thread until it has
+/// been answered.
+#[cfg(feature = "thread_safe")]
+impl Window<crate::ThreadSafe> {
+    /// Block the current thread on a window operation.
+    fn block_on<T>(&self, future: impl Future<Output = T>) -> T {
+        // The event loop can't answer the request if we're blocking it.
+        if self.reactor.on_event_loop_thread() {
+            panic!("blocking window operations cannot be used on the event loop thread");
+        }
+
+        crate::sync::thread_safe::block_on(future)
+    }
+
+    /// Get the inner position of the window, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn inner_position_blocking(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
+        self.block_on(self.inner_position())
+    }
+
+    /// Get the outer position of the window, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn outer_position_blocking(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
+        self.block_on(self.outer_position())
+    }
+
+    /// Get the inner size of the window, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn inner_size_blocking(&self) -> PhysicalSize<u32> {
+        self.block_on(self.inner_size())
+    }
+
+    /// Get the outer size of the window, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn outer_size_blocking(&self) -> PhysicalSize<u32> {
+        self.block_on(self.outer_size())
+    }
+
+    /// Get the resize increments of the window, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn resize_increments_blocking(&self) -> Option<PhysicalSize<u32>> {
+        self.block_on(self.resize_increments())
+    }
+
+    /// Get whether the window is visible, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn is_visible_blocking(&self) -> Option<bool> {
+        self.block_on(self.is_visible())
+    }
+
+    /// Get whether the window is resizable, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn is_resizable_blocking(&self) -> bool {
+        self.block_on(self.is_resizable())
+    }
+
+    /// Get whether the window is minimized, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn is_minimized_blocking(&self) -> Option<bool> {
+        self.block_on(self.is_minimized())
+    }
+
+    /// Get whether the window is maximized, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn is_maximized_blocking(&self) -> bool {
+        self.block_on(self.is_maximized())
+    }
+
+    /// Get the fullscreen state of the window, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn fullscreen_blocking(&self) -> Option<Fullscreen> {
+        self.block_on(self.fullscreen())
+    }
+
+    /// Get whether the window is decorated, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn is_decorated_blocking(&self) -> bool {
+        self.block_on(self.is_decorated())
+    }
+
+    /// Get whether the window is focused, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn is_focused_blocking(&self) -> bool {
+        self.block_on(self.is_focused())
+    }
+
+    /// Get the theme of the window, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn theme_blocking(&self) -> Option<Theme> {
+        self.block_on(self.theme())
+    }
+
+    /// Get the title of the window, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn title_blocking(&self) -> String {
+        self.block_on(self.title())
+    }
+
+    /// Get the monitor that the window is on, blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from the event loop thread.
+    pub fn current_monitor_blocking(&self) -> Option<MonitorHandle> {
+        self.block_on(self.current_monitor())
+    }
+}
+
 /// Waiting for events.
 impl<TS: ThreadSafety> Window<TS> {
     /// Get the handler for the `RedrawRequested` event.
@@ -0,0 +1,338 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+//! Streams of animation frames.
+
+use super::Window;
+use crate::handler::Waiter;
+use crate::sync::ThreadSafety;
+use crate::timer::{TickPolicy, Timer};
+use crate::DefaultThreadSafety;
+
+use std::cmp;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_lite::Stream;
+use instant::Instant;
+
+/// An animation frame, emitted by [`AnimationFrames`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Frame {
+    /// The time at which the frame started.
+    pub timestamp: Instant,
+
+    /// The time since the previous frame started.
+    ///
+    /// This is zero for the first frame.
+    pub delta: Duration,
+}
+
+/// A stream that yields once per frame of a window.
+///
+/// This is returned by [`Window::animation_frames`].
+pub struct AnimationFrames<'a, TS: ThreadSafety = DefaultThreadSafety> {
+    /// The window to animate.
+    window: &'a Window<TS>,
+
+    /// Waits for the window to be redrawn.
+    redraw: Waiter<'a, (), TS>,
+
+    /// Whether we have requested a redraw that hasn't happened yet.
+    requested: bool,
+
+    /// The time at which the last frame started.
+    last_frame: Option<Instant>,
+}
+
+impl<TS: ThreadSafety> fmt::Debug for AnimationFrames<'_, TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnimationFrames")
+            .field("requested", &self.requested)
+            .field("last_frame", &self.last_frame)
+            .finish()
+    }
+}
+
+impl<TS: ThreadSafety> Unpin for AnimationFrames<'_, TS> {}
+
+impl<'a, TS: ThreadSafety> AnimationFrames<'a, TS> {
+    pub(super) fn new(window: &'a Window<TS>) -> Self {
+        Self {
+            window,
+            redraw: window.redraw_requested().wait(),
+            requested: false,
+            last_frame: None,
+        }
+    }
+}
+
+impl<TS: ThreadSafety> Stream for AnimationFrames<'_, TS> {
+    type Item = Frame;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        // Ask for the next frame.
+        if !this.requested {
+            this.window.request_redraw();
+            this.requested = true;
+        }
+
+        match Pin::new(&mut this.redraw).poll_next(cx) {
+            Poll::Ready(Some(())) => {
+                this.requested = false;
+
+                let timestamp = Instant::now();
+                let delta = this
+                    .last_frame
+                    .map_or(Duration::ZERO, |last| timestamp - last);
+                this.last_frame = Some(timestamp);
+
+                Poll::Ready(Some(Frame { timestamp, delta }))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// The frame time to use when the refresh rate of the monitor is unknown.
+const DEFAULT_FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+
+/// Paces a render loop to a fixed frame rate.
+///
+/// This is useful for applications that don't use vsync, which would otherwise render as fast as
+/// possible. Frames that are missed because rendering took too long are skipped.
+///
+/// ```no_run
+/// use async_winit::window::{FramePacer, Window};
+/// use async_winit::ThreadUnsafe;
+///
+/// # async fn render(window: &Window<ThreadUnsafe>) {
+/// let mut pacer = FramePacer::<ThreadUnsafe>::for_window(window).await;
+///
+/// loop {
+///     let frame = pacer.next_frame().await;
+///     println!("{:?} since the last frame", frame.delta);
+/// }
+/// # }
+/// ```
+pub struct FramePacer<TS: ThreadSafety = DefaultThreadSafety> {
+    /// The timer that fires once per frame.
+    timer: Timer<TS>,
+
+    /// The time between two frames.
+    frame_time: Duration,
+
+    /// The time at which the last frame started.
+    last_frame: Option<Instant>,
+}
+
+impl<TS: ThreadSafety> fmt::Debug for FramePacer<TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FramePacer")
+            .field("frame_time", &self.frame_time)
+            .field("last_frame", &self.last_frame)
+            .finish()
+    }
+}
+
+impl<TS: ThreadSafety> FramePacer<TS> {
+    /// Create a frame pacer with the given time between two frames.
+    pub fn new(frame_time: Duration) -> Self {
+        let mut timer = Timer::interval(frame_time);
+        timer.set_tick_policy(TickPolicy::Skip);
+
+        Self {
+            timer,
+            frame_time,
+            last_frame: None,
+        }
+    }
+
+    /// Create a frame pacer that matches the refresh rate of the monitor that the window is on.
+    ///
+    /// If the refresh rate can't be determined, this paces frames at 60 frames per second.
+    pub async fn for_window(window: &Window<TS>) -> Self {
+        let frame_time = window
+            .current_monitor()
+            .await
+            .and_then(|monitor| monitor.refresh_rate_millihertz())
+            .filter(|&millihertz| millihertz > 0)
+            .map_or(DEFAULT_FRAME_TIME, |millihertz| {
+                Duration::from_secs(1000) / millihertz
+            });
+
+        Self::new(frame_time)
+    }
+
+    /// Get the time between two frames.
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+
+    /// Set the time between two frames.
+    pub fn set_frame_time(&mut self, frame_time: Duration) {
+        self.frame_time = frame_time;
+        self.timer.set_interval(frame_time);
+    }
+
+    /// Align the following frames to the given time.
+    ///
+    /// This can be used to line frames up with an estimate of when the display refreshes, such as
+    /// the time at which the last frame was presented.
+    pub fn align_to(&mut self, instant: Instant) {
+        // Find the next frame boundary after now.
+        let now = Instant::now();
+        let mut start = instant;
+        if start <= now && !self.frame_time.is_zero() {
+            let behind = now.duration_since(start).as_nanos() / self.frame_time.as_nanos() + 1;
+            start = u32::try_from(behind)
+                .ok()
+                .and_then(|behind| self.frame_time.checked_mul(behind))
+                .and_then(|offset| start.checked_add(offset))
+                .unwrap_or(now);
+        }
+
+        self.timer.set_interval_at(start, self.frame_time);
+    }
+
+    /// Wait for the next frame to start.
+    pub async fn next_frame(&mut self) -> Frame {
+        (&mut self.timer).await;
+
+        let timestamp = Instant::now();
+        let delta = self
+            .last_frame
+            .map_or(Duration::ZERO, |last| timestamp - last);
+        self.last_frame = Some(timestamp);
+
+        Frame { timestamp, delta }
+    }
+}
+
+/// The largest frame delta that a [`GameLoop`] will simulate.
+///
+/// This avoids a long burst of updates after a hitch, like a window being dragged.
+const MAX_FRAME_DELTA: Duration = Duration::from_millis(250);
+
+/// An event emitted by a [`GameLoop`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameLoopEvent {
+    /// Advance the simulation by a fixed timestep.
+    Update(Duration),
+
+    /// Render the current state.
+    ///
+    /// The value is between `0.0` and `1.0`, and indicates how far along the current frame is
+    /// between the last update and the next one. This can be used to interpolate between states.
+    Render(f64),
+}
+
+/// A fixed-timestep game loop.
+///
+/// This is returned by [`Window::game_loop`].
+pub struct GameLoop<'a, TS: ThreadSafety = DefaultThreadSafety> {
+    /// The frames that drive the loop.
+    frames: AnimationFrames<'a, TS>,
+
+    /// The fixed timestep.
+    step: Duration,
+
+    /// The amount of time that hasn't been simulated yet.
+    accumulator: Duration,
+
+    /// The number of updates left to emit for the current frame.
+    pending_updates: u32,
+
+    /// The render event left to emit for the current frame.
+    pending_render: Option<f64>,
+}
+
+impl<TS: ThreadSafety> fmt::Debug for GameLoop<'_, TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GameLoop")
+            .field("step", &self.step)
+            .field("accumulator", &self.accumulator)
+            .finish()
+    }
+}
+
+impl<TS: ThreadSafety> Unpin for GameLoop<'_, TS> {}
+
+impl<'a, TS: ThreadSafety> GameLoop<'a, TS> {
+    pub(super) fn new(window: &'a Window<TS>, update_hz: f64) -> Self {
+        Self {
+            frames: AnimationFrames::new(window),
+            step: Duration::from_secs_f64(1.0 / update_hz),
+            accumulator: Duration::ZERO,
+            pending_updates: 0,
+            pending_render: None,
+        }
+    }
+}
+
+impl<TS: ThreadSafety> Stream for GameLoop<'_, TS> {
+    type Item = GameLoopEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            // Emit the events for the current frame.
+            if this.pending_updates > 0 {
+                this.pending_updates -= 1;
+                return Poll::Ready(Some(GameLoopEvent::Update(this.step)));
+            }
+
+            if let Some(alpha) = this.pending_render.take() {
+                return Poll::Ready(Some(GameLoopEvent::Render(alpha)));
+            }
+
+            // Wait for the next frame.
+            let frame = match Pin::new(&mut this.frames).poll_next(cx) {
+                Poll::Ready(Some(frame)) => frame,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            // Don't simulate the time that the application spent in the background.
+            let delta = if this.frames.window.reactor.in_background() {
+                Duration::ZERO
+            } else {
+                cmp::min(frame.delta, MAX_FRAME_DELTA)
+            };
+
+            // Figure out how many updates we need to run.
+            this.accumulator += delta;
+            while this.accumulator >= this.step && !this.step.is_zero() {
+                this.accumulator -= this.step;
+                this.pending_updates += 1;
+            }
+
+            let alpha = if this.step.is_zero() {
+                0.0
+            } else {
+                this.accumulator.as_secs_f64() / this.step.as_secs_f64()
+            };
+            this.pending_render = Some(alpha);
+        }
+    }
+}
@@ -0,0 +1,421 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Helpers for rendering to windows with OpenGL through `glutin`.
+
+use super::{RenderSurface, Window};
+use crate::sync::ThreadSafety;
+use crate::DefaultThreadSafety;
+
+use std::cell::RefCell;
+use std::ffi::{c_void, CStr};
+use std::fmt;
+use std::future::Future;
+use std::num::NonZeroU32;
+use std::pin::Pin;
+use std::ptr;
+
+use glutin::config::{Config, ConfigTemplateBuilder};
+use glutin::context::{ContextAttributesBuilder, NotCurrentContext, PossiblyCurrentContext};
+use glutin::display::{Display, DisplayApiPreference};
+use glutin::error::{Error, ErrorKind};
+use glutin::prelude::*;
+use glutin::surface::{Surface, SurfaceAttributesBuilder, SwapInterval, WindowSurface};
+use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle, RawWindowHandle};
+use winit::dpi::PhysicalSize;
+
+/// Picks the configuration to use out of the ones that match the template.
+type ConfigPicker = Box<dyn Fn(&mut dyn Iterator<Item = Config>) -> Option<Config>>;
+
+/// Manages the OpenGL context and surface of a window across resizes and suspensions.
+///
+/// The display, configuration, context and surface are created when the context is first made
+/// current. The surface is resized whenever the size of the window changes. When the application
+/// is suspended, the surface is dropped and the context is made not current, since the underlying
+/// window surface is destroyed on some platforms like Android. The surface is recreated once the
+/// context is made current after the application resumes.
+///
+/// OpenGL contexts are bound to the thread that they are current on, so this type can only be
+/// used on the event loop thread. Making the context current on any other thread fails.
+///
+/// ```no_run
+/// use async_winit::window::{GlSurfaceManager, Window};
+/// use async_winit::ThreadUnsafe;
+/// use futures_lite::future;
+///
+/// # async fn render(window: &Window<ThreadUnsafe>) {
+/// let manager = GlSurfaceManager::new(window);
+///
+/// let draw = async {
+///     loop {
+///         window.redraw_requested().await;
+///
+///         manager.make_current().await.unwrap();
+///         // Render with OpenGL...
+///         manager.swap_buffers().unwrap();
+///     }
+/// };
+///
+/// future::zip(draw, manager.drop_on_suspend()).await;
+/// # }
+/// ```
+pub struct GlSurfaceManager<TS: ThreadSafety = DefaultThreadSafety> {
+    /// The OpenGL state.
+    ///
+    /// This is declared before the window so that it is dropped first.
+    state: RefCell<GlState>,
+
+    /// The window to render to.
+    window: Window<TS>,
+
+    /// The template used to find configurations.
+    template: ConfigTemplateBuilder,
+
+    /// The attributes used to create the context.
+    context_attributes: ContextAttributesBuilder,
+
+    /// The swap interval to set on new surfaces.
+    swap_interval: Option<SwapInterval>,
+
+    /// Picks the configuration to use.
+    picker: ConfigPicker,
+}
+
+/// The mutable state of a [`GlSurfaceManager`].
+#[derive(Default)]
+struct GlState {
+    /// The display and the configuration picked from it, once they are created.
+    display: Option<(Display, Config)>,
+
+    /// The context, once it is created.
+    context: Option<Context>,
+
+    /// The surface, if it currently exists.
+    surface: Option<Surface<WindowSurface>>,
+
+    /// The size that the surface was last resized to.
+    size: PhysicalSize<u32>,
+
+    /// The number of suspensions when the surface was created.
+    suspensions: usize,
+}
+
+/// An OpenGL context in either of its states.
+enum Context {
+    /// The context may be current on this thread.
+    Current(PossiblyCurrentContext),
+
+    /// The context is not current on any thread.
+    NotCurrent(NotCurrentContext),
+}
+
+impl<TS: ThreadSafety> fmt::Debug for GlSurfaceManager<TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GlSurfaceManager")
+            .field("window", &self.window.id())
+            .field("template", &self.template)
+            .field("context_attributes", &self.context_attributes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<TS: ThreadSafety> GlSurfaceManager<TS> {
+    /// Create a new OpenGL surface manager for a window.
+    ///
+    /// By default, the configuration with the most samples that is compatible with the window is
+    /// picked, and the context is created with the default attributes.
+    pub fn new(window: &Window<TS>) -> Self {
+        Self {
+            state: RefCell::new(GlState::default()),
+            window: window.clone(),
+            template: ConfigTemplateBuilder::new(),
+            context_attributes: ContextAttributesBuilder::new(),
+            swap_interval: None,
+            picker: Box::new(|configs| configs.max_by_key(|config| config.num_samples())),
+        }
+    }
+
+    /// Set the template used to find configurations.
+    ///
+    /// The template is always made compatible with the window.
+    pub fn with_config_template(mut self, template: ConfigTemplateBuilder) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Set the function that picks the configuration to use.
+    ///
+    /// The function is called with every configuration that matches the template. If it returns
+    /// `None`, making the context current fails.
+    pub fn with_config_picker(
+        mut self,
+        picker: impl Fn(&mut dyn Iterator<Item = Config>) -> Option<Config> + 'static,
+    ) -> Self {
+        self.picker = Box::new(picker);
+        self
+    }
+
+    /// Set the attributes used to create the context.
+    pub fn with_context_attributes(mut self, attributes: ContextAttributesBuilder) -> Self {
+        self.context_attributes = attributes;
+        self
+    }
+
+    /// Set the swap interval of the surface.
+    ///
+    /// This is applied every time the surface is created.
+    pub fn with_swap_interval(mut self, interval: SwapInterval) -> Self {
+        self.swap_interval = Some(interval);
+        self
+    }
+
+    /// Get the window that this manager renders to.
+    pub fn window(&self) -> &Window<TS> {
+        &self.window
+    }
+
+    /// Get the display, if it has been created.
+    pub fn display(&self) -> Option<Display> {
+        self.state
+            .borrow()
+            .display
+            .as_ref()
+            .map(|(display, _)| display.clone())
+    }
+
+    /// Get the configuration that was picked, if the display has been created.
+    pub fn config(&self) -> Option<Config> {
+        self.state
+            .borrow()
+            .display
+            .as_ref()
+            .map(|(_, config)| config.clone())
+    }
+
+    /// Get the address of an OpenGL function.
+    ///
+    /// Returns a null pointer if the display hasn't been created yet.
+    pub fn get_proc_address(&self, name: &CStr) -> *const c_void {
+        match &self.state.borrow().display {
+            Some((display, _)) => display.get_proc_address(name),
+            None => ptr::null(),
+        }
+    }
+
+    /// Make the context current on the event loop thread.
+    ///
+    /// This creates the display, context and surface if they don't exist, and resizes the surface
+    /// if the size of the window has changed.
+    pub async fn make_current(&self) -> Result<(), Error> {
+        if !self.window.reactor.on_event_loop_thread() {
+            return Err(ErrorKind::NotSupported(
+                "OpenGL contexts can only be used on the event loop thread",
+            )
+            .into());
+        }
+
+        // Surfaces can't have a size of zero, which happens when windows are minimized.
+        let size = self.window.inner_size().await;
+        let size = PhysicalSize::new(size.width.max(1), size.height.max(1));
+        let suspensions = self.window.reactor.suspend_count();
+
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let window = self.window.inner.raw_window_handle();
+
+        // Drop the surface if the application has been suspended since it was created.
+        if state.suspensions != suspensions {
+            state.surface = None;
+        }
+        let created = state.surface.is_none();
+
+        let (display, config) = match &mut state.display {
+            Some(display) => display,
+            slot => slot.insert(self.create_display(window)?),
+        };
+
+        let surface = match &mut state.surface {
+            Some(surface) => surface,
+            slot => {
+                let attributes = SurfaceAttributesBuilder::<WindowSurface>::new().build(
+                    window,
+                    non_zero(size.width),
+                    non_zero(size.height),
+                );
+
+                // SAFETY: The surface is dropped before the window, since the manager holds it.
+                let surface = unsafe { display.create_window_surface(config, &attributes) }?;
+                state.size = size;
+                state.suspensions = suspensions;
+                slot.insert(surface)
+            }
+        };
+
+        let context = match state.context.take() {
+            Some(Context::Current(context)) => {
+                context.make_current(surface)?;
+                context
+            }
+            Some(Context::NotCurrent(context)) => context.make_current(surface)?,
+            None => {
+                let attributes = self.context_attributes.clone().build(Some(window));
+
+                // SAFETY: The context is only used with surfaces for this window.
+                let context = unsafe { display.create_context(config, &attributes) }?;
+                context.make_current(surface)?
+            }
+        };
+
+        if let Some(interval) = self.swap_interval.filter(|_| created) {
+            surface.set_swap_interval(&context, interval)?;
+        }
+
+        if size != state.size {
+            surface.resize(&context, non_zero(size.width), non_zero(size.height));
+            state.size = size;
+        }
+
+        state.context = Some(Context::Current(context));
+        Ok(())
+    }
+
+    /// Swap the buffers of the surface, presenting what was rendered.
+    ///
+    /// The context must have been made current with [`make_current`](Self::make_current).
+    pub fn swap_buffers(&self) -> Result<(), Error> {
+        let state = self.state.borrow();
+
+        match (&state.context, &state.surface) {
+            (Some(Context::Current(context)), Some(surface)) => surface.swap_buffers(context),
+            (_, None) => Err(ErrorKind::BadSurface.into()),
+            _ => Err(ErrorKind::BadContextState.into()),
+        }
+    }
+
+    /// Drop the surface whenever the application is suspended.
+    ///
+    /// The context is made not current, and is kept around to be used with the next surface. The
+    /// event loop waits for the surface to be dropped before it finishes handling the suspension.
+    /// This future never completes, so it should be run alongside the render loop.
+    pub async fn drop_on_suspend(&self) -> ! {
+        let mut waiter = self.window.reactor.evl_registration.suspended.wait();
+
+        loop {
+            let _guard = waiter.hold().await;
+            self.release();
+        }
+    }
+
+    /// Drop the surface and make the context not current.
+    fn release(&self) {
+        let mut state = self.state.borrow_mut();
+        state.context = match state.context.take() {
+            Some(Context::Current(context)) => {
+                context.make_not_current().ok().map(Context::NotCurrent)
+            }
+            context => context,
+        };
+        state.surface = None;
+    }
+
+    /// Create the display and pick a configuration for the window.
+    fn create_display(&self, window: RawWindowHandle) -> Result<(Display, Config), Error> {
+        // SAFETY: The display handle comes from a live window.
+        let display = unsafe {
+            Display::new(
+                self.window.inner.raw_display_handle(),
+                display_preference(window),
+            )
+        }?;
+
+        let template = self
+            .template
+            .clone()
+            .compatible_with_native_window(window)
+            .build();
+
+        let config = {
+            // SAFETY: The template is compatible with the window.
+            let mut configs = unsafe { display.find_configs(template) }?;
+            (self.picker)(&mut configs).ok_or(ErrorKind::NotFound)?
+        };
+
+        Ok((display, config))
+    }
+}
+
+impl<TS: ThreadSafety> RenderSurface<TS> for GlSurfaceManager<TS> {
+    type Frame<'a> = () where Self: 'a;
+    type Error = Error;
+
+    /// Make the context current, creating the surface.
+    ///
+    /// The surface is always created for the window that the manager was created with.
+    fn create<'a>(
+        &'a mut self,
+        _window: &'a Window<TS>,
+        _size: PhysicalSize<u32>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + 'a>> {
+        Box::pin(self.make_current())
+    }
+
+    /// Make the context current, resizing the surface to the size of the window.
+    fn resize(
+        &mut self,
+        _size: PhysicalSize<u32>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + '_>> {
+        Box::pin(self.make_current())
+    }
+
+    fn suspend(&mut self) {
+        self.release();
+    }
+
+    /// Swap the buffers of the surface.
+    fn present<'a>(
+        &'a mut self,
+        _frame: (),
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + 'a>> {
+        Box::pin(async move { self.swap_buffers() })
+    }
+}
+
+/// The display APIs to try on this platform.
+fn display_preference(window: RawWindowHandle) -> DisplayApiPreference {
+    #[cfg(not(windows))]
+    let _ = window;
+
+    cfg_if::cfg_if! {
+        if #[cfg(windows)] {
+            DisplayApiPreference::WglThenEgl(Some(window))
+        } else if #[cfg(macos_platform)] {
+            DisplayApiPreference::Cgl
+        } else if #[cfg(x11_platform)] {
+            DisplayApiPreference::EglThenGlx(Box::new(
+                winit::platform::x11::register_xlib_error_hook,
+            ))
+        } else {
+            DisplayApiPreference::Egl
+        }
+    }
+}
+
+/// Convert a size that has already been clamped to be at least one.
+fn non_zero(size: u32) -> NonZeroU32 {
+    NonZeroU32::new(size).expect("surface sizes are clamped to at least one")
+}
@@ -0,0 +1,375 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Helpers for rendering to windows with `wgpu`.
+
+use super::{RenderSurface, Window};
+use crate::oneoff::oneoff;
+use crate::reactor::EventLoopOp;
+use crate::sync::{ThreadSafety, __private::*};
+use crate::DefaultThreadSafety;
+
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use wgpu::{
+    CreateSurfaceError, Device, Instance, Surface, SurfaceConfiguration, SurfaceError,
+    SurfaceTexture,
+};
+use winit::dpi::PhysicalSize;
+
+/// A `wgpu` surface for a window.
+///
+/// This is created by [`Window::create_wgpu_surface`]. It keeps the window alive for as long as
+/// the surface exists, and dereferences to the underlying [`Surface`].
+pub struct WgpuSurface<TS: ThreadSafety = DefaultThreadSafety> {
+    /// The surface.
+    ///
+    /// This is declared before the window so that it is dropped first.
+    surface: Surface,
+
+    /// The window that the surface renders to.
+    window: Window<TS>,
+}
+
+impl<TS: ThreadSafety> fmt::Debug for WgpuSurface<TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WgpuSurface")
+            .field("window", &self.window.id())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<TS: ThreadSafety> Deref for WgpuSurface<TS> {
+    type Target = Surface;
+
+    fn deref(&self) -> &Self::Target {
+        &self.surface
+    }
+}
+
+impl<TS: ThreadSafety> WgpuSurface<TS> {
+    /// Get the window that this surface renders to.
+    pub fn window(&self) -> &Window<TS> {
+        &self.window
+    }
+
+    /// Get the underlying surface.
+    pub fn surface(&self) -> &Surface {
+        &self.surface
+    }
+}
+
+impl<TS: ThreadSafety> Window<TS> {
+    /// Create a `wgpu` surface for this window.
+    ///
+    /// Some platforms, like macOS, require the surface to be created on the main thread. If this
+    /// is called from another thread, the surface is created on the event loop thread instead,
+    /// which is why the instance is shared with the event loop.
+    pub async fn create_wgpu_surface(
+        &self,
+        instance: &Arc<Instance>,
+    ) -> Result<WgpuSurface<TS>, CreateSurfaceError> {
+        let surface = if self.reactor.on_event_loop_thread() {
+            // SAFETY: `WgpuSurface` keeps the window alive for as long as the surface exists.
+            unsafe { instance.create_surface(&*self.inner.window) }?
+        } else {
+            let (tx, rx) = oneoff();
+            self.reactor
+                .push_event_loop_op_or_wait(EventLoopOp::CreateWgpuSurface {
+                    window: self.inner.window.clone(),
+                    instance: instance.clone(),
+                    waker: tx,
+                })
+                .await;
+
+            rx.recv().await?
+        };
+
+        Ok(WgpuSurface {
+            surface,
+            window: self.clone(),
+        })
+    }
+}
+
+/// An error that occurred while getting the next frame from a [`SurfaceManager`].
+#[derive(Debug)]
+pub enum WgpuSurfaceError {
+    /// The surface could not be created.
+    Create(CreateSurfaceError),
+
+    /// The next texture could not be acquired.
+    Surface(SurfaceError),
+}
+
+impl fmt::Display for WgpuSurfaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WgpuSurfaceError::Create(err) => write!(f, "failed to create surface: {err}"),
+            WgpuSurfaceError::Surface(err) => write!(f, "failed to acquire texture: {err}"),
+        }
+    }
+}
+
+impl Error for WgpuSurfaceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WgpuSurfaceError::Create(err) => Some(err),
+            WgpuSurfaceError::Surface(err) => Some(err),
+        }
+    }
+}
+
+impl From<CreateSurfaceError> for WgpuSurfaceError {
+    fn from(err: CreateSurfaceError) -> Self {
+        WgpuSurfaceError::Create(err)
+    }
+}
+
+impl From<SurfaceError> for WgpuSurfaceError {
+    fn from(err: SurfaceError) -> Self {
+        WgpuSurfaceError::Surface(err)
+    }
+}
+
+/// Manages the `wgpu` surface of a window across resizes and suspensions.
+///
+/// The surface is created when the first frame is requested, and is reconfigured whenever the size
+/// of the window changes, including when its scale factor changes. When the application is
+/// suspended, the surface is dropped, since the underlying window surface is destroyed on some
+/// platforms like Android. It is recreated once a frame is requested after the application resumes.
+///
+/// ```no_run
+/// use async_winit::window::{SurfaceManager, Window};
+/// use async_winit::ThreadUnsafe;
+/// use futures_lite::future;
+///
+/// # async fn render(
+/// #     window: &Window<ThreadUnsafe>,
+/// #     instance: &std::sync::Arc<wgpu::Instance>,
+/// #     device: &wgpu::Device,
+/// #     config: wgpu::SurfaceConfiguration,
+/// # ) {
+/// let manager = SurfaceManager::new(window, config);
+///
+/// let draw = async {
+///     loop {
+///         window.redraw_requested().await;
+///
+///         let frame = manager.current_texture(instance, device).await.unwrap();
+///         // Render to the frame...
+///         frame.present();
+///     }
+/// };
+///
+/// future::zip(draw, manager.drop_on_suspend()).await;
+/// # }
+/// ```
+pub struct SurfaceManager<TS: ThreadSafety = DefaultThreadSafety> {
+    /// The window to render to.
+    window: Window<TS>,
+
+    /// The surface and its configuration.
+    state: TS::Mutex<ManagerState<TS>>,
+}
+
+/// The mutable state of a [`SurfaceManager`].
+struct ManagerState<TS: ThreadSafety> {
+    /// The surface, if it currently exists.
+    surface: Option<WgpuSurface<TS>>,
+
+    /// The configuration to use for the surface.
+    config: SurfaceConfiguration,
+
+    /// Whether the surface has been configured with `config`.
+    configured: bool,
+
+    /// The number of suspensions when the surface was created.
+    suspensions: usize,
+}
+
+impl<TS: ThreadSafety> fmt::Debug for SurfaceManager<TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SurfaceManager")
+            .field("window", &self.window.id())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<TS: ThreadSafety> SurfaceManager<TS> {
+    /// Create a new surface manager for a window.
+    ///
+    /// The width and height of `config` are replaced with the size of the window whenever the
+    /// surface is configured.
+    pub fn new(window: &Window<TS>, config: SurfaceConfiguration) -> Self {
+        Self {
+            window: window.clone(),
+            state: TS::Mutex::new(ManagerState {
+                surface: None,
+                config,
+                configured: false,
+                suspensions: window.reactor.suspend_count(),
+            }),
+        }
+    }
+
+    /// Get the window that this manager renders to.
+    pub fn window(&self) -> &Window<TS> {
+        &self.window
+    }
+
+    /// Get the configuration used for the surface.
+    pub fn config(&self) -> SurfaceConfiguration {
+        self.state.lock().unwrap().config.clone()
+    }
+
+    /// Set the configuration used for the surface.
+    ///
+    /// The surface is reconfigured when the next frame is requested.
+    pub fn set_config(&self, config: SurfaceConfiguration) {
+        let mut state = self.state.lock().unwrap();
+        state.config = config;
+        state.configured = false;
+    }
+
+    /// Get the next texture to render to.
+    ///
+    /// This creates the surface if it doesn't exist, and reconfigures it if the size of the window
+    /// has changed.
+    pub async fn current_texture(
+        &self,
+        instance: &Arc<Instance>,
+        device: &Device,
+    ) -> Result<SurfaceTexture, WgpuSurfaceError> {
+        // Surfaces can't have a size of zero, which happens when windows are minimized.
+        let size = self.window.inner_size().await;
+        let size = PhysicalSize::new(size.width.max(1), size.height.max(1));
+        let suspensions = self.window.reactor.suspend_count();
+
+        // Drop the surface if the application has been suspended since it was created.
+        let needs_surface = {
+            let mut state = self.state.lock().unwrap();
+            if state.suspensions != suspensions {
+                state.surface = None;
+            }
+            state.surface.is_none()
+        };
+
+        // Don't hold the lock while waiting for the event loop.
+        if needs_surface {
+            let surface = self.window.create_wgpu_surface(instance).await?;
+
+            let mut state = self.state.lock().unwrap();
+            state.surface = Some(surface);
+            state.configured = false;
+            state.suspensions = suspensions;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        let surface = match &state.surface {
+            Some(surface) => surface,
+            None => return Err(SurfaceError::Lost.into()),
+        };
+
+        if !state.configured || size != config_size(&state.config) {
+            configure(surface, device, &mut state.config, size);
+            state.configured = true;
+        }
+
+        match surface.get_current_texture() {
+            Ok(texture) => Ok(texture),
+            Err(SurfaceError::Outdated | SurfaceError::Lost) => {
+                // Try once more with a freshly configured surface.
+                configure(surface, device, &mut state.config, size);
+                Ok(surface.get_current_texture()?)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Drop the surface whenever the application is suspended.
+    ///
+    /// The event loop waits for the surface to be dropped before it finishes handling the
+    /// suspension. This future never completes, so it should be run alongside the render loop.
+    pub async fn drop_on_suspend(&self) -> ! {
+        let mut waiter = self.window.reactor.evl_registration.suspended.wait();
+
+        loop {
+            let _guard = waiter.hold().await;
+            self.state.lock().unwrap().surface = None;
+        }
+    }
+}
+
+impl<TS: ThreadSafety> RenderSurface<TS> for SurfaceManager<TS> {
+    type Frame<'a> = SurfaceTexture where Self: 'a;
+    type Error = WgpuSurfaceError;
+
+    /// Does nothing, since the surface is created when the first texture is requested.
+    fn create<'a>(
+        &'a mut self,
+        _window: &'a Window<TS>,
+        _size: PhysicalSize<u32>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + 'a>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Does nothing, since the surface is reconfigured when the next texture is requested.
+    fn resize(
+        &mut self,
+        _size: PhysicalSize<u32>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + '_>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn suspend(&mut self) {
+        self.state.lock().unwrap().surface = None;
+    }
+
+    /// Present a texture returned by [`SurfaceManager::current_texture`].
+    fn present<'a>(
+        &'a mut self,
+        frame: SurfaceTexture,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + 'a>> {
+        frame.present();
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Get the size that a surface configuration is set to.
+fn config_size(config: &SurfaceConfiguration) -> PhysicalSize<u32> {
+    PhysicalSize::new(config.width, config.height)
+}
+
+/// Configure a surface for the given window size.
+fn configure(
+    surface: &Surface,
+    device: &Device,
+    config: &mut SurfaceConfiguration,
+    size: PhysicalSize<u32>,
+) {
+    config.width = size.width;
+    config.height = size.height;
+    surface.configure(device, config);
+}
@@ -0,0 +1,187 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Conversions into the `keyboard-types` vocabulary used by GUI toolkits.
+
+use super::registration::KeyboardInput;
+use crate::event::{ElementState, ModifiersState, VirtualKeyCode};
+
+use keyboard_types::{Code, Key, KeyState, KeyboardEvent, Location, Modifiers};
+
+impl KeyboardInput {
+    /// Convert this event into a `keyboard-types` keyboard event.
+    ///
+    /// `modifiers` should be the last state received by the `modifiers_changed` handler. Keys that
+    /// produce characters are reported as the character on a US keyboard layout, since the layout
+    /// isn't known; use the `received_character` handler for text input.
+    pub fn to_keyboard_event(&self, modifiers: ModifiersState) -> KeyboardEvent {
+        let (key, code, location) = match self.input.virtual_keycode {
+            Some(keycode) => convert_keycode(keycode, modifiers.shift()),
+            None => (Key::Unidentified, Code::Unidentified, Location::Standard),
+        };
+
+        KeyboardEvent {
+            state: match self.input.state {
+                ElementState::Pressed => KeyState::Down,
+                ElementState::Released => KeyState::Up,
+            },
+            key,
+            code,
+            location,
+            modifiers: keyboard_modifiers(modifiers),
+            repeat: false,
+            is_composing: false,
+        }
+    }
+}
+
+/// Convert the modifiers held down into `keyboard-types` modifiers.
+pub fn keyboard_modifiers(modifiers: ModifiersState) -> Modifiers {
+    let mut result = Modifiers::empty();
+    result.set(Modifiers::SHIFT, modifiers.shift());
+    result.set(Modifiers::CONTROL, modifiers.ctrl());
+    result.set(Modifiers::ALT, modifiers.alt());
+    result.set(Modifiers::META, modifiers.logo());
+    result
+}
+
+/// Convert a virtual key code into a key, a physical code and a location.
+fn convert_keycode(keycode: VirtualKeyCode, shift: bool) -> (Key, Code, Location) {
+    use Location::{Left, Numpad, Right, Standard};
+    use VirtualKeyCode as K;
+
+    // Keys that produce a character, along with the character produced with shift held.
+    let character = |code, lower: &str, upper: &str| {
+        let text = if shift { upper } else { lower };
+        (Key::Character(text.into()), code, Standard)
+    };
+    let numpad = |code, text: &str| (Key::Character(text.into()), code, Numpad);
+
+    match keycode {
+        K::A => character(Code::KeyA, "a", "A"),
+        K::B => character(Code::KeyB, "b", "B"),
+        K::C => character(Code::KeyC, "c", "C"),
+        K::D => character(Code::KeyD, "d", "D"),
+        K::E => character(Code::KeyE, "e", "E"),
+        K::F => character(Code::KeyF, "f", "F"),
+        K::G => character(Code::KeyG, "g", "G"),
+        K::H => character(Code::KeyH, "h", "H"),
+        K::I => character(Code::KeyI, "i", "I"),
+        K::J => character(Code::KeyJ, "j", "J"),
+        K::K => character(Code::KeyK, "k", "K"),
+        K::L => character(Code::KeyL, "l", "L"),
+        K::M => character(Code::KeyM, "m", "M"),
+        K::N => character(Code::KeyN, "n", "N"),
+        K::O => character(Code::KeyO, "o", "O"),
+        K::P => character(Code::KeyP, "p", "P"),
+        K::Q => character(Code::KeyQ, "q", "Q"),
+        K::R => character(Code::KeyR, "r", "R"),
+        K::S => character(Code::KeyS, "s", "S"),
+        K::T => character(Code::KeyT, "t", "T"),
+        K::U => character(Code::KeyU, "u", "U"),
+        K::V => character(Code::KeyV, "v", "V"),
+        K::W => character(Code::KeyW, "w", "W"),
+        K::X => character(Code::KeyX, "x", "X"),
+        K::Y => character(Code::KeyY, "y", "Y"),
+        K::Z => character(Code::KeyZ, "z", "Z"),
+        K::Key1 => character(Code::Digit1, "1", "!"),
+        K::Key2 => character(Code::Digit2, "2", "@"),
+        K::Key3 => character(Code::Digit3, "3", "#"),
+        K::Key4 => character(Code::Digit4, "4", "$"),
+        K::Key5 => character(Code::Digit5, "5", "%"),
+        K::Key6 => character(Code::Digit6, "6", "^"),
+        K::Key7 => character(Code::Digit7, "7", "&"),
+        K::Key8 => character(Code::Digit8, "8", "*"),
+        K::Key9 => character(Code::Digit9, "9", "("),
+        K::Key0 => character(Code::Digit0, "0", ")"),
+        K::Space => character(Code::Space, " ", " "),
+        K::Minus => character(Code::Minus, "-", "_"),
+        K::Equals => character(Code::Equal, "=", "+"),
+        K::LBracket => character(Code::BracketLeft, "[", "{"),
+        K::RBracket => character(Code::BracketRight, "]", "}"),
+        K::Backslash => character(Code::Backslash, "\\", "|"),
+        K::Semicolon => character(Code::Semicolon, ";", ":"),
+        K::Apostrophe => character(Code::Quote, "'", "\""),
+        K::Grave => character(Code::Backquote, "`", "~"),
+        K::Comma => character(Code::Comma, ",", "<"),
+        K::Period => character(Code::Period, ".", ">"),
+        K::Slash => character(Code::Slash, "/", "?"),
+
+        K::Numpad0 => numpad(Code::Numpad0, "0"),
+        K::Numpad1 => numpad(Code::Numpad1, "1"),
+        K::Numpad2 => numpad(Code::Numpad2, "2"),
+        K::Numpad3 => numpad(Code::Numpad3, "3"),
+        K::Numpad4 => numpad(Code::Numpad4, "4"),
+        K::Numpad5 => numpad(Code::Numpad5, "5"),
+        K::Numpad6 => numpad(Code::Numpad6, "6"),
+        K::Numpad7 => numpad(Code::Numpad7, "7"),
+        K::Numpad8 => numpad(Code::Numpad8, "8"),
+        K::Numpad9 => numpad(Code::Numpad9, "9"),
+        K::NumpadAdd => numpad(Code::NumpadAdd, "+"),
+        K::NumpadSubtract => numpad(Code::NumpadSubtract, "-"),
+        K::NumpadMultiply => numpad(Code::NumpadMultiply, "*"),
+        K::NumpadDivide => numpad(Code::NumpadDivide, "/"),
+        K::NumpadDecimal => numpad(Code::NumpadDecimal, "."),
+        K::NumpadEnter => (Key::Enter, Code::NumpadEnter, Numpad),
+
+        K::Escape => (Key::Escape, Code::Escape, Standard),
+        K::Tab => (Key::Tab, Code::Tab, Standard),
+        K::Back => (Key::Backspace, Code::Backspace, Standard),
+        K::Return => (Key::Enter, Code::Enter, Standard),
+        K::Insert => (Key::Insert, Code::Insert, Standard),
+        K::Delete => (Key::Delete, Code::Delete, Standard),
+        K::Home => (Key::Home, Code::Home, Standard),
+        K::End => (Key::End, Code::End, Standard),
+        K::PageUp => (Key::PageUp, Code::PageUp, Standard),
+        K::PageDown => (Key::PageDown, Code::PageDown, Standard),
+        K::Left => (Key::ArrowLeft, Code::ArrowLeft, Standard),
+        K::Right => (Key::ArrowRight, Code::ArrowRight, Standard),
+        K::Up => (Key::ArrowUp, Code::ArrowUp, Standard),
+        K::Down => (Key::ArrowDown, Code::ArrowDown, Standard),
+        K::Capital => (Key::CapsLock, Code::CapsLock, Standard),
+        K::Numlock => (Key::NumLock, Code::NumLock, Numpad),
+        K::Scroll => (Key::ScrollLock, Code::ScrollLock, Standard),
+        K::Snapshot => (Key::PrintScreen, Code::PrintScreen, Standard),
+        K::Pause => (Key::Pause, Code::Pause, Standard),
+        K::Apps => (Key::ContextMenu, Code::ContextMenu, Standard),
+
+        K::LShift => (Key::Shift, Code::ShiftLeft, Left),
+        K::RShift => (Key::Shift, Code::ShiftRight, Right),
+        K::LControl => (Key::Control, Code::ControlLeft, Left),
+        K::RControl => (Key::Control, Code::ControlRight, Right),
+        K::LAlt => (Key::Alt, Code::AltLeft, Left),
+        K::RAlt => (Key::Alt, Code::AltRight, Right),
+        K::LWin => (Key::Meta, Code::MetaLeft, Left),
+        K::RWin => (Key::Meta, Code::MetaRight, Right),
+
+        K::F1 => (Key::F1, Code::F1, Standard),
+        K::F2 => (Key::F2, Code::F2, Standard),
+        K::F3 => (Key::F3, Code::F3, Standard),
+        K::F4 => (Key::F4, Code::F4, Standard),
+        K::F5 => (Key::F5, Code::F5, Standard),
+        K::F6 => (Key::F6, Code::F6, Standard),
+        K::F7 => (Key::F7, Code::F7, Standard),
+        K::F8 => (Key::F8, Code::F8, Standard),
+        K::F9 => (Key::F9, Code::F9, Standard),
+        K::F10 => (Key::F10, Code::F10, Standard),
+        K::F11 => (Key::F11, Code::F11, Standard),
+        K::F12 => (Key::F12, Code::F12, Standard),
+
+        _ => (Key::Unidentified, Code::Unidentified, Standard),
+    }
+}
@@ -0,0 +1,311 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Driving renderer surfaces through resizes, suspensions and occlusion.
+
+use super::Window;
+use crate::event::ScaleFactor;
+use crate::handler::Waiter;
+use crate::sync::ThreadSafety;
+use crate::DefaultThreadSafety;
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+use futures_lite::FutureExt;
+use winit::dpi::PhysicalSize;
+
+/// A renderer surface whose lifecycle can be driven by a [`SurfaceLifecycle`].
+///
+/// This is implemented by the surface helpers in this crate, and can be implemented by third-party
+/// renderers to get the same handling of resizes and suspensions.
+pub trait RenderSurface<TS: ThreadSafety = DefaultThreadSafety> {
+    /// The contents of a frame that are passed to [`present`](Self::present).
+    type Frame<'a>
+    where
+        Self: 'a;
+
+    /// The error that can occur while managing the surface.
+    type Error;
+
+    /// Create the surface for a window.
+    ///
+    /// This is called before the first frame is drawn, and before the first frame after the
+    /// application resumes from a suspension.
+    fn create<'a>(
+        &'a mut self,
+        window: &'a Window<TS>,
+        size: PhysicalSize<u32>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + 'a>>;
+
+    /// Resize the surface.
+    ///
+    /// This is called before the next frame is drawn whenever the size of the window changes,
+    /// including when its scale factor changes.
+    fn resize(
+        &mut self,
+        size: PhysicalSize<u32>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + '_>>;
+
+    /// Release the resources tied to the window surface.
+    ///
+    /// This is called when the application is suspended, before the suspension is finished.
+    fn suspend(&mut self);
+
+    /// Present a frame to the window.
+    fn present<'a>(
+        &'a mut self,
+        frame: Self::Frame<'a>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + 'a>>;
+}
+
+/// Drives a [`RenderSurface`] through the lifecycle of a window.
+///
+/// The driver listens for resizes, scale factor changes, suspensions and occlusion, and calls into
+/// the surface at the right times. [`next_frame`](Self::next_frame) waits until a frame should be
+/// drawn, which is whenever a redraw is requested while the window is visible.
+///
+/// ```no_run
+/// use async_winit::window::{RenderSurface, SurfaceLifecycle, Window};
+/// use async_winit::ThreadUnsafe;
+///
+/// # async fn draw<S>(window: &Window<ThreadUnsafe>, surface: S) -> Result<(), S::Error>
+/// # where
+/// #     S: for<'a> RenderSurface<ThreadUnsafe, Frame<'a> = ()> + 'static,
+/// # {
+/// let mut lifecycle = SurfaceLifecycle::new(window, surface);
+///
+/// loop {
+///     let size = lifecycle.next_frame().await?;
+///     // Draw a frame of the given size...
+///     lifecycle.present(()).await?;
+/// }
+/// # }
+/// ```
+pub struct SurfaceLifecycle<'a, S, TS: ThreadSafety = DefaultThreadSafety> {
+    /// The window that the surface renders to.
+    window: &'a Window<TS>,
+
+    /// The surface being driven.
+    surface: S,
+
+    /// The size of the surface, if it has been created.
+    size: Option<PhysicalSize<u32>>,
+
+    /// The size that the surface should be resized to before the next frame.
+    pending_size: Option<PhysicalSize<u32>>,
+
+    /// Whether the window is occluded.
+    occluded: bool,
+
+    /// Waits for the window to be resized.
+    resized: Waiter<'a, PhysicalSize<u32>, TS>,
+
+    /// Waits for the scale factor of the window to change.
+    scale_factor_changed: Waiter<'a, ScaleFactor, TS>,
+
+    /// Waits for the window to be occluded or revealed.
+    occlusion: Waiter<'a, bool, TS>,
+
+    /// Waits for the application to be suspended.
+    suspended: Waiter<'a, (), TS>,
+
+    /// Waits for the application to be resumed.
+    resumed: Waiter<'a, (), TS>,
+
+    /// Waits for the window to be redrawn.
+    redraw: Waiter<'a, (), TS>,
+}
+
+/// An event that the lifecycle driver reacts to.
+enum LifecycleEvent {
+    /// The window has a new size.
+    Resized(PhysicalSize<u32>),
+
+    /// The window was occluded or revealed.
+    Occluded(bool),
+
+    /// The application was suspended, and the surface was released.
+    Suspended,
+
+    /// The application was resumed.
+    Resumed,
+
+    /// The window should be redrawn.
+    Redraw,
+}
+
+impl<S, TS: ThreadSafety> fmt::Debug for SurfaceLifecycle<'_, S, TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SurfaceLifecycle")
+            .field("window", &self.window.id())
+            .field("size", &self.size)
+            .field("occluded", &self.occluded)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, S: RenderSurface<TS>, TS: ThreadSafety> SurfaceLifecycle<'a, S, TS> {
+    /// Create a new driver for a surface that renders to `window`.
+    pub fn new(window: &'a Window<TS>, surface: S) -> Self {
+        Self {
+            window,
+            surface,
+            size: None,
+            pending_size: None,
+            occluded: false,
+            resized: window.resized().wait(),
+            scale_factor_changed: window.scale_factor_changed().wait(),
+            occlusion: window.occluded().wait(),
+            suspended: window.reactor.evl_registration.suspended.wait(),
+            resumed: window.reactor.evl_registration.resumed.wait(),
+            redraw: window.redraw_requested().wait(),
+        }
+    }
+
+    /// Get the window that the surface renders to.
+    pub fn window(&self) -> &'a Window<TS> {
+        self.window
+    }
+
+    /// Get the surface.
+    pub fn surface(&self) -> &S {
+        &self.surface
+    }
+
+    /// Get the surface mutably.
+    pub fn surface_mut(&mut self) -> &mut S {
+        &mut self.surface
+    }
+
+    /// Get the surface back, releasing it if it exists.
+    pub fn into_inner(mut self) -> S {
+        if self.size.take().is_some() {
+            self.surface.suspend();
+        }
+
+        self.surface
+    }
+
+    /// Wait until the next frame should be drawn.
+    ///
+    /// This creates the surface if it doesn't exist and resizes it if the window has been resized,
+    /// then returns the size of the surface. Redraws are skipped while the window is occluded.
+    pub async fn next_frame(&mut self) -> Result<PhysicalSize<u32>, S::Error> {
+        loop {
+            match self.next_event().await {
+                LifecycleEvent::Resized(size) => {
+                    if self.size != Some(size) {
+                        self.pending_size = Some(size);
+                        self.window.request_redraw();
+                    }
+                }
+
+                LifecycleEvent::Occluded(occluded) => {
+                    self.occluded = occluded;
+                    if !occluded {
+                        self.window.request_redraw();
+                    }
+                }
+
+                LifecycleEvent::Suspended => {}
+
+                LifecycleEvent::Resumed => self.window.request_redraw(),
+
+                LifecycleEvent::Redraw => {
+                    if !self.occluded {
+                        return self.prepare().await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Present a frame to the window.
+    pub async fn present<'f>(&'f mut self, frame: S::Frame<'f>) -> Result<(), S::Error> {
+        self.surface.present(frame).await
+    }
+
+    /// Make sure that the surface exists and has the right size.
+    async fn prepare(&mut self) -> Result<PhysicalSize<u32>, S::Error> {
+        match self.size {
+            None => {
+                let size = self.window.inner_size().await;
+                self.surface.create(self.window, size).await?;
+                self.size = Some(size);
+                self.pending_size = None;
+                Ok(size)
+            }
+
+            Some(size) => match self.pending_size.take() {
+                Some(new_size) if new_size != size => {
+                    self.surface.resize(new_size).await?;
+                    self.size = Some(new_size);
+                    Ok(new_size)
+                }
+                _ => Ok(size),
+            },
+        }
+    }
+
+    /// Wait for the next event that affects the surface.
+    async fn next_event(&mut self) -> LifecycleEvent {
+        let Self {
+            surface,
+            size,
+            resized,
+            scale_factor_changed,
+            occlusion,
+            suspended,
+            resumed,
+            redraw,
+            ..
+        } = self;
+
+        let resized = async { LifecycleEvent::Resized(resized.await) };
+        let scale_factor_changed =
+            async { LifecycleEvent::Resized(scale_factor_changed.await.new_inner_size) };
+        let occlusion = async { LifecycleEvent::Occluded(occlusion.await) };
+        let suspended = async {
+            // Release the surface before the suspension is finished.
+            let _guard = suspended.hold().await;
+            if size.take().is_some() {
+                surface.suspend();
+            }
+
+            LifecycleEvent::Suspended
+        };
+        let resumed = async {
+            resumed.await;
+            LifecycleEvent::Resumed
+        };
+        let redraw = async {
+            redraw.await;
+            LifecycleEvent::Redraw
+        };
+
+        suspended
+            .or(resumed)
+            .or(resized)
+            .or(scale_factor_changed)
+            .or(occlusion)
+            .or(redraw)
+            .await
+    }
+}
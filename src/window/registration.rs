@@ -20,9 +20,24 @@ Public License along with `async-winit`. If not, see <https://www.gnu.org/licens
 
 use crate::dpi::PhysicalSize;
 use crate::handler::Handler;
+#[cfg(ios_platform)]
+use crate::platform::ios::{InterfaceOrientation, SafeAreaInsets};
 use crate::sync::ThreadSafety;
 use crate::Event;
 
+#[cfg(any(feature = "executor", ios_platform))]
+use crate::sync::__private::*;
+#[cfg(feature = "executor")]
+use crate::Task;
+#[cfg(feature = "executor")]
+use slab::Slab;
+#[cfg(feature = "executor")]
+use std::future::Future;
+#[cfg(feature = "executor")]
+use std::pin::Pin;
+#[cfg(feature = "executor")]
+use std::task::{Context, Poll};
+
 use winit::dpi::PhysicalPosition;
 use winit::event::{
     AxisId, DeviceId, ElementState, Ime, ModifiersState, MouseButton, MouseScrollDelta, Touch,
@@ -31,60 +46,85 @@ use winit::event::{
 use winit::window::Theme;
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyboardInput {
+    #[cfg_attr(feature = "serde", serde(skip, default = "dummy_device_id"))]
     pub device_id: DeviceId,
     pub input: winit::event::KeyboardInput,
     pub is_synthetic: bool,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CursorMoved {
+    #[cfg_attr(feature = "serde", serde(skip, default = "dummy_device_id"))]
     pub device_id: DeviceId,
     pub position: PhysicalPosition<f64>,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseWheel {
+    #[cfg_attr(feature = "serde", serde(skip, default = "dummy_device_id"))]
     pub device_id: DeviceId,
     pub delta: MouseScrollDelta,
     pub phase: TouchPhase,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseInput {
+    #[cfg_attr(feature = "serde", serde(skip, default = "dummy_device_id"))]
     pub device_id: DeviceId,
     pub state: ElementState,
     pub button: MouseButton,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TouchpadMagnify {
+    #[cfg_attr(feature = "serde", serde(skip, default = "dummy_device_id"))]
     pub device_id: DeviceId,
     pub delta: f64,
     pub phase: TouchPhase,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TouchpadRotate {
+    #[cfg_attr(feature = "serde", serde(skip, default = "dummy_device_id"))]
     pub device_id: DeviceId,
     pub delta: f32,
     pub phase: TouchPhase,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TouchpadPressure {
+    #[cfg_attr(feature = "serde", serde(skip, default = "dummy_device_id"))]
     pub device_id: DeviceId,
     pub pressure: f32,
     pub stage: i64,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AxisMotion {
+    #[cfg_attr(feature = "serde", serde(skip, default = "dummy_device_id"))]
     pub device_id: DeviceId,
     pub axis: AxisId,
     pub value: f64,
 }
 
+/// The device ID given to deserialized events.
+///
+/// Device IDs can't be serialized, so they only compare equal to each other.
+#[cfg(feature = "serde")]
+fn dummy_device_id() -> DeviceId {
+    // SAFETY: The ID is only compared against other IDs.
+    unsafe { DeviceId::dummy() }
+}
+
 pub struct ScaleFactor;
 
 pub struct ScaleFactorChanging<'a> {
@@ -93,6 +133,7 @@ pub struct ScaleFactorChanging<'a> {
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScaleFactorChanged {
     pub scale_factor: f64,
     pub new_inner_size: PhysicalSize<u32>,
@@ -182,6 +223,28 @@ pub(crate) struct Registration<TS: ThreadSafety> {
 
     /// `Event::Occluded`
     pub(crate) occluded: Handler<bool, TS>,
+
+    /// Interface orientation changes, detected on `Event::Resized`.
+    #[cfg(ios_platform)]
+    pub(crate) orientation_changed: Handler<InterfaceOrientation, TS>,
+
+    /// The last interface orientation that was seen.
+    #[cfg(ios_platform)]
+    orientation: TS::Mutex<InterfaceOrientation>,
+
+    /// Safe area changes, detected on `Event::Resized`.
+    #[cfg(ios_platform)]
+    pub(crate) safe_area_changed: Handler<SafeAreaInsets, TS>,
+
+    /// The last safe area insets that were seen.
+    #[cfg(ios_platform)]
+    safe_area: TS::Mutex<Option<SafeAreaInsets>>,
+
+    /// Tasks scoped to this window.
+    ///
+    /// This is `None` once the window has been closed.
+    #[cfg(feature = "executor")]
+    scope: TS::Mutex<Option<Slab<Task<()>>>>,
 }
 
 impl<TS: ThreadSafety> Registration<TS> {
@@ -211,13 +274,93 @@ impl<TS: ThreadSafety> Registration<TS> {
             mouse_input: Handler::new(),
             mouse_wheel: Handler::new(),
             occluded: Handler::new(),
+            #[cfg(ios_platform)]
+            orientation_changed: Handler::new(),
+            #[cfg(ios_platform)]
+            orientation: TS::Mutex::new(InterfaceOrientation::Unknown),
+            #[cfg(ios_platform)]
+            safe_area_changed: Handler::new(),
+            #[cfg(ios_platform)]
+            safe_area: TS::Mutex::new(None),
+            #[cfg(feature = "executor")]
+            scope: TS::Mutex::new(Some(Slab::new())),
+        }
+    }
+
+    /// Spawn a task that is scoped to this window, using the provided spawn function.
+    ///
+    /// If the window has already been closed, the future is dropped without being run.
+    #[cfg(feature = "executor")]
+    pub(crate) fn spawn_scoped<F>(
+        this: &TS::Rc<Self>,
+        future: F,
+        spawn: impl FnOnce(Scoped<F, TS>) -> Task<()>,
+    ) where
+        F: Future<Output = ()>,
+    {
+        let mut scope = this.scope.lock().unwrap();
+        let scope = match scope.as_mut() {
+            Some(scope) => scope,
+            None => return,
+        };
+
+        let entry = scope.vacant_entry();
+        let task = spawn(Scoped {
+            future,
+            registration: this.clone(),
+            key: entry.key(),
+        });
+        entry.insert(task);
+    }
+
+    /// The window has been closed; cancel everything tied to it.
+    pub(crate) fn close(&self) {
+        #[cfg(feature = "executor")]
+        {
+            let tasks = self.scope.lock().unwrap().take();
+
+            // Cancel the tasks outside of the lock. The executor drops their futures.
+            drop(tasks);
+        }
+    }
+
+    /// Fire `orientation_changed` if the interface orientation is different from last time.
+    #[cfg(ios_platform)]
+    async fn check_orientation(&self) {
+        let mut orientation = InterfaceOrientation::current();
+        let changed = {
+            let mut last = self.orientation.lock().unwrap();
+            let changed = *last != orientation;
+            *last = orientation;
+            changed
+        };
+
+        if changed {
+            self.orientation_changed.run_with(&mut orientation).await;
+        }
+    }
+
+    /// Fire `safe_area_changed` if the safe area insets are different from last time.
+    #[cfg(ios_platform)]
+    pub(crate) async fn check_safe_area(&self, window: &winit::window::Window) {
+        let mut insets = SafeAreaInsets::of(window);
+        let changed = self.safe_area.lock().unwrap().replace(insets) != Some(insets);
+
+        if changed {
+            self.safe_area_changed.run_with(&mut insets).await;
         }
     }
 
     pub(crate) async fn signal(&self, event: WindowEvent<'_>) {
         match event {
             WindowEvent::CloseRequested => self.close_requested.run_with(&mut ()).await,
-            WindowEvent::Resized(mut size) => self.resized.run_with(&mut size).await,
+            WindowEvent::Resized(mut size) => {
+                // Rotating the device resizes the window, so check the orientation here.
+                #[cfg(ios_platform)]
+                self.check_orientation().await;
+
+                self.resized.run_with(&mut size).await
+            }
             WindowEvent::Moved(mut posn) => self.moved.run_with(&mut posn).await,
             WindowEvent::AxisMotion {
                 device_id,
@@ -250,7 +393,10 @@ impl<TS: ThreadSafety> Registration<TS> {
                     })
                     .await
             }
-            WindowEvent::Destroyed => self.destroyed.run_with(&mut ()).await,
+            WindowEvent::Destroyed => {
+                self.destroyed.run_with(&mut ()).await;
+                self.close();
+            }
             WindowEvent::Focused(mut foc) => self.focused.run_with(&mut foc).await,
             WindowEvent::Ime(mut ime) => self.ime.run_with(&mut ime).await,
             WindowEvent::KeyboardInput {
@@ -360,3 +506,41 @@ impl<TS: ThreadSafety> Registration<TS> {
         }
     }
 }
+
+#[cfg(feature = "executor")]
+pin_project_lite::pin_project! {
+    /// A future spawned onto a window's scope.
+    ///
+    /// Removes its task from the scope once it completes.
+    pub(crate) struct Scoped<F, TS: ThreadSafety> {
+        #[pin]
+        future: F,
+        registration: TS::Rc<Registration<TS>>,
+        key: usize,
+    }
+}
+
+#[cfg(feature = "executor")]
+impl<F: Future<Output = ()>, TS: ThreadSafety> Future for Scoped<F, TS> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if this.future.poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+
+        // Remove our task from the scope; the task is running, so this will not cancel it.
+        let task = this
+            .registration
+            .scope
+            .lock()
+            .unwrap()
+            .as_mut()
+            .and_then(|scope| scope.try_remove(*this.key));
+        drop(task);
+
+        Poll::Ready(())
+    }
+}
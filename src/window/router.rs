@@ -0,0 +1,150 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+//! Routing of per-window state across multiple windows.
+
+use super::Window;
+use crate::event_loop::EventLoopWindowTarget;
+use crate::sync::ThreadSafety;
+use crate::DefaultThreadSafety;
+
+use std::convert::Infallible;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+
+use futures_lite::{future, FutureExt, Stream};
+use slab::Slab;
+use winit::window::WindowId;
+
+/// Runs a separate state machine for every window.
+///
+/// When a window is created, the router calls the factory with a handle to the window, and runs
+/// the returned future until it completes or the window is destroyed. Windows that already exist
+/// when the router starts running are routed as well.
+///
+/// Note that each state machine holds a handle to its window, which keeps the window alive until
+/// the state machine completes.
+///
+/// ```no_run
+/// use async_winit::event_loop::EventLoop;
+/// use async_winit::window::{Window, WindowRouter};
+/// use async_winit::ThreadUnsafe;
+///
+/// let evl = EventLoop::<ThreadUnsafe>::new();
+/// let target = evl.window_target().clone();
+///
+/// evl.block_on(async move {
+///     let router = WindowRouter::new(&target, |window: Window<ThreadUnsafe>| async move {
+///         window.close_requested().wait().await;
+///     });
+///
+///     router.run().await
+/// });
+/// ```
+pub struct WindowRouter<F, TS: ThreadSafety = DefaultThreadSafety> {
+    /// The event loop window target.
+    target: EventLoopWindowTarget<TS>,
+
+    /// Creates the state machine for a window.
+    factory: F,
+}
+
+impl<F, TS: ThreadSafety> fmt::Debug for WindowRouter<F, TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WindowRouter { .. }")
+    }
+}
+
+impl<F, Fut, TS> WindowRouter<F, TS>
+where
+    TS: ThreadSafety + 'static,
+    F: FnMut(Window<TS>) -> Fut,
+    Fut: Future<Output = ()> + 'static,
+{
+    /// Create a new window router.
+    pub fn new(target: &EventLoopWindowTarget<TS>, factory: F) -> Self {
+        Self {
+            target: target.clone(),
+            factory,
+        }
+    }
+
+    /// Run the state machines for all windows, forever.
+    pub async fn run(self) -> ! {
+        let Self {
+            target,
+            mut factory,
+        } = self;
+
+        // Listen for new windows before looking at the existing ones, so none are missed.
+        let mut created = target.window_created().wait();
+        let mut states = Slab::new();
+
+        for window in target.windows() {
+            states.insert(route(&mut factory, window));
+        }
+
+        let result = future::poll_fn(|cx| {
+            // Create state machines for new windows.
+            while let Poll::Ready(Some(id)) = Pin::new(&mut created).poll_next(cx) {
+                // The window may already be routed if it was created while we were starting up.
+                let routed = states.iter().any(|(_, (routed_id, _))| *routed_id == id);
+
+                if !routed {
+                    if let Some(window) = target.window(id) {
+                        states.insert(route(&mut factory, window));
+                    }
+                }
+            }
+
+            // Poll the state machines, and remove the ones that have finished.
+            states.retain(|_, (_, state)| state.as_mut().poll(cx).is_pending());
+
+            Poll::<Infallible>::Pending
+        })
+        .await;
+
+        match result {}
+    }
+}
+
+/// Create the state machine for a window.
+fn route<TS, F, Fut>(
+    factory: &mut F,
+    window: Window<TS>,
+) -> (WindowId, Pin<Box<dyn Future<Output = ()>>>)
+where
+    TS: ThreadSafety + 'static,
+    F: FnMut(Window<TS>) -> Fut,
+    Fut: Future<Output = ()> + 'static,
+{
+    let id = window.id();
+    let state = factory(window.clone());
+
+    // Stop running the state machine once the window is destroyed.
+    let state = Box::pin(async move {
+        state
+            .or(async {
+                window.destroyed().wait().await;
+            })
+            .await
+    });
+
+    (id, state)
+}
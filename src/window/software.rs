@@ -0,0 +1,182 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Managed `softbuffer` surfaces.
+
+use super::{RenderSurface, Window};
+use crate::sync::ThreadSafety;
+use crate::DefaultThreadSafety;
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+use softbuffer::{GraphicsContext, SoftBufferError};
+use winit::dpi::PhysicalSize;
+
+/// A `softbuffer` surface for a window.
+///
+/// This is returned by [`Window::softbuffer`]. The graphics context is created the first time a
+/// buffer is presented, and is recreated after the application has been suspended, since the
+/// underlying window surface may have been destroyed.
+///
+/// ```no_run
+/// use async_winit::window::Window;
+/// use async_winit::ThreadUnsafe;
+///
+/// # async fn draw(window: &Window<ThreadUnsafe>) {
+/// let mut surface = window.softbuffer();
+/// let mut buffer = vec![];
+///
+/// loop {
+///     window.redraw_requested().await;
+///
+///     let size = surface.size().await;
+///     buffer.resize(size.width as usize * size.height as usize, 0xAA11AA11);
+///     surface.present(&buffer).await.unwrap();
+/// }
+/// # }
+/// ```
+pub struct SoftbufferSurface<TS: ThreadSafety = DefaultThreadSafety> {
+    /// The window to draw to.
+    window: Window<TS>,
+
+    /// The graphics context, if it has been created.
+    context: Option<GraphicsContext>,
+
+    /// The number of suspensions when the context was created.
+    suspensions: usize,
+
+    /// The size of the next buffer.
+    size: Option<PhysicalSize<u32>>,
+}
+
+impl<TS: ThreadSafety> fmt::Debug for SoftbufferSurface<TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SoftbufferSurface")
+            .field("created", &self.context.is_some())
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl<TS: ThreadSafety> SoftbufferSurface<TS> {
+    pub(super) fn new(window: Window<TS>) -> Self {
+        Self {
+            window,
+            context: None,
+            suspensions: 0,
+            size: None,
+        }
+    }
+
+    /// Get the size that the next buffer should have.
+    ///
+    /// This is the current inner size of the window. It is remembered, so that the next call to
+    /// [`present`] uses the same size, even if the window is resized in the meantime.
+    ///
+    /// [`present`]: Self::present
+    pub async fn size(&mut self) -> PhysicalSize<u32> {
+        let size = self.window.inner_size().await;
+        self.size = Some(size);
+        size
+    }
+
+    /// Present a buffer of pixels to the window.
+    ///
+    /// The buffer must contain `width * height` pixels, using the size last returned by
+    /// [`size`]. Each pixel is a `u32` in the `0RGB` format. If [`size`] hasn't been called yet,
+    /// the current size of the window is used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer doesn't have the right number of pixels.
+    ///
+    /// [`size`]: Self::size
+    pub async fn present(&mut self, buffer: &[u32]) -> Result<(), SoftBufferError> {
+        let size = match self.size {
+            Some(size) => size,
+            None => self.size().await,
+        };
+
+        // The surface may have been destroyed while the app was suspended.
+        let suspensions = self.window.reactor.suspend_count();
+        if self.suspensions != suspensions {
+            self.context = None;
+            self.suspensions = suspensions;
+        }
+
+        self.context()?
+            .set_buffer(buffer, size.width as u16, size.height as u16);
+        Ok(())
+    }
+
+    /// Get the graphics context, creating it if it doesn't exist.
+    fn context(&mut self) -> Result<&mut GraphicsContext, SoftBufferError> {
+        match &mut self.context {
+            Some(context) => Ok(context),
+            context @ None => {
+                // SAFETY: The context is dropped before the window, which it holds a handle to.
+                let new = unsafe { GraphicsContext::new(&self.window, &self.window) }?;
+                Ok(context.insert(new))
+            }
+        }
+    }
+}
+
+impl<TS: ThreadSafety> RenderSurface<TS> for SoftbufferSurface<TS> {
+    type Frame<'a> = &'a [u32] where Self: 'a;
+    type Error = SoftBufferError;
+
+    /// Create the graphics context.
+    ///
+    /// The context is always created for the window that the surface was created for.
+    fn create<'a>(
+        &'a mut self,
+        _window: &'a Window<TS>,
+        size: PhysicalSize<u32>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + 'a>> {
+        Box::pin(async move {
+            self.size = Some(size);
+            self.suspensions = self.window.reactor.suspend_count();
+            self.context()?;
+            Ok(())
+        })
+    }
+
+    /// Use `size` for the next buffer.
+    fn resize(
+        &mut self,
+        size: PhysicalSize<u32>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + '_>> {
+        self.size = Some(size);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn suspend(&mut self) {
+        self.context = None;
+    }
+
+    /// Present a buffer of pixels, which must have the size passed to `create` or `resize`.
+    fn present<'a>(
+        &'a mut self,
+        frame: &'a [u32],
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + 'a>> {
+        Box::pin(SoftbufferSurface::present(self, frame))
+    }
+}
@@ -0,0 +1,547 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! An adapter for layering `egui` user interfaces over a window.
+
+use super::{Frame, Window};
+use crate::event::{
+    CursorMoved, ElementState, KeyboardInput, ModifiersState, MouseButton, MouseInput,
+    MouseScrollDelta, MouseWheel, VirtualKeyCode,
+};
+use crate::handler::Handler;
+use crate::sync::ThreadSafety;
+use crate::DefaultThreadSafety;
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use egui::{Context, FullOutput, Modifiers, PointerButton, Pos2, RawInput, Rect, Vec2};
+use instant::Instant;
+use winit::dpi::PhysicalSize;
+use winit::window::CursorIcon;
+
+/// The number of points scrolled per line of a mouse wheel.
+const POINTS_PER_SCROLL_LINE: f32 = 50.0;
+
+/// Feeds the input of a window into an `egui` context.
+///
+/// The adapter registers direct listeners on the input handlers of the window, and translates the
+/// events into an [`egui::RawInput`]. Events that `egui` wants, such as clicks over a panel or key
+/// presses while a text field is focused, are consumed, so they don't reach the other listeners of
+/// the window. The listeners stay registered until the adapter is dropped.
+///
+/// Each frame is bracketed by [`begin_frame`] and [`end_frame`], which take the [`Frame`]s emitted
+/// by [`Window::animation_frames`] and [`FramePacer`]. The rendering of the output is left to the
+/// renderer of the application.
+///
+/// ```no_run
+/// use async_winit::window::{EguiAdapter, Window};
+/// use async_winit::ThreadUnsafe;
+/// use futures_lite::StreamExt;
+///
+/// # async fn ui(window: &Window<ThreadUnsafe>) {
+/// let adapter = EguiAdapter::new(window, egui::Context::default()).await;
+/// let mut frames = window.animation_frames();
+///
+/// while let Some(frame) = frames.next().await {
+///     let output = adapter
+///         .run(frame, |ctx| {
+///             egui::CentralPanel::default().show(ctx, |ui| {
+///                 ui.label("Hello, world!");
+///             });
+///         })
+///         .await;
+///
+///     let primitives = adapter.context().tessellate(output.shapes);
+///     // Render the primitives and apply `output.textures_delta`...
+/// }
+/// # }
+/// ```
+///
+/// [`begin_frame`]: Self::begin_frame
+/// [`end_frame`]: Self::end_frame
+/// [`FramePacer`]: super::FramePacer
+pub struct EguiAdapter<TS: ThreadSafety = DefaultThreadSafety> {
+    /// The window that input is taken from.
+    window: Window<TS>,
+
+    /// The state shared with the listeners.
+    shared: Arc<Shared>,
+
+    /// The time at which the adapter was created.
+    start: Instant,
+
+    /// The cursor icon that was last set on the window.
+    cursor: Mutex<Option<egui::CursorIcon>>,
+}
+
+/// The state shared between the adapter and its listeners.
+struct Shared {
+    /// The `egui` context.
+    context: Context,
+
+    /// The input collected since the last frame.
+    input: Mutex<InputState>,
+}
+
+/// The input collected for the next frame.
+struct InputState {
+    /// The raw input to pass to `egui`.
+    raw: RawInput,
+
+    /// The position of the pointer, in points.
+    pointer: Option<Pos2>,
+
+    /// The number of physical pixels per point.
+    pixels_per_point: f32,
+
+    /// The inner size of the window.
+    size: PhysicalSize<u32>,
+}
+
+impl<TS: ThreadSafety> fmt::Debug for EguiAdapter<TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EguiAdapter")
+            .field("window", &self.window.id())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<TS: ThreadSafety> EguiAdapter<TS> {
+    /// Create a new adapter that feeds the input of `window` into `context`.
+    pub async fn new(window: &Window<TS>, context: Context) -> Self {
+        let shared = Arc::new(Shared {
+            context,
+            input: Mutex::new(InputState {
+                raw: RawInput {
+                    focused: window.is_focused().await,
+                    ..Default::default()
+                },
+                pointer: None,
+                pixels_per_point: window.scale_factor() as f32,
+                size: window.inner_size().await,
+            }),
+        });
+
+        intercept(window.resized(), &shared, |shared, size| {
+            shared.input.lock().unwrap().size = *size;
+            false
+        });
+
+        let weak = Arc::downgrade(&shared);
+        window.scale_factor_changed().wait_direct(move |event| {
+            if let Some(shared) = weak.upgrade() {
+                let mut input = shared.input.lock().unwrap();
+                input.pixels_per_point = event.scale_factor as f32;
+                input.size = *event.new_inner_size;
+            }
+
+            false
+        });
+
+        intercept(window.focused(), &shared, |shared, focused| {
+            shared.input.lock().unwrap().raw.focused = *focused;
+            false
+        });
+
+        intercept(window.modifiers_changed(), &shared, |shared, modifiers| {
+            shared.input.lock().unwrap().raw.modifiers = egui_modifiers(*modifiers);
+            false
+        });
+
+        intercept(window.cursor_moved(), &shared, |shared, event| {
+            shared.cursor_moved(event);
+            shared.context.is_using_pointer()
+        });
+
+        intercept(window.cursor_left(), &shared, |shared, _| {
+            let mut input = shared.input.lock().unwrap();
+            input.pointer = None;
+            input.raw.events.push(egui::Event::PointerGone);
+            false
+        });
+
+        intercept(window.mouse_input(), &shared, |shared, event| {
+            shared.mouse_input(event);
+            shared.context.wants_pointer_input()
+        });
+
+        intercept(window.mouse_wheel(), &shared, |shared, event| {
+            shared.mouse_wheel(event);
+            shared.context.wants_pointer_input()
+        });
+
+        intercept(window.keyboard_input(), &shared, |shared, event| {
+            shared.keyboard_input(event);
+            shared.context.wants_keyboard_input()
+        });
+
+        intercept(window.received_character(), &shared, |shared, ch| {
+            shared.received_character(*ch);
+            shared.context.wants_keyboard_input()
+        });
+
+        Self {
+            window: window.clone(),
+            shared,
+            start: Instant::now(),
+            cursor: Mutex::new(None),
+        }
+    }
+
+    /// Get the window that input is taken from.
+    pub fn window(&self) -> &Window<TS> {
+        &self.window
+    }
+
+    /// Get the `egui` context.
+    pub fn context(&self) -> &Context {
+        &self.shared.context
+    }
+
+    /// Take the input collected since the last frame.
+    pub fn take_input(&self) -> RawInput {
+        let mut input = self.shared.input.lock().unwrap();
+        let points = input.size.to_logical::<f32>(input.pixels_per_point.into());
+
+        input.raw.screen_rect = Some(Rect::from_min_size(
+            Pos2::ZERO,
+            Vec2::new(points.width, points.height),
+        ));
+        input.raw.pixels_per_point = Some(input.pixels_per_point);
+        input.raw.time = Some(self.start.elapsed().as_secs_f64());
+        input.raw.take()
+    }
+
+    /// Begin an `egui` frame.
+    ///
+    /// This passes the input collected since the last frame to the context.
+    pub fn begin_frame(&self, frame: Frame) {
+        let mut raw = self.take_input();
+        if !frame.delta.is_zero() {
+            raw.predicted_dt = frame.delta.as_secs_f32();
+        }
+
+        self.shared.context.begin_frame(raw);
+    }
+
+    /// End the `egui` frame and get its output.
+    ///
+    /// This applies the cursor icon requested by `egui` to the window, and requests a redraw if
+    /// `egui` needs to be repainted immediately.
+    pub async fn end_frame(&self) -> FullOutput {
+        let output = self.shared.context.end_frame();
+
+        let icon = output.platform_output.cursor_icon;
+        let changed = self.cursor.lock().unwrap().replace(icon) != Some(icon);
+        if changed {
+            match winit_cursor(icon) {
+                Some(icon) => {
+                    self.window.set_cursor_icon(icon).await;
+                    self.window.set_cursor_visible(true).await;
+                }
+                None => self.window.set_cursor_visible(false).await,
+            }
+        }
+
+        if output.repaint_after.is_zero() {
+            self.window.request_redraw();
+        }
+
+        output
+    }
+
+    /// Run an `egui` frame.
+    ///
+    /// This calls [`begin_frame`](Self::begin_frame), runs `ui`, and then calls
+    /// [`end_frame`](Self::end_frame).
+    pub async fn run(&self, frame: Frame, ui: impl FnOnce(&Context)) -> FullOutput {
+        self.begin_frame(frame);
+        ui(&self.shared.context);
+        self.end_frame().await
+    }
+}
+
+impl Shared {
+    /// Handle the cursor moving.
+    fn cursor_moved(&self, event: &CursorMoved) {
+        let mut input = self.input.lock().unwrap();
+        let ppp = input.pixels_per_point;
+        let pos = Pos2::new(event.position.x as f32 / ppp, event.position.y as f32 / ppp);
+
+        input.pointer = Some(pos);
+        input.raw.events.push(egui::Event::PointerMoved(pos));
+    }
+
+    /// Handle a mouse button being pressed or released.
+    fn mouse_input(&self, event: &MouseInput) {
+        let mut input = self.input.lock().unwrap();
+        let (pos, button) = match (input.pointer, egui_button(event.button)) {
+            (Some(pos), Some(button)) => (pos, button),
+            _ => return,
+        };
+
+        let modifiers = input.raw.modifiers;
+        input.raw.events.push(egui::Event::PointerButton {
+            pos,
+            button,
+            pressed: event.state == ElementState::Pressed,
+            modifiers,
+        });
+    }
+
+    /// Handle the mouse wheel being scrolled.
+    fn mouse_wheel(&self, event: &MouseWheel) {
+        let mut input = self.input.lock().unwrap();
+        let delta = match event.delta {
+            MouseScrollDelta::LineDelta(x, y) => Vec2::new(x, y) * POINTS_PER_SCROLL_LINE,
+            MouseScrollDelta::PixelDelta(delta) => {
+                Vec2::new(delta.x as f32, delta.y as f32) / input.pixels_per_point
+            }
+        };
+
+        // Scrolling while holding the command key zooms instead.
+        let event = if input.raw.modifiers.command {
+            egui::Event::Zoom((delta.y / 200.0).exp())
+        } else {
+            egui::Event::Scroll(delta)
+        };
+        input.raw.events.push(event);
+    }
+
+    /// Handle a key being pressed or released.
+    fn keyboard_input(&self, event: &KeyboardInput) {
+        let mut input = self.input.lock().unwrap();
+        let (keycode, pressed) = match event.input.virtual_keycode {
+            Some(keycode) => (keycode, event.input.state == ElementState::Pressed),
+            None => return,
+        };
+
+        let modifiers = input.raw.modifiers;
+        if pressed && modifiers.command {
+            match keycode {
+                VirtualKeyCode::C => input.raw.events.push(egui::Event::Copy),
+                VirtualKeyCode::X => input.raw.events.push(egui::Event::Cut),
+                _ => {}
+            }
+        }
+
+        if let Some(key) = egui_key(keycode) {
+            input.raw.events.push(egui::Event::Key {
+                key,
+                pressed,
+                repeat: false,
+                modifiers,
+            });
+        }
+    }
+
+    /// Handle a character being typed.
+    fn received_character(&self, ch: char) {
+        let mut input = self.input.lock().unwrap();
+
+        // Characters typed alongside shortcuts are handled as keys.
+        let modifiers = input.raw.modifiers;
+        if is_printable(ch) && !modifiers.ctrl && !modifiers.mac_cmd {
+            input.raw.events.push(egui::Event::Text(ch.to_string()));
+        }
+    }
+}
+
+/// Register a direct listener that forwards events to the shared state until it is dropped.
+///
+/// The listener consumes the event if `f` returns `true`.
+fn intercept<T: Clone + Send + 'static, TS: ThreadSafety>(
+    handler: &Handler<T, TS>,
+    shared: &Arc<Shared>,
+    f: impl Fn(&Shared, &T) -> bool + Send + 'static,
+) {
+    let shared = Arc::downgrade(shared);
+    handler.wait_direct(move |event| match shared.upgrade() {
+        Some(shared) => f(&shared, event),
+        None => false,
+    });
+}
+
+/// Whether a character should be typed into text fields.
+fn is_printable(ch: char) -> bool {
+    // Characters in the private use area are sent for function keys on macOS.
+    let private_use = ('\u{e000}'..='\u{f8ff}').contains(&ch)
+        || ('\u{f0000}'..='\u{ffffd}').contains(&ch)
+        || ('\u{100000}'..='\u{10fffd}').contains(&ch);
+
+    !private_use && !ch.is_ascii_control()
+}
+
+/// Convert the modifiers held down into `egui` modifiers.
+fn egui_modifiers(modifiers: ModifiersState) -> Modifiers {
+    let mac = cfg!(any(target_os = "macos", target_os = "ios"));
+    let command = if mac {
+        modifiers.logo()
+    } else {
+        modifiers.ctrl()
+    };
+
+    Modifiers {
+        alt: modifiers.alt(),
+        ctrl: modifiers.ctrl(),
+        shift: modifiers.shift(),
+        mac_cmd: mac && modifiers.logo(),
+        command,
+    }
+}
+
+/// Convert a mouse button into an `egui` pointer button.
+fn egui_button(button: MouseButton) -> Option<PointerButton> {
+    Some(match button {
+        MouseButton::Left => PointerButton::Primary,
+        MouseButton::Right => PointerButton::Secondary,
+        MouseButton::Middle => PointerButton::Middle,
+        MouseButton::Other(1) => PointerButton::Extra1,
+        MouseButton::Other(2) => PointerButton::Extra2,
+        MouseButton::Other(_) => return None,
+    })
+}
+
+/// Convert a virtual key code into an `egui` key.
+fn egui_key(keycode: VirtualKeyCode) -> Option<egui::Key> {
+    use egui::Key;
+    use VirtualKeyCode as K;
+
+    Some(match keycode {
+        K::Down => Key::ArrowDown,
+        K::Left => Key::ArrowLeft,
+        K::Right => Key::ArrowRight,
+        K::Up => Key::ArrowUp,
+        K::Escape => Key::Escape,
+        K::Tab => Key::Tab,
+        K::Back => Key::Backspace,
+        K::Return | K::NumpadEnter => Key::Enter,
+        K::Space => Key::Space,
+        K::Insert => Key::Insert,
+        K::Delete => Key::Delete,
+        K::Home => Key::Home,
+        K::End => Key::End,
+        K::PageUp => Key::PageUp,
+        K::PageDown => Key::PageDown,
+        K::Minus | K::NumpadSubtract => Key::Minus,
+        K::Equals | K::Plus | K::NumpadAdd => Key::PlusEquals,
+        K::Key0 | K::Numpad0 => Key::Num0,
+        K::Key1 | K::Numpad1 => Key::Num1,
+        K::Key2 | K::Numpad2 => Key::Num2,
+        K::Key3 | K::Numpad3 => Key::Num3,
+        K::Key4 | K::Numpad4 => Key::Num4,
+        K::Key5 | K::Numpad5 => Key::Num5,
+        K::Key6 | K::Numpad6 => Key::Num6,
+        K::Key7 | K::Numpad7 => Key::Num7,
+        K::Key8 | K::Numpad8 => Key::Num8,
+        K::Key9 | K::Numpad9 => Key::Num9,
+        K::A => Key::A,
+        K::B => Key::B,
+        K::C => Key::C,
+        K::D => Key::D,
+        K::E => Key::E,
+        K::F => Key::F,
+        K::G => Key::G,
+        K::H => Key::H,
+        K::I => Key::I,
+        K::J => Key::J,
+        K::K => Key::K,
+        K::L => Key::L,
+        K::M => Key::M,
+        K::N => Key::N,
+        K::O => Key::O,
+        K::P => Key::P,
+        K::Q => Key::Q,
+        K::R => Key::R,
+        K::S => Key::S,
+        K::T => Key::T,
+        K::U => Key::U,
+        K::V => Key::V,
+        K::W => Key::W,
+        K::X => Key::X,
+        K::Y => Key::Y,
+        K::Z => Key::Z,
+        K::F1 => Key::F1,
+        K::F2 => Key::F2,
+        K::F3 => Key::F3,
+        K::F4 => Key::F4,
+        K::F5 => Key::F5,
+        K::F6 => Key::F6,
+        K::F7 => Key::F7,
+        K::F8 => Key::F8,
+        K::F9 => Key::F9,
+        K::F10 => Key::F10,
+        K::F11 => Key::F11,
+        K::F12 => Key::F12,
+        K::F13 => Key::F13,
+        K::F14 => Key::F14,
+        K::F15 => Key::F15,
+        K::F16 => Key::F16,
+        K::F17 => Key::F17,
+        K::F18 => Key::F18,
+        K::F19 => Key::F19,
+        K::F20 => Key::F20,
+        _ => return None,
+    })
+}
+
+/// Convert an `egui` cursor icon into a window cursor icon.
+///
+/// Returns `None` if the cursor should be hidden.
+fn winit_cursor(icon: egui::CursorIcon) -> Option<CursorIcon> {
+    use egui::CursorIcon as C;
+
+    Some(match icon {
+        C::None => return None,
+        C::Default => CursorIcon::Default,
+        C::ContextMenu => CursorIcon::ContextMenu,
+        C::Help => CursorIcon::Help,
+        C::PointingHand => CursorIcon::Hand,
+        C::Progress => CursorIcon::Progress,
+        C::Wait => CursorIcon::Wait,
+        C::Cell => CursorIcon::Cell,
+        C::Crosshair => CursorIcon::Crosshair,
+        C::Text => CursorIcon::Text,
+        C::VerticalText => CursorIcon::VerticalText,
+        C::Alias => CursorIcon::Alias,
+        C::Copy => CursorIcon::Copy,
+        C::Move => CursorIcon::Move,
+        C::NoDrop => CursorIcon::NoDrop,
+        C::NotAllowed => CursorIcon::NotAllowed,
+        C::Grab => CursorIcon::Grab,
+        C::Grabbing => CursorIcon::Grabbing,
+        C::AllScroll => CursorIcon::AllScroll,
+        C::ResizeHorizontal => CursorIcon::EwResize,
+        C::ResizeNeSw => CursorIcon::NeswResize,
+        C::ResizeNwSe => CursorIcon::NwseResize,
+        C::ResizeVertical => CursorIcon::NsResize,
+        C::ResizeEast => CursorIcon::EResize,
+        C::ResizeSouthEast => CursorIcon::SeResize,
+        C::ResizeSouth => CursorIcon::SResize,
+        C::ResizeSouthWest => CursorIcon::SwResize,
+        C::ResizeWest => CursorIcon::WResize,
+        C::ResizeNorthWest => CursorIcon::NwResize,
+        C::ResizeNorth => CursorIcon::NResize,
+        C::ResizeNorthEast => CursorIcon::NeResize,
+        C::ResizeColumn => CursorIcon::ColResize,
+        C::ResizeRow => CursorIcon::RowResize,
+        C::ZoomIn => CursorIcon::ZoomIn,
+        C::ZoomOut => CursorIcon::ZoomOut,
+    })
+}
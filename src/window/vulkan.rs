@@ -0,0 +1,283 @@
+/*
+
+`async-winit` is free software: you can redistribute it and/or modify it under the terms of one of
+the following licenses:
+
+* GNU Lesser General Public License as published by the Free Software Foundation, either
+  version 3 of the License, or (at your option) any later version.
+* Mozilla Public License as published by the Mozilla Foundation, version 2.
+
+`async-winit` is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General
+Public License and the Patron License for more details.
+
+You should have received a copy of the GNU Lesser General Public License and the Mozilla
+Public License along with `async-winit`. If not, see <https://www.gnu.org/licenses/>.
+
+*/
+
+//! Helpers for rendering to windows with Vulkan through `ash`.
+
+use super::Window;
+use crate::oneoff::oneoff;
+use crate::reactor::EventLoopOp;
+use crate::sync::ThreadSafety;
+use crate::DefaultThreadSafety;
+
+use std::fmt;
+use std::os::raw::c_char;
+
+use ash::extensions::khr;
+use ash::prelude::VkResult;
+use ash::{vk, Entry, Instance};
+use raw_window_handle::{
+    HasRawDisplayHandle, HasRawWindowHandle, RawDisplayHandle, RawWindowHandle,
+};
+
+/// A Vulkan surface for a window.
+///
+/// This is created by [`Window::create_vulkan_surface`]. It keeps the window alive for as long as
+/// the surface exists, and destroys the surface when it is dropped.
+pub struct VulkanSurface<TS: ThreadSafety = DefaultThreadSafety> {
+    /// The surface.
+    surface: vk::SurfaceKHR,
+
+    /// The loader used to destroy the surface.
+    loader: khr::Surface,
+
+    /// The window that the surface renders to.
+    window: Window<TS>,
+}
+
+impl<TS: ThreadSafety> fmt::Debug for VulkanSurface<TS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VulkanSurface")
+            .field("surface", &self.surface)
+            .field("window", &self.window.id())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<TS: ThreadSafety> Drop for VulkanSurface<TS> {
+    fn drop(&mut self) {
+        // SAFETY: The caller of `create_vulkan_surface` promised that the instance outlives us.
+        unsafe { self.loader.destroy_surface(self.surface, None) }
+    }
+}
+
+impl<TS: ThreadSafety> VulkanSurface<TS> {
+    /// Get the window that this surface renders to.
+    pub fn window(&self) -> &Window<TS> {
+        &self.window
+    }
+
+    /// Get the underlying surface handle.
+    pub fn surface(&self) -> vk::SurfaceKHR {
+        self.surface
+    }
+
+    /// Get the `VK_KHR_surface` functions for the instance that created this surface.
+    pub fn loader(&self) -> &khr::Surface {
+        &self.loader
+    }
+}
+
+impl<TS: ThreadSafety> Window<TS> {
+    /// Get the instance extensions needed to create a Vulkan surface for this window.
+    ///
+    /// These should be enabled on the instance passed to [`Window::create_vulkan_surface`].
+    /// Returns `ERROR_EXTENSION_NOT_PRESENT` if Vulkan surfaces aren't supported on this platform.
+    pub fn required_vulkan_extensions(&self) -> VkResult<&'static [*const c_char]> {
+        let extensions: &'static [*const c_char] = match self.inner.raw_display_handle() {
+            RawDisplayHandle::Windows(_) => {
+                const WINDOWS: [*const c_char; 2] = [
+                    khr::Surface::name().as_ptr(),
+                    khr::Win32Surface::name().as_ptr(),
+                ];
+                &WINDOWS
+            }
+
+            RawDisplayHandle::Xlib(_) => {
+                const XLIB: [*const c_char; 2] = [
+                    khr::Surface::name().as_ptr(),
+                    khr::XlibSurface::name().as_ptr(),
+                ];
+                &XLIB
+            }
+
+            RawDisplayHandle::Xcb(_) => {
+                const XCB: [*const c_char; 2] = [
+                    khr::Surface::name().as_ptr(),
+                    khr::XcbSurface::name().as_ptr(),
+                ];
+                &XCB
+            }
+
+            RawDisplayHandle::Wayland(_) => {
+                const WAYLAND: [*const c_char; 2] = [
+                    khr::Surface::name().as_ptr(),
+                    khr::WaylandSurface::name().as_ptr(),
+                ];
+                &WAYLAND
+            }
+
+            RawDisplayHandle::Android(_) => {
+                const ANDROID: [*const c_char; 2] = [
+                    khr::Surface::name().as_ptr(),
+                    khr::AndroidSurface::name().as_ptr(),
+                ];
+                &ANDROID
+            }
+
+            #[cfg(macos_platform)]
+            RawDisplayHandle::AppKit(_) => {
+                const METAL: [*const c_char; 2] = [
+                    khr::Surface::name().as_ptr(),
+                    ash::extensions::ext::MetalSurface::name().as_ptr(),
+                ];
+                &METAL
+            }
+
+            _ => return Err(vk::Result::ERROR_EXTENSION_NOT_PRESENT),
+        };
+
+        Ok(extensions)
+    }
+
+    /// Create a Vulkan surface for this window.
+    ///
+    /// The extensions returned by [`Window::required_vulkan_extensions`] must be enabled on
+    /// `instance`. On macOS, this replaces the layer of the window's view with a `CAMetalLayer`,
+    /// which has to happen on the main thread. If this is called from another thread, the surface
+    /// is created on the event loop thread instead, and this fails with
+    /// `ERROR_INITIALIZATION_FAILED` if the event loop has already exited.
+    ///
+    /// # Safety
+    ///
+    /// `instance` must have been created from `entry`, and must outlive both this future and the
+    /// returned surface.
+    pub async unsafe fn create_vulkan_surface(
+        &self,
+        entry: &Entry,
+        instance: &Instance,
+    ) -> VkResult<VulkanSurface<TS>> {
+        let surface = if self.reactor.on_event_loop_thread() {
+            // SAFETY: `VulkanSurface` keeps the window alive for as long as the surface exists.
+            unsafe { create_surface(entry, instance, &self.inner) }?
+        } else {
+            let (tx, rx) = oneoff();
+            self.reactor
+                .push_event_loop_op(EventLoopOp::CreateVulkanSurface {
+                    window: self.inner.window.clone(),
+                    entry: entry.clone(),
+                    instance: instance.clone(),
+                    waker: tx,
+                })
+                .await
+                .map_err(|_| vk::Result::ERROR_INITIALIZATION_FAILED)?;
+
+            rx.recv().await?
+        };
+
+        Ok(VulkanSurface {
+            surface,
+            loader: khr::Surface::new(entry, instance),
+            window: self.clone(),
+        })
+    }
+}
+
+/// Create a surface for a window with the platform's surface extension.
+///
+/// # Safety
+///
+/// The window must outlive the surface.
+pub(crate) unsafe fn create_surface(
+    entry: &Entry,
+    instance: &Instance,
+    window: &winit::window::Window,
+) -> VkResult<vk::SurfaceKHR> {
+    match (window.raw_display_handle(), window.raw_window_handle()) {
+        (RawDisplayHandle::Windows(_), RawWindowHandle::Win32(window)) => {
+            let info = vk::Win32SurfaceCreateInfoKHR::builder()
+                .hinstance(window.hinstance)
+                .hwnd(window.hwnd);
+            khr::Win32Surface::new(entry, instance).create_win32_surface(&info, None)
+        }
+
+        (RawDisplayHandle::Xlib(display), RawWindowHandle::Xlib(window)) => {
+            let info = vk::XlibSurfaceCreateInfoKHR::builder()
+                .dpy(display.display.cast())
+                .window(window.window);
+            khr::XlibSurface::new(entry, instance).create_xlib_surface(&info, None)
+        }
+
+        (RawDisplayHandle::Xcb(display), RawWindowHandle::Xcb(window)) => {
+            let info = vk::XcbSurfaceCreateInfoKHR::builder()
+                .connection(display.connection)
+                .window(window.window);
+            khr::XcbSurface::new(entry, instance).create_xcb_surface(&info, None)
+        }
+
+        (RawDisplayHandle::Wayland(display), RawWindowHandle::Wayland(window)) => {
+            let info = vk::WaylandSurfaceCreateInfoKHR::builder()
+                .display(display.display)
+                .surface(window.surface);
+            khr::WaylandSurface::new(entry, instance).create_wayland_surface(&info, None)
+        }
+
+        (RawDisplayHandle::Android(_), RawWindowHandle::AndroidNdk(window)) => {
+            let info = vk::AndroidSurfaceCreateInfoKHR::builder().window(window.a_native_window);
+            khr::AndroidSurface::new(entry, instance).create_android_surface(&info, None)
+        }
+
+        #[cfg(macos_platform)]
+        (RawDisplayHandle::AppKit(_), RawWindowHandle::AppKit(window)) => {
+            let layer = metal_layer(window.ns_view.cast());
+            let info = vk::MetalSurfaceCreateInfoEXT::builder().layer(layer.cast());
+            ash::extensions::ext::MetalSurface::new(entry, instance)
+                .create_metal_surface(&info, None)
+        }
+
+        _ => Err(vk::Result::ERROR_EXTENSION_NOT_PRESENT),
+    }
+}
+
+/// Get the `CAMetalLayer` backing a view, replacing its layer with one if necessary.
+///
+/// # Safety
+///
+/// `view` must be a valid `NSView`, and this must be called on the main thread.
+#[cfg(macos_platform)]
+unsafe fn metal_layer(view: *mut objc2::runtime::Object) -> *mut objc2::runtime::Object {
+    use objc2::runtime::{Bool, Object};
+    use objc2::{class, msg_send};
+
+    // Make sure the class of the layer is available.
+    #[link(name = "QuartzCore", kind = "framework")]
+    extern "C" {}
+
+    let layer: *mut Object = msg_send![view, layer];
+    if !layer.is_null() {
+        let is_metal: Bool = msg_send![layer, isKindOfClass: class!(CAMetalLayer)];
+        if is_metal.as_bool() {
+            return layer;
+        }
+    }
+
+    let layer: *mut Object = msg_send![class!(CAMetalLayer), new];
+    let _: () = msg_send![view, setLayer: layer];
+    let _: () = msg_send![view, setWantsLayer: Bool::YES];
+
+    // The view retains the layer, so we can give up our reference.
+    let _: () = msg_send![layer, release];
+
+    // Render at the resolution of the screen the window is on.
+    let window: *mut Object = msg_send![view, window];
+    if !window.is_null() {
+        let scale: f64 = msg_send![window, backingScaleFactor];
+        let _: () = msg_send![layer, setContentsScale: scale];
+    }
+
+    layer
+}